@@ -10,12 +10,31 @@ use std::sync::Arc;
 
 use crossbeam_channel::Receiver;
 
+use crate::app_detections::{EventIndexFilter, IocLoadResult, SigmaLoadResult};
+use crate::core::alert::{AlertDispatcher, AlertMonitor, AlertRule, FiredAlert};
+use crate::core::attack::AttackMap;
 use crate::core::channel_enumerator;
+use crate::core::correlation::{CorrelationEngine, CorrelationRule};
+use crate::core::dedup::{DuplicateKey, Run};
 use crate::core::event_reader::ReaderMessage;
 use crate::core::event_record::EventRecord;
 use crate::core::filter::FilterState;
-use crate::core::filter_preset::FilterPreset;
-use crate::ui::stats_panel::EventStats;
+use crate::core::filter_group::FilterGroup;
+use crate::core::filter_preset::{CompoundPreset, FilterPreset};
+use crate::core::ioc::IocScanner;
+use crate::core::sigma::SigmaRule;
+use crate::core::stats::{EventStats, Granularity};
+use crate::ui::activity_panel::ActivityView;
+use crate::ui::anomaly_panel::AnomalyView;
+use crate::ui::boot_sessions_panel::BootSessionView;
+use crate::ui::brute_force_panel::BruteForceView;
+use crate::ui::crash_panel::CrashView;
+use crate::ui::crosstab_panel::CrosstabView;
+use crate::ui::field_explorer::FieldExplorerView;
+use crate::ui::logon_sessions_panel::LogonSessionView;
+use crate::ui::process_tree_panel::ProcessTreeView;
+use crate::ui::services_panel::ServicesView;
+use crate::ui::template_table::TemplateView;
 use crate::util::constants;
 
 // ── Enums ───────────────────────────────────────────────────────────────
@@ -73,6 +92,19 @@ impl Default for ColumnVisibility {
     }
 }
 
+/// Folding of consecutive duplicate rows in the event table.
+#[derive(Debug, Default)]
+pub struct DuplicateCollapse {
+    /// Fold runs of duplicates into one row. Persisted.
+    pub enabled: bool,
+    /// Fields compared between neighbours. Persisted.
+    pub key: DuplicateKey,
+    /// Runs over `filtered_indices`; valid unless `dirty`.
+    pub runs: Vec<Run>,
+    /// Set when `filtered_indices` or `key` changed.
+    pub dirty: bool,
+}
+
 // ── App state ───────────────────────────────────────────────────────────
 
 /// Central application state for EventSleuth.
@@ -92,7 +124,12 @@ pub struct EventSleuthApp {
 
     // ── Event storage ───────────────────────────────────────────
     /// Master list of all loaded events (unsorted, unfiltered).
-    pub all_events: Vec<EventRecord>,
+    ///
+    /// Shared so background work (rule loading, field profiling) can read
+    /// a snapshot without copying; appends go through `Arc::make_mut`
+    /// (copying only while a snapshot is still held), and a reload
+    /// replaces the list instead of clearing it.
+    pub all_events: Arc<Vec<EventRecord>>,
     /// Indices into `all_events` that match the current filter, in
     /// display order (sorted).
     pub filtered_indices: Vec<usize>,
@@ -102,8 +139,14 @@ pub struct EventSleuthApp {
     pub needs_refilter: bool,
 
     // ── Filter ──────────────────────────────────────────────────
-    /// All active filter criteria.
+    /// Primary filter criteria (always an include group).
     pub filter: FilterState,
+    /// Extra criteria groups ORed with (include) or subtracted from
+    /// (exclude) the primary filter. See [`crate::core::filter_group`].
+    pub filter_groups: Vec<FilterGroup>,
+    /// Which group the filter panel is editing: `None` = the primary
+    /// filter, `Some(i)` = `filter_groups[i]`.
+    pub editing_group: Option<usize>,
 
     // ── Sorting ─────────────────────────────────────────────────
     /// Current sort column.
@@ -158,8 +201,11 @@ pub struct EventSleuthApp {
     pub debounce_timer: Option<std::time::Instant>,
 
     // ── Filter presets ──────────────────────────────────────────
+    /// Undo/redo history of applied filters (kept for the whole session,
+    /// across reloads).
+    pub filter_history: crate::core::filter_history::FilterHistory,
     /// Saved named filter presets (persisted via eframe storage).
-    pub filter_presets: Vec<FilterPreset>,
+    pub filter_presets: Vec<CompoundPreset>,
     /// Whether the "save preset" dialog is open.
     pub show_save_preset: bool,
     /// Text input for the new preset name.
@@ -184,6 +230,10 @@ pub struct EventSleuthApp {
     pub stats_cache: EventStats,
     /// Flag: recompute stats on the next frame when the panel is visible.
     pub stats_dirty: bool,
+    /// Bucket width of the statistics histogram.
+    pub stats_granularity: Granularity,
+    /// Bucket the histogram and heatmap in UTC instead of local time.
+    pub stats_utc: bool,
 
     // ── Regex search ────────────────────────────────────────────
     // (The `use_regex` flag lives in FilterState; no extra app fields.)
@@ -198,6 +248,14 @@ pub struct EventSleuthApp {
     /// across frames, rather than being overwritten on every frame with
     /// the clamped integer value (Bug fix: ephemeral-binding reset bug).
     pub max_events_input: String,
+    /// Half-width in minutes of the "same value within ±N minutes" pivot.
+    pub pivot_window_minutes: i64,
+
+    // ── Query-by-example ────────────────────────────────────────
+    /// Open filter suggestion, if the "Suggested Filter" dialog is shown.
+    pub filter_suggestion: Option<crate::core::query_by_example::FilterSuggestion>,
+    /// Preset name typed in the suggestion dialog.
+    pub suggestion_preset_name: String,
 
     // ── Bookmarked / pinned events ──────────────────────────────
     /// Set of bookmarked event indices (into `all_events`).
@@ -216,6 +274,123 @@ pub struct EventSleuthApp {
     // ── Column visibility ───────────────────────────────────────
     /// Controls which columns are visible in the event table.
     pub column_visibility: ColumnVisibility,
+    /// Collapsing of consecutive duplicate events in the table.
+    pub duplicate_collapse: DuplicateCollapse,
+
+    // ── Detections ──────────────────────────────────────────────
+    /// Built-in detection pack (see [`crate::core::builtin_rules`]).
+    pub builtin_rules: Vec<SigmaRule>,
+    /// Per-rule hits of the built-in pack: sorted indices into `all_events`.
+    pub builtin_hits: Vec<Vec<usize>>,
+    /// Number of leading `all_events` already evaluated by the built-in
+    /// pack and the correlation rules; both run over the rest each time a
+    /// query completes.
+    pub builtin_scanned: usize,
+    /// Correlation rules: the built-in ones followed by any loaded from
+    /// `sigma_rules_dir`.
+    pub correlation_rules: Vec<CorrelationRule>,
+    /// Partial and completed correlation chains.
+    pub correlation_engine: CorrelationEngine,
+    /// Sigma rules loaded from `sigma_rules_dir`.
+    pub sigma_rules: Vec<SigmaRule>,
+    /// Per-rule hits (parallel to `sigma_rules`): sorted indices into
+    /// `all_events`.
+    pub sigma_hits: Vec<Vec<usize>>,
+    /// Rule files that failed to load: `(path, reason)`.
+    pub sigma_load_errors: Vec<(std::path::PathBuf, String)>,
+    /// Folder the rules were loaded from (persisted, reloaded at startup).
+    pub sigma_rules_dir: Option<std::path::PathBuf>,
+    /// Receiver for a rule folder being loaded on a background thread.
+    pub sigma_rx: Option<Receiver<SigmaLoadResult>>,
+    /// Whether the detections window is visible.
+    pub show_detections: bool,
+    /// When set, only these events are shown (on top of the filter).
+    pub event_index_filter: Option<EventIndexFilter>,
+    /// ATT&CK techniques observed in `all_events` (rebuilt lazily).
+    pub attack_map: AttackMap,
+    /// Set whenever detections change; the map is rebuilt on next use.
+    pub attack_dirty: bool,
+    /// Whether the ATT&CK matrix window is visible.
+    pub show_attack: bool,
+
+    // ── Live-tail alerts ────────────────────────────────────────
+    /// Alert rules (persisted alongside the filter presets).
+    pub alert_rules: Vec<AlertRule>,
+    /// Sliding-window state for threshold rules.
+    pub alert_monitor: AlertMonitor,
+    /// Worker running alert log writes and commands; started on the first
+    /// fired alert.
+    pub alert_dispatcher: Option<AlertDispatcher>,
+    /// Recently fired alerts, oldest first (capped at
+    /// [`constants::MAX_FIRED_ALERTS`]).
+    pub fired_alerts: Vec<FiredAlert>,
+    /// Alerts fired since the banner was last dismissed.
+    pub unseen_alerts: usize,
+    /// File each fired alert is appended to.
+    pub alert_log_path: Option<std::path::PathBuf>,
+    /// Whether the alerts window is visible.
+    pub show_alerts: bool,
+
+    // ── IOC scanning ────────────────────────────────────────────
+    /// Imported indicators and the events they hit.
+    pub ioc_scanner: IocScanner,
+    /// Indicator files that failed to import: `(path, reason)`.
+    pub ioc_load_errors: Vec<(std::path::PathBuf, String)>,
+    /// Receiver for indicator files being parsed on a background thread.
+    pub ioc_rx: Option<Receiver<IocLoadResult>>,
+    /// Whether the IOC results window is visible.
+    pub show_iocs: bool,
+
+    // ── Investigation views ─────────────────────────────────────
+    /// Bumped whenever `all_events` changes (query completed, oldest
+    /// events evicted, list cleared); cached views rebuild when it moves.
+    pub events_generation: u64,
+    /// Bumped only when `all_events` is cleared or its oldest events are
+    /// evicted, i.e. when stored indices stop being valid; appends leave
+    /// it alone.
+    pub events_epoch: u64,
+    /// Reconstructed logon sessions and their view options.
+    pub logon_view: LogonSessionView,
+    /// Whether the logon sessions window is visible.
+    pub show_logon_sessions: bool,
+    /// Reconstructed process trees and their view options.
+    pub process_view: ProcessTreeView,
+    /// Whether the process tree side panel is visible.
+    pub show_process_tree: bool,
+    /// ActivityID chains and their view options.
+    pub activity_view: ActivityView,
+    /// Whether the activity chains window is visible.
+    pub show_activity: bool,
+    /// Boot sessions and their view options.
+    pub boot_view: BootSessionView,
+    /// Whether the boot sessions window is visible.
+    pub show_boot_sessions: bool,
+    /// Anomaly report and its view options.
+    pub anomaly_view: AnomalyView,
+    /// Whether the anomalies window is visible.
+    pub show_anomalies: bool,
+    /// Message templates and the grouped table mode.
+    pub template_view: TemplateView,
+    /// Pivot table and its axis / order options.
+    pub crosstab_view: CrosstabView,
+    /// Whether the pivot table window is visible.
+    pub show_crosstab: bool,
+    /// EventData value distributions and the explorer's view options.
+    pub field_view: FieldExplorerView,
+    /// Whether the field explorer window is visible.
+    pub show_field_explorer: bool,
+    /// Failed-logon report and its thresholds.
+    pub brute_force_view: BruteForceView,
+    /// Whether the failed logons window is visible.
+    pub show_brute_force: bool,
+    /// Application crash / hang report and its view options.
+    pub crash_view: CrashView,
+    /// Whether the app crashes window is visible.
+    pub show_crashes: bool,
+    /// Service lifecycle timeline and its view options.
+    pub services_view: ServicesView,
+    /// Whether the services window is visible.
+    pub show_services: bool,
 }
 
 // ── Pre-initialisation state (built before eframe::run_native) ──────
@@ -264,12 +439,15 @@ impl EventSleuthApp {
             selected_channels: pre.selected,
             show_channel_selector: false,
 
-            all_events: Vec::new(),
+            all_events: Arc::default(),
             filtered_indices: Vec::new(),
             selected_event_idx: None,
             needs_refilter: false,
 
             filter: FilterState::default(),
+            filter_groups: Vec::new(),
+            filter_history: Default::default(),
+            editing_group: None,
 
             sort_column: SortColumn::Timestamp,
             sort_ascending: false, // newest first
@@ -309,9 +487,14 @@ impl EventSleuthApp {
             show_stats: false,
             stats_cache: EventStats::default(),
             stats_dirty: true,
+            stats_granularity: Granularity::default(),
+            stats_utc: false,
 
             max_events_per_channel: constants::MAX_EVENTS_PER_CHANNEL,
             max_events_input: constants::MAX_EVENTS_PER_CHANNEL.to_string(),
+            pivot_window_minutes: constants::DEFAULT_PIVOT_WINDOW_MINUTES,
+            filter_suggestion: None,
+            suggestion_preset_name: String::new(),
 
             bookmarked_indices: std::collections::HashSet::new(),
             show_bookmarks_only: false,
@@ -319,6 +502,66 @@ impl EventSleuthApp {
             save_preset_focus_requested: false,
 
             column_visibility: ColumnVisibility::default(),
+            duplicate_collapse: DuplicateCollapse {
+                dirty: true,
+                ..Default::default()
+            },
+
+            builtin_rules: crate::core::builtin_rules::builtin_rules(),
+            builtin_hits: Vec::new(),
+            builtin_scanned: 0,
+            correlation_rules: crate::core::builtin_rules::builtin_correlations(),
+            correlation_engine: CorrelationEngine::default(),
+            sigma_rules: Vec::new(),
+            sigma_hits: Vec::new(),
+            sigma_load_errors: Vec::new(),
+            sigma_rules_dir: None,
+            sigma_rx: None,
+            show_detections: false,
+            event_index_filter: None,
+            attack_map: AttackMap::default(),
+            attack_dirty: true,
+            show_attack: false,
+
+            alert_rules: Vec::new(),
+            alert_monitor: AlertMonitor::default(),
+            alert_dispatcher: None,
+            fired_alerts: Vec::new(),
+            unseen_alerts: 0,
+            alert_log_path: crate::core::alert::default_alert_log_path(),
+            show_alerts: false,
+
+            ioc_scanner: IocScanner::default(),
+            ioc_load_errors: Vec::new(),
+            ioc_rx: None,
+            show_iocs: false,
+
+            events_generation: 0,
+            events_epoch: 0,
+            logon_view: LogonSessionView::default(),
+            show_logon_sessions: false,
+            process_view: ProcessTreeView::default(),
+            show_process_tree: false,
+            activity_view: ActivityView {
+                min_events: 2,
+                ..Default::default()
+            },
+            show_activity: false,
+            boot_view: BootSessionView::default(),
+            show_boot_sessions: false,
+            anomaly_view: AnomalyView::default(),
+            show_anomalies: false,
+            template_view: TemplateView::default(),
+            crosstab_view: CrosstabView::default(),
+            show_crosstab: false,
+            field_view: FieldExplorerView::default(),
+            show_field_explorer: false,
+            brute_force_view: BruteForceView::default(),
+            show_brute_force: false,
+            crash_view: CrashView::default(),
+            show_crashes: false,
+            services_view: ServicesView::default(),
+            show_services: false,
         };
 
         // ── Restore persisted preferences ──────────────────────────
@@ -336,9 +579,19 @@ impl EventSleuthApp {
                     app.selected_channels = ch;
                 }
             }
-            if let Some(presets) = eframe::get_value::<Vec<FilterPreset>>(storage, "filter_presets")
+            // Compound presets live under their own key; fall back to the
+            // legacy single-group list written by older versions.
+            if let Some(presets) =
+                eframe::get_value::<Vec<CompoundPreset>>(storage, "filter_presets_v2")
             {
                 app.filter_presets = presets;
+            } else if let Some(legacy) =
+                eframe::get_value::<Vec<FilterPreset>>(storage, "filter_presets")
+            {
+                app.filter_presets = legacy.into_iter().map(CompoundPreset::from).collect();
+            }
+            if let Some(rules) = eframe::get_value::<Vec<AlertRule>>(storage, "alert_rules") {
+                app.alert_rules = rules;
             }
             if let Some(max_ev) = eframe::get_value::<usize>(storage, "max_events_per_channel") {
                 app.max_events_per_channel = max_ev.clamp(1000, 10_000_000);
@@ -348,6 +601,18 @@ impl EventSleuthApp {
             if let Some(cv) = eframe::get_value::<ColumnVisibility>(storage, "column_visibility") {
                 app.column_visibility = cv;
             }
+            if let Some(key) = eframe::get_value::<DuplicateKey>(storage, "duplicate_key") {
+                app.duplicate_collapse.key = key;
+            }
+            if let Some(on) = eframe::get_value::<bool>(storage, "collapse_duplicates") {
+                app.duplicate_collapse.enabled = on;
+            }
+            if let Some(mins) = eframe::get_value::<i64>(storage, "pivot_window_minutes") {
+                app.pivot_window_minutes = mins.clamp(1, 24 * 60);
+            }
+            if let Some(dir) = eframe::get_value::<std::path::PathBuf>(storage, "sigma_rules_dir") {
+                app.load_sigma_rules(dir);
+            }
         }
 
         // Auto-start loading default channels
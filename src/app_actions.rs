@@ -1,6 +1,6 @@
 //! Extended actions for [`EventSleuthApp`]: export, keyboard shortcuts,
 //! export message processing, About dialog, .evtx import, live tail,
-//! and filter preset management.
+//! filter group helpers, and filter preset management.
 //!
 //! These are `impl` blocks on the app struct, split out from `app.rs`
 //! to keep file sizes manageable (< 400 lines each).
@@ -10,6 +10,8 @@ use std::sync::Arc;
 
 use crate::app::EventSleuthApp;
 use crate::core::event_reader;
+use crate::core::filter_group;
+use crate::core::filter_preset::CompoundPreset;
 use crate::util::constants;
 
 // ── Export actions ──────────────────────────────────────────────────────
@@ -33,6 +35,7 @@ impl EventSleuthApp {
             self.export_message = Some(("No events to export".into(), std::time::Instant::now()));
             return;
         }
+        let attack = self.filtered_attack_tags();
 
         let (tx, rx) = crossbeam_channel::bounded::<String>(1);
         self.export_rx = Some(rx);
@@ -43,7 +46,7 @@ impl EventSleuthApp {
                 .set_file_name("EventSleuth_export.csv")
                 .save_file()
             {
-                match crate::export::csv_export::export_csv(&events, &path) {
+                match crate::export::csv_export::export_csv(&events, &attack, &path) {
                     Ok(()) => {
                         let _ = tx.send(format!("Exported {} events to CSV", events.len()));
                     }
@@ -74,6 +77,7 @@ impl EventSleuthApp {
             self.export_message = Some(("No events to export".into(), std::time::Instant::now()));
             return;
         }
+        let attack = self.filtered_attack_tags();
 
         let (tx, rx) = crossbeam_channel::bounded::<String>(1);
         self.export_rx = Some(rx);
@@ -84,7 +88,7 @@ impl EventSleuthApp {
                 .set_file_name("EventSleuth_export.json")
                 .save_file()
             {
-                match crate::export::json_export::export_json(&events, &path) {
+                match crate::export::json_export::export_json(&events, &attack, &path) {
                     Ok(()) => {
                         let _ = tx.send(format!("Exported {} events to JSON", events.len()));
                     }
@@ -138,6 +142,7 @@ impl EventSleuthApp {
     /// - **Page Up/Down**: Jump 20 rows in event table
     /// - **Home/End**: Jump to first/last event
     /// - **Ctrl+Shift+X**: Clear all filters
+    /// - **Ctrl+Z / Ctrl+Y**: Undo / redo filter changes (outside text fields)
     pub fn handle_keyboard_shortcuts(&mut self, ctx: &egui::Context) {
         // Read keyboard-focus state BEFORE entering the input closure because
         // `wants_keyboard_input()` is a method on `Context`, not `InputState`.
@@ -152,10 +157,20 @@ impl EventSleuthApp {
 
             // Ctrl+Shift+X = Clear all filters
             if i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(egui::Key::X) {
-                self.filter.clear();
-                self.filter.parse_event_ids();
-                self.filter.parse_time_range();
-                self.needs_refilter = true;
+                self.clear_all_filters();
+            }
+
+            // Ctrl+Z / Ctrl+Y (or Ctrl+Shift+Z) = filter undo / redo.
+            // Left to the text field's own undo while one has focus.
+            if no_text_field_focus && i.modifiers.ctrl && i.key_pressed(egui::Key::Z) {
+                if i.modifiers.shift {
+                    self.redo_filter();
+                } else {
+                    self.undo_filter();
+                }
+            }
+            if no_text_field_focus && i.modifiers.ctrl && i.key_pressed(egui::Key::Y) {
+                self.redo_filter();
             }
 
             // Escape = Cancel loading, close dialogs, then clear selection.
@@ -173,57 +188,97 @@ impl EventSleuthApp {
                     self.show_save_preset = false;
                     self.preset_name_input.clear();
                     self.save_preset_focus_requested = false;
+                } else if self.filter_suggestion.is_some() {
+                    self.filter_suggestion = None;
+                    self.suggestion_preset_name.clear();
+                } else if self.show_services {
+                    self.show_services = false;
+                } else if self.show_crashes {
+                    self.show_crashes = false;
+                } else if self.show_brute_force {
+                    self.show_brute_force = false;
+                } else if self.show_field_explorer {
+                    self.show_field_explorer = false;
+                } else if self.show_crosstab {
+                    self.show_crosstab = false;
+                } else if self.show_anomalies {
+                    self.show_anomalies = false;
                 } else if self.show_stats {
                     self.show_stats = false;
+                } else if self.show_attack {
+                    self.show_attack = false;
+                } else if self.show_detections {
+                    self.show_detections = false;
+                } else if self.show_alerts {
+                    self.show_alerts = false;
+                } else if self.show_iocs {
+                    self.show_iocs = false;
+                } else if self.show_logon_sessions {
+                    self.show_logon_sessions = false;
+                } else if self.show_process_tree {
+                    self.show_process_tree = false;
+                } else if self.show_activity {
+                    self.show_activity = false;
+                } else if self.show_boot_sessions {
+                    self.show_boot_sessions = false;
                 } else {
                     self.selected_event_idx = None;
                 }
             }
 
-            // Arrow keys for event navigation
+            // Arrow keys for event navigation. Moves are in table rows, so
+            // a collapsed run of duplicates counts as one step.
             if no_text_field_focus {
+                let rows = self.table_row_count();
+                let current = self.selected_event_idx.map(|pos| self.table_row_of(pos));
+                let mut target: Option<usize> = None;
+
                 if i.key_pressed(egui::Key::ArrowDown) {
-                    if let Some(idx) = self.selected_event_idx {
-                        if idx + 1 < self.filtered_indices.len() {
-                            self.selected_event_idx = Some(idx + 1);
+                    if let Some(row) = current {
+                        if row + 1 < rows {
+                            target = Some(row + 1);
                         }
-                    } else if !self.filtered_indices.is_empty() {
-                        self.selected_event_idx = Some(0);
+                    } else if rows > 0 {
+                        target = Some(0);
                     }
                 }
                 if i.key_pressed(egui::Key::ArrowUp) {
-                    if let Some(idx) = self.selected_event_idx {
-                        if idx > 0 {
-                            self.selected_event_idx = Some(idx - 1);
+                    if let Some(row) = current {
+                        if row > 0 {
+                            target = Some(row - 1);
                         }
                     }
                 }
 
                 // Page Down = jump 20 rows forward
                 if i.key_pressed(egui::Key::PageDown) {
-                    let max = self.filtered_indices.len().saturating_sub(1);
-                    if let Some(idx) = self.selected_event_idx {
-                        self.selected_event_idx = Some((idx + 20).min(max));
-                    } else if !self.filtered_indices.is_empty() {
-                        self.selected_event_idx = Some(0);
+                    let max = rows.saturating_sub(1);
+                    if let Some(row) = current {
+                        target = Some((row + 20).min(max));
+                    } else if rows > 0 {
+                        target = Some(0);
                     }
                 }
 
                 // Page Up = jump 20 rows backward
                 if i.key_pressed(egui::Key::PageUp) {
-                    if let Some(idx) = self.selected_event_idx {
-                        self.selected_event_idx = Some(idx.saturating_sub(20));
+                    if let Some(row) = current {
+                        target = Some(row.saturating_sub(20));
                     }
                 }
 
                 // Home = jump to first event
-                if i.key_pressed(egui::Key::Home) && !self.filtered_indices.is_empty() {
-                    self.selected_event_idx = Some(0);
+                if i.key_pressed(egui::Key::Home) && rows > 0 {
+                    target = Some(0);
                 }
 
                 // End = jump to last event
-                if i.key_pressed(egui::Key::End) && !self.filtered_indices.is_empty() {
-                    self.selected_event_idx = Some(self.filtered_indices.len().saturating_sub(1));
+                if i.key_pressed(egui::Key::End) && rows > 0 {
+                    target = Some(rows - 1);
+                }
+
+                if let Some(row) = target {
+                    self.selected_event_idx = Some(self.table_row_start(row));
                 }
             }
         });
@@ -318,7 +373,7 @@ impl EventSleuthApp {
         self.cancel_loading();
         self.live_tail = false;
 
-        self.all_events.clear();
+        self.all_events = Arc::default();
         self.filtered_indices.clear();
         self.selected_event_idx = None;
         self.errors.clear();
@@ -330,6 +385,7 @@ impl EventSleuthApp {
         // invalid after a file import and must be cleared.
         self.bookmarked_indices.clear();
         self.show_bookmarks_only = false;
+        self.clear_detections();
 
         // Invalidate the stats cache immediately so a zero-event file
         // import never leaves the panel showing the previous run's data.
@@ -416,6 +472,121 @@ impl EventSleuthApp {
     }
 }
 
+// ── Filter groups & presets ────────────────────────────────────────────
+
+impl EventSleuthApp {
+    /// Reset the primary filter and drop every extra filter group.
+    ///
+    /// Shared by the filter panel "Clear all" button, the empty-table
+    /// button, and the Ctrl+Shift+X shortcut.
+    pub fn clear_all_filters(&mut self) {
+        self.filter.clear();
+        self.filter.parse_event_ids();
+        self.filter.parse_time_range();
+        self.filter_groups.clear();
+        self.editing_group = None;
+        self.event_index_filter = None;
+        self.needs_refilter = true;
+    }
+
+    /// Replace the primary filter and extra groups with a saved preset.
+    pub fn load_preset(&mut self, idx: usize) {
+        let Some(preset) = self.filter_presets.get(idx) else {
+            return;
+        };
+        let (primary, groups) = preset.to_filters();
+        self.filter = primary;
+        self.filter_groups = groups;
+        self.editing_group = None;
+        self.needs_refilter = true;
+    }
+
+    /// Apply a field pivot to the filter currently being edited (the
+    /// primary filter or the selected group), keeping its other criteria.
+    pub fn apply_pivot_request(&mut self, req: crate::core::pivot::PivotRequest) {
+        let window = self.pivot_window_minutes;
+        let target = match self.editing_group {
+            Some(i) if i < self.filter_groups.len() => &mut self.filter_groups[i].state,
+            _ => &mut self.filter,
+        };
+        crate::core::pivot::apply_pivot(target, &req, window);
+        target.parse_event_ids();
+        target.parse_time_range();
+        self.needs_refilter = true;
+    }
+
+    /// Restrict the filter currently being edited to `from..=to`, keeping
+    /// its other criteria.
+    pub fn apply_time_window(
+        &mut self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) {
+        let target = match self.editing_group {
+            Some(i) if i < self.filter_groups.len() => &mut self.filter_groups[i].state,
+            _ => &mut self.filter,
+        };
+        target.set_time_window(from, to);
+        self.needs_refilter = true;
+    }
+
+    /// Snapshot the current filter into the undo history together with
+    /// its result count. Called at the end of every `apply_filter`.
+    pub fn record_filter_history(&mut self) {
+        let snapshot = CompoundPreset::from_filters("", &self.filter, &self.filter_groups);
+        let extra = filter_group::active_group_count(&self.filter, &self.filter_groups);
+        let summary = if extra == 0 {
+            self.filter.summary()
+        } else {
+            format!("{} + {extra} group(s)", self.filter.summary())
+        };
+        self.filter_history
+            .record(snapshot, summary, self.filtered_indices.len());
+    }
+
+    /// Restore the previous filter from the history.
+    pub fn undo_filter(&mut self) {
+        if let Some(snapshot) = self.filter_history.undo().cloned() {
+            self.restore_filter_snapshot(&snapshot);
+        }
+    }
+
+    /// Re-apply the next filter from the history.
+    pub fn redo_filter(&mut self) {
+        if let Some(snapshot) = self.filter_history.redo().cloned() {
+            self.restore_filter_snapshot(&snapshot);
+        }
+    }
+
+    /// Restore history entry `idx` (picked from the history dropdown).
+    pub fn jump_to_filter_history(&mut self, idx: usize) {
+        if let Some(snapshot) = self.filter_history.jump_to(idx).cloned() {
+            self.restore_filter_snapshot(&snapshot);
+        }
+    }
+
+    fn restore_filter_snapshot(&mut self, snapshot: &CompoundPreset) {
+        let (primary, groups) = snapshot.to_filters();
+        self.filter = primary;
+        self.filter_groups = groups;
+        self.editing_group = None;
+        self.needs_refilter = true;
+    }
+
+    /// `true` if the primary filter or any extra group narrows the results.
+    pub fn has_active_filters(&self) -> bool {
+        !self.filter.is_empty()
+            || filter_group::active_group_count(&self.filter, &self.filter_groups) > 0
+    }
+
+    /// Number of active filter categories plus active extra groups, for the
+    /// toolbar and filter panel badges.
+    pub fn active_filter_count(&self) -> usize {
+        self.filter.active_count()
+            + filter_group::active_group_count(&self.filter, &self.filter_groups)
+    }
+}
+
 // ── Save-preset dialog ─────────────────────────────────────────────────
 
 impl EventSleuthApp {
@@ -476,16 +647,14 @@ impl EventSleuthApp {
 
         if should_save {
             let name = self.preset_name_input.trim().to_owned();
+            let preset = CompoundPreset::from_filters(&name, &self.filter, &self.filter_groups);
             // Replace existing preset with the same name, or append
             if let Some(existing) = self.filter_presets.iter_mut().find(|p| p.name == name) {
-                *existing =
-                    crate::core::filter_preset::FilterPreset::from_state(&name, &self.filter);
+                *existing = preset;
+                // Alert rules on this preset now match something else.
+                self.alert_monitor.reset();
             } else {
-                self.filter_presets
-                    .push(crate::core::filter_preset::FilterPreset::from_state(
-                        &name,
-                        &self.filter,
-                    ));
+                self.filter_presets.push(preset);
             }
             self.preset_name_input.clear();
             self.show_save_preset = false;
@@ -0,0 +1,483 @@
+//! Detection state management: loading Sigma and correlation rules,
+//! evaluating them and the built-in pack as events arrive, IOC list
+//! scanning, live-tail alerts, and the event-index filter used to show
+//! only the events behind a detection.
+//!
+//! Rule hits are stored as indices into `all_events`, like bookmarks, so
+//! they are cleared on reload and shifted when live tail evicts the oldest
+//! events.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::app::EventSleuthApp;
+use crate::core::alert;
+use crate::core::attack::AttackMap;
+use crate::core::correlation::{CorrelationEngine, CorrelationRule};
+use crate::core::event_record::EventRecord;
+use crate::core::ioc::{self, Ioc};
+use crate::core::sigma::{self, SigmaLoadReport};
+use crate::util::constants;
+use crate::util::error::Result;
+
+/// Message sent back by the rule-loading thread: the directory that was
+/// scanned, the outcome, and the rules already evaluated against the
+/// events in memory when the load started.
+#[derive(Debug)]
+pub struct SigmaLoadResult {
+    dir: PathBuf,
+    report: Result<SigmaLoadReport>,
+    /// Sigma hits over the first `evaluated` events.
+    hits: Vec<Vec<usize>>,
+    evaluated: usize,
+    /// Built-in plus loaded correlation rules.
+    correlation_rules: Vec<CorrelationRule>,
+    /// Engine fed the first `correlated` events.
+    correlation_engine: CorrelationEngine,
+    correlated: usize,
+    /// `events_epoch` of the snapshot; the indices above are only valid
+    /// while it is unchanged.
+    epoch: u64,
+}
+
+/// Message sent back by the IOC import thread: each chosen file and its
+/// parsed indicators.
+pub type IocLoadResult = Vec<(PathBuf, Result<Vec<Ioc>>)>;
+
+/// Show only a fixed set of events (e.g. the hits of one rule) in the
+/// table, on top of the regular filter.
+#[derive(Debug, Clone)]
+pub struct EventIndexFilter {
+    /// What the set represents, shown in the filter-panel banner.
+    pub label: String,
+    /// Indices into `all_events`.
+    pub indices: HashSet<usize>,
+}
+
+/// Load the rules in `dir` and evaluate them against a snapshot of the
+/// loaded events. Evaluating thousands of rules is the slow part of a
+/// load, so it runs on the loader thread; `process_sigma_load` only
+/// catches up on events that arrived meanwhile.
+fn load_and_evaluate(
+    dir: PathBuf,
+    (events, correlated, epoch): (Arc<Vec<EventRecord>>, usize, u64),
+) -> SigmaLoadResult {
+    let report = sigma::load_rules_dir(&dir);
+    let mut hits = Vec::new();
+    let mut correlation_rules = crate::core::builtin_rules::builtin_correlations();
+    let mut correlation_engine = CorrelationEngine::default();
+    if let Ok(report) = &report {
+        sigma::evaluate_rules(&report.rules, &events, 0, &mut hits);
+        correlation_rules.extend(report.correlations.iter().cloned());
+        correlation_engine.process(&correlation_rules, &events[..correlated], 0);
+    }
+    SigmaLoadResult {
+        dir,
+        report,
+        hits,
+        evaluated: events.len(),
+        correlation_rules,
+        correlation_engine,
+        correlated,
+        epoch,
+    }
+}
+
+impl EventSleuthApp {
+    /// Open a folder picker (on a background thread) and load every Sigma
+    /// rule found under the chosen directory.
+    pub fn pick_sigma_rules_dir(&mut self) {
+        if self.sigma_rx.is_some() {
+            tracing::debug!("pick_sigma_rules_dir: load already pending, ignoring");
+            return;
+        }
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.sigma_rx = Some(rx);
+
+        let snapshot = self.rule_snapshot();
+        std::thread::spawn(move || {
+            if let Some(dir) = rfd::FileDialog::new()
+                .set_title("Select Sigma Rules Folder")
+                .pick_folder()
+            {
+                let _ = tx.send(load_and_evaluate(dir, snapshot));
+            }
+        });
+    }
+
+    /// Load (or reload) the rules in `dir` on a background thread.
+    pub fn load_sigma_rules(&mut self, dir: PathBuf) {
+        if self.sigma_rx.is_some() {
+            return;
+        }
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.sigma_rx = Some(rx);
+
+        let snapshot = self.rule_snapshot();
+        std::thread::spawn(move || {
+            let _ = tx.send(load_and_evaluate(dir, snapshot));
+        });
+    }
+
+    /// Events for the rule-loading thread to evaluate: the shared list,
+    /// how many of them the correlation engine has seen, and the epoch.
+    fn rule_snapshot(&self) -> (Arc<Vec<EventRecord>>, usize, u64) {
+        let correlated = self.builtin_scanned.min(self.all_events.len());
+        (Arc::clone(&self.all_events), correlated, self.events_epoch)
+    }
+
+    /// Poll the rule-loading channel and install the loaded rules.
+    ///
+    /// The loader thread already evaluated the rules against the events in
+    /// memory when it started; only events appended since are evaluated
+    /// here. Events arriving later are evaluated batch by batch in
+    /// `process_messages`.
+    pub(crate) fn process_sigma_load(&mut self) {
+        let load = {
+            let rx = match &self.sigma_rx {
+                Some(rx) => rx,
+                None => return,
+            };
+            match rx.try_recv() {
+                Ok(msg) => msg,
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    // Folder dialog cancelled.
+                    self.sigma_rx = None;
+                    return;
+                }
+                Err(crossbeam_channel::TryRecvError::Empty) => return,
+            }
+        };
+        self.sigma_rx = None;
+        let dir = load.dir;
+
+        match load.report {
+            Ok(report) => {
+                tracing::info!(
+                    "Loaded {} Sigma rules from {} ({} skipped)",
+                    report.rules.len(),
+                    dir.display(),
+                    report.errors.len(),
+                );
+                self.sigma_rules = report.rules;
+                self.sigma_load_errors = report.errors;
+                self.sigma_rules_dir = Some(dir);
+                self.correlation_rules = load.correlation_rules;
+
+                // A reload or eviction since the snapshot invalidates its
+                // indices; start over from the current events then.
+                let (evaluated, correlated) = if load.epoch == self.events_epoch {
+                    self.sigma_hits = load.hits;
+                    self.correlation_engine = load.correlation_engine;
+                    (load.evaluated, load.correlated)
+                } else {
+                    self.sigma_hits.clear();
+                    self.correlation_engine.clear();
+                    (0, 0)
+                };
+                sigma::evaluate_rules(
+                    &self.sigma_rules,
+                    &self.all_events,
+                    evaluated,
+                    &mut self.sigma_hits,
+                );
+                self.correlation_engine.process(
+                    &self.correlation_rules,
+                    &self.all_events[..self.builtin_scanned.min(self.all_events.len())],
+                    correlated,
+                );
+                if self.event_index_filter.take().is_some() {
+                    self.needs_refilter = true;
+                }
+                self.attack_dirty = true;
+                self.show_detections = true;
+            }
+            Err(e) => {
+                tracing::error!("Failed to load Sigma rules from {}: {}", dir.display(), e);
+                self.sigma_load_errors = vec![(dir, e.to_string())];
+            }
+        }
+    }
+
+    /// Open a file picker (on a background thread) and parse the chosen
+    /// indicator lists (text, CSV or STIX 2.1 JSON).
+    pub fn pick_ioc_files(&mut self) {
+        if self.ioc_rx.is_some() {
+            tracing::debug!("pick_ioc_files: import already pending, ignoring");
+            return;
+        }
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.ioc_rx = Some(rx);
+
+        std::thread::spawn(move || {
+            if let Some(paths) = rfd::FileDialog::new()
+                .set_title("Import IOC Lists")
+                .add_filter("IOC lists", &["txt", "csv", "json", "stix"])
+                .add_filter("All files", &["*"])
+                .pick_files()
+            {
+                let results = paths
+                    .into_iter()
+                    .map(|path| {
+                        let parsed = ioc::load_file(&path);
+                        (path, parsed)
+                    })
+                    .collect();
+                let _ = tx.send(results);
+            }
+        });
+    }
+
+    /// Poll the IOC import channel, add the indicators and rescan every
+    /// event in memory.
+    pub(crate) fn process_ioc_load(&mut self) {
+        let results = {
+            let rx = match &self.ioc_rx {
+                Some(rx) => rx,
+                None => return,
+            };
+            match rx.try_recv() {
+                Ok(msg) => msg,
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    // File dialog cancelled.
+                    self.ioc_rx = None;
+                    return;
+                }
+                Err(crossbeam_channel::TryRecvError::Empty) => return,
+            }
+        };
+        self.ioc_rx = None;
+
+        self.ioc_load_errors.clear();
+        let mut iocs = Vec::new();
+        for (path, parsed) in results {
+            match parsed {
+                Ok(list) if list.is_empty() => {
+                    self.ioc_load_errors
+                        .push((path, "no indicators found".to_owned()));
+                }
+                Ok(list) => iocs.extend(list),
+                Err(e) => {
+                    tracing::error!("Failed to import IOCs from {}: {}", path.display(), e);
+                    self.ioc_load_errors.push((path, e.to_string()));
+                }
+            }
+        }
+        match self.ioc_scanner.add(iocs) {
+            Ok(added) => tracing::info!(
+                "Imported {} indicators ({} total)",
+                added,
+                self.ioc_scanner.indicators.len()
+            ),
+            Err(e) => {
+                tracing::error!("Failed to build IOC matcher: {}", e);
+                self.ioc_load_errors.push((PathBuf::new(), e.to_string()));
+            }
+        }
+        self.ioc_scanner.scan(&self.all_events, 0);
+        if self.event_index_filter.take().is_some() {
+            self.needs_refilter = true;
+        }
+        self.show_iocs = true;
+    }
+
+    /// Unload all indicators.
+    pub fn clear_iocs(&mut self) {
+        self.ioc_scanner.clear();
+        self.ioc_load_errors.clear();
+        self.clear_event_index_filter();
+    }
+
+    /// Evaluate all detections against `all_events[start..]`, i.e. the
+    /// events appended by the latest batch.
+    pub(crate) fn evaluate_new_events(&mut self, start: usize) {
+        if !self.sigma_rules.is_empty() {
+            sigma::evaluate_rules(
+                &self.sigma_rules,
+                &self.all_events,
+                start,
+                &mut self.sigma_hits,
+            );
+        }
+        self.ioc_scanner.scan(&self.all_events, start);
+        self.attack_dirty = true;
+    }
+
+    /// Run the built-in pack and the correlation rules over events that
+    /// arrived since their last run.
+    ///
+    /// Called when a query completes (full load or live-tail poll), so
+    /// findings are summarised once the data set is complete rather than
+    /// being recomputed batch by batch, and correlation sees each query's
+    /// events in time order across channels.
+    pub(crate) fn evaluate_completed_query(&mut self) {
+        let start = self.builtin_scanned.min(self.all_events.len());
+        sigma::evaluate_rules(
+            &self.builtin_rules,
+            &self.all_events,
+            start,
+            &mut self.builtin_hits,
+        );
+        self.correlation_engine
+            .process(&self.correlation_rules, &self.all_events, start);
+        self.builtin_scanned = self.all_events.len();
+        self.attack_dirty = true;
+        self.events_generation += 1;
+    }
+
+    /// Re-base stored hit indices after the `evict` oldest events were
+    /// dropped from the front of `all_events`.
+    pub(crate) fn shift_detections(&mut self, evict: usize) {
+        for hits in self.sigma_hits.iter_mut().chain(&mut self.builtin_hits) {
+            evict_indices(hits, evict);
+        }
+        self.builtin_scanned = self.builtin_scanned.saturating_sub(evict);
+        self.correlation_engine.shift(evict);
+        self.ioc_scanner.shift(evict);
+        self.attack_dirty = true;
+        self.events_generation += 1;
+        self.events_epoch += 1;
+        if let Some(f) = &mut self.event_index_filter {
+            f.indices = f
+                .indices
+                .iter()
+                .filter_map(|&i| i.checked_sub(evict))
+                .collect();
+        }
+    }
+
+    /// Drop all detection results (called when the event list is replaced).
+    pub(crate) fn clear_detections(&mut self) {
+        for hits in self.sigma_hits.iter_mut().chain(&mut self.builtin_hits) {
+            hits.clear();
+        }
+        self.builtin_scanned = 0;
+        self.correlation_engine.clear();
+        self.ioc_scanner.clear_hits();
+        self.attack_dirty = true;
+        self.events_generation += 1;
+        self.events_epoch += 1;
+        self.event_index_filter = None;
+    }
+
+    /// Restrict the table to `indices` (into `all_events`).
+    pub fn set_event_index_filter(&mut self, label: String, indices: &[usize]) {
+        self.event_index_filter = Some(EventIndexFilter {
+            label,
+            indices: indices.iter().copied().collect(),
+        });
+        self.selected_event_idx = None;
+        self.needs_refilter = true;
+    }
+
+    /// Remove the event-index restriction.
+    pub fn clear_event_index_filter(&mut self) {
+        if self.event_index_filter.take().is_some() {
+            self.needs_refilter = true;
+        }
+    }
+
+    /// Total number of rule hits across all loaded Sigma rules.
+    pub fn sigma_hit_count(&self) -> usize {
+        self.sigma_hits.iter().map(Vec::len).sum()
+    }
+
+    /// Evaluate alert rules against `all_events[start..]` (a live-tail
+    /// batch) and queue each fired alert on the dispatch worker for logging
+    /// and its command.
+    pub(crate) fn evaluate_alerts(&mut self, start: usize) {
+        if self.alert_rules.is_empty() || start >= self.all_events.len() {
+            return;
+        }
+        let fired = self.alert_monitor.evaluate(
+            &self.alert_rules,
+            &self.filter_presets,
+            &self.all_events[start..],
+        );
+        if fired.is_empty() {
+            return;
+        }
+        for alert in &fired {
+            tracing::info!("Alert '{}': {}", alert.rule, alert.message);
+        }
+
+        let dispatcher = self
+            .alert_dispatcher
+            .get_or_insert_with(alert::AlertDispatcher::spawn);
+        for alert in &fired {
+            dispatcher.send(alert.clone(), self.alert_log_path.clone());
+        }
+
+        self.unseen_alerts += fired.len();
+        self.fired_alerts.extend(fired);
+        if self.fired_alerts.len() > constants::MAX_FIRED_ALERTS {
+            let excess = self.fired_alerts.len() - constants::MAX_FIRED_ALERTS;
+            self.fired_alerts.drain(..excess);
+        }
+    }
+
+    /// Rebuild [`Self::attack_map`] if detections changed since the last
+    /// build.
+    pub fn refresh_attack_map(&mut self) {
+        if !self.attack_dirty {
+            return;
+        }
+        let rule_hits = self
+            .builtin_rules
+            .iter()
+            .zip(&self.builtin_hits)
+            .chain(self.sigma_rules.iter().zip(&self.sigma_hits))
+            .map(|(rule, hits)| (rule.attack_tags().collect::<Vec<_>>(), hits.as_slice()));
+        let chains = self.correlation_engine.matches().iter().filter_map(|m| {
+            let rule = self.correlation_rules.get(m.rule)?;
+            Some((rule.attack_tags().collect::<Vec<_>>(), m.events.as_slice()))
+        });
+        self.attack_map = AttackMap::build(&self.all_events, rule_hits.chain(chains));
+        self.attack_dirty = false;
+    }
+
+    /// ATT&CK techniques of each filtered event, parallel to
+    /// [`Self::filtered_event_list`] (for exports).
+    pub fn filtered_attack_tags(&mut self) -> Vec<Vec<String>> {
+        self.refresh_attack_map();
+        self.filtered_indices
+            .iter()
+            .map(|&idx| self.attack_map.event_tags(idx))
+            .collect()
+    }
+
+    /// Built-in findings per severity, indexed by [`sigma::SigmaLevel`] order
+    /// (`Informational` … `Critical`).
+    pub fn builtin_level_counts(&self) -> [usize; 5] {
+        let mut counts = [0; 5];
+        for (rule, hits) in self.builtin_rules.iter().zip(&self.builtin_hits) {
+            counts[rule.level as usize] += hits.len();
+        }
+        counts
+    }
+}
+
+/// Drop indices below `evict` from a sorted index list and shift the rest
+/// down by `evict`.
+pub(crate) fn evict_indices(list: &mut Vec<usize>, evict: usize) {
+    let keep_from = list.partition_point(|&i| i < evict);
+    list.drain(..keep_from);
+    for i in list.iter_mut() {
+        *i -= evict;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evict_indices;
+
+    #[test]
+    fn evict_indices_drops_and_shifts() {
+        let mut list = vec![0, 2, 5, 9];
+        evict_indices(&mut list, 3);
+        assert_eq!(list, vec![2, 6]);
+        evict_indices(&mut list, 10);
+        assert!(list.is_empty());
+    }
+}
@@ -8,8 +8,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use crate::app::{EventSleuthApp, SortColumn};
+use crate::core::dedup;
 use crate::core::event_reader::{self, ReaderMessage};
 use crate::core::event_record::EventRecord;
+use crate::core::filter_group;
 use crate::util::constants;
 
 // ── Core logic ──────────────────────────────────────────────────────────
@@ -34,7 +36,7 @@ impl EventSleuthApp {
         }
 
         // Clear previous results
-        self.all_events.clear();
+        self.all_events = Arc::default();
         self.filtered_indices.clear();
         self.selected_event_idx = None;
         self.errors.clear();
@@ -46,6 +48,7 @@ impl EventSleuthApp {
         // invalid after a reload and must be cleared.
         self.bookmarked_indices.clear();
         self.show_bookmarks_only = false;
+        self.clear_detections();
 
         // Invalidate the stats cache immediately so a zero-event query
         // never leaves the panel showing the previous run's data.
@@ -119,7 +122,8 @@ impl EventSleuthApp {
             match rx.try_recv() {
                 Ok(msg) => match msg {
                     ReaderMessage::EventBatch(batch) => {
-                        self.all_events.extend(batch);
+                        let len_before = self.all_events.len();
+                        Arc::make_mut(&mut self.all_events).extend(batch);
 
                         // Guard against unbounded memory growth during live-tail.
                         //
@@ -136,15 +140,18 @@ impl EventSleuthApp {
                         //  • `bookmarked_indices` are cleared because they are raw indices
                         //    into `all_events` whose values shift after the drain.  We
                         //    cannot remap them cheaply without a reverse lookup map.
+                        //  • Detection hits are sorted, so they are shifted down instead.
                         //
                         // The cap scales with the user-configured per-channel maximum
                         // (which can exceed the compile-time default by up to 20x) so a
                         // deliberately large full load is never silently evicted by the
                         // first tail poll.
                         let cap = effective_tail_cap(self.max_events_per_channel);
+                        let mut evict = 0;
                         if self.is_tail_query && self.all_events.len() > cap {
-                            let evict = self.all_events.len() - cap;
-                            self.all_events.drain(0..evict);
+                            evict = self.all_events.len() - cap;
+                            Arc::make_mut(&mut self.all_events).drain(0..evict);
+                            self.shift_detections(evict);
                             self.filtered_indices.clear();
                             self.selected_event_idx = None;
                             if !self.bookmarked_indices.is_empty() {
@@ -163,6 +170,11 @@ impl EventSleuthApp {
                                 cap,
                             );
                         }
+                        let new_start = len_before.saturating_sub(evict);
+                        self.evaluate_new_events(new_start);
+                        if self.is_tail_query {
+                            self.evaluate_alerts(new_start);
+                        }
 
                         received_events = true;
                     }
@@ -178,6 +190,7 @@ impl EventSleuthApp {
                         // including the zero-event case where no EventBatch
                         // messages arrived and needs_refilter was never set.
                         self.stats_dirty = true;
+                        self.evaluate_completed_query();
                         if self.is_tail_query {
                             // Tail query: only update status if new events arrived
                             if total > 0 {
@@ -250,7 +263,12 @@ impl EventSleuthApp {
                     if self.show_bookmarks_only && !self.bookmarked_indices.contains(i) {
                         return false;
                     }
-                    self.filter.matches(event)
+                    if let Some(f) = &self.event_index_filter {
+                        if !f.indices.contains(i) {
+                            return false;
+                        }
+                    }
+                    filter_group::matches_groups(&self.filter, &self.filter_groups, event)
                 })
                 .map(|(i, _)| i),
         );
@@ -288,6 +306,7 @@ impl EventSleuthApp {
 
         self.needs_refilter = false;
         self.stats_dirty = true;
+        self.record_filter_history();
     }
 
     /// Sort `filtered_indices` by the current sort column and direction.
@@ -315,6 +334,53 @@ impl EventSleuthApp {
                 ord.reverse()
             }
         });
+        // Template groups and duplicate runs hold positions in the sorted
+        // list.
+        self.template_view.groups_dirty = true;
+        self.duplicate_collapse.dirty = true;
+        self.crosstab_view.dirty = true;
+        self.field_view.dirty = true;
+    }
+
+    /// Recompute the duplicate runs if `filtered_indices` or the key
+    /// changed since the last call.
+    pub fn refresh_duplicate_runs(&mut self) {
+        let dc = &mut self.duplicate_collapse;
+        if dc.enabled && dc.dirty {
+            dc.runs = dedup::collapse_runs(&self.all_events, &self.filtered_indices, dc.key);
+            dc.dirty = false;
+        }
+    }
+
+    /// Number of rows in the event table: one per filtered event, or one
+    /// per run of duplicates while collapsing.
+    pub fn table_row_count(&mut self) -> usize {
+        self.refresh_duplicate_runs();
+        if self.duplicate_collapse.enabled {
+            self.duplicate_collapse.runs.len()
+        } else {
+            self.filtered_indices.len()
+        }
+    }
+
+    /// Table row showing filtered position `pos`.
+    pub fn table_row_of(&mut self, pos: usize) -> usize {
+        self.refresh_duplicate_runs();
+        if self.duplicate_collapse.enabled {
+            dedup::run_of(&self.duplicate_collapse.runs, pos).unwrap_or(0)
+        } else {
+            pos
+        }
+    }
+
+    /// Filtered position shown by table row `row` (the first event of a
+    /// run while collapsing).
+    pub fn table_row_start(&self, row: usize) -> usize {
+        if self.duplicate_collapse.enabled {
+            self.duplicate_collapse.runs.get(row).map_or(0, |r| r.start)
+        } else {
+            row
+        }
     }
 
     /// Get a reference to the currently selected event, if any.
@@ -412,6 +478,10 @@ impl eframe::App for EventSleuthApp {
         // 3. Process .evtx import file selection
         self.process_import_selection();
 
+        // 3b. Install Sigma rules and IOC lists loaded in the background
+        self.process_sigma_load();
+        self.process_ioc_load();
+
         // 4. Debounce: apply filter after FILTER_DEBOUNCE_MS of inactivity
         if let Some(timer) = self.debounce_timer {
             let debounce = std::time::Duration::from_millis(constants::FILTER_DEBOUNCE_MS);
@@ -419,6 +489,11 @@ impl eframe::App for EventSleuthApp {
             if elapsed >= debounce {
                 self.filter.parse_event_ids();
                 self.filter.parse_time_range();
+                // The panel may have been editing an extra group.
+                for group in &mut self.filter_groups {
+                    group.state.parse_event_ids();
+                    group.state.parse_time_range();
+                }
                 self.needs_refilter = true;
                 self.debounce_timer = None;
             } else {
@@ -491,6 +566,9 @@ impl eframe::App for EventSleuthApp {
                 });
             });
 
+        // ── Right process tree panel ────────────────────────────────
+        self.render_process_tree_panel(ctx);
+
         // ── Central event table ─────────────────────────────────────
         egui::CentralPanel::default().show(ctx, |ui| {
             // Security elevation banner
@@ -516,6 +594,7 @@ impl eframe::App for EventSleuthApp {
                     });
                 ui.add_space(4.0);
             }
+            self.render_alert_banner(ui);
             self.render_event_table(ui);
         });
 
@@ -523,7 +602,21 @@ impl eframe::App for EventSleuthApp {
         self.render_channel_selector(ctx);
         self.render_about_dialog(ctx);
         self.render_save_preset_dialog(ctx);
+        self.render_filter_suggestion_dialog(ctx);
         self.render_stats_panel(ctx);
+        self.render_detections_panel(ctx);
+        self.render_attack_panel(ctx);
+        self.render_alerts_panel(ctx);
+        self.render_ioc_panel(ctx);
+        self.render_logon_sessions_panel(ctx);
+        self.render_activity_panel(ctx);
+        self.render_boot_sessions_panel(ctx);
+        self.render_anomaly_panel(ctx);
+        self.render_crosstab_panel(ctx);
+        self.render_field_explorer(ctx);
+        self.render_brute_force_panel(ctx);
+        self.render_crash_panel(ctx);
+        self.render_services_panel(ctx);
     }
 
     /// Return the clear colour used before each frame render.
@@ -542,13 +635,24 @@ impl eframe::App for EventSleuthApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, "dark_mode", &self.dark_mode);
         eframe::set_value(storage, "selected_channels", &self.selected_channels);
-        eframe::set_value(storage, "filter_presets", &self.filter_presets);
+        eframe::set_value(storage, "filter_presets_v2", &self.filter_presets);
+        eframe::set_value(storage, "alert_rules", &self.alert_rules);
         eframe::set_value(
             storage,
             "max_events_per_channel",
             &self.max_events_per_channel,
         );
         eframe::set_value(storage, "column_visibility", &self.column_visibility);
+        eframe::set_value(storage, "duplicate_key", &self.duplicate_collapse.key);
+        eframe::set_value(
+            storage,
+            "collapse_duplicates",
+            &self.duplicate_collapse.enabled,
+        );
+        eframe::set_value(storage, "pivot_window_minutes", &self.pivot_window_minutes);
+        if let Some(dir) = &self.sigma_rules_dir {
+            eframe::set_value(storage, "sigma_rules_dir", dir);
+        }
     }
 }
 
@@ -0,0 +1,195 @@
+//! ActivityID correlation: group events into activity chains.
+//!
+//! Events that share an ActivityID belong to one activity; an event whose
+//! RelatedActivityID names another activity links the two, so a whole
+//! multi-component operation (Windows Update, Group Policy processing,
+//! Kerberos, ...) collapses into a single chain across all loaded
+//! channels.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_ACTIVITY_CHAINS;
+
+/// Events linked by ActivityID / RelatedActivityID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityChain {
+    /// Activity IDs in the chain (normalised, sorted).
+    pub activity_ids: Vec<String>,
+    /// Indices into the event list, in time order.
+    pub events: Vec<usize>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Providers involved, sorted.
+    pub providers: Vec<String>,
+    /// Channels involved, sorted.
+    pub channels: Vec<String>,
+}
+
+impl ActivityChain {
+    /// First-to-last event time.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+}
+
+/// Lower-cased GUID without braces; `None` for the all-zero GUID, which
+/// means "no activity".
+fn normalise(id: &str) -> Option<String> {
+    let id = id.trim().trim_matches(['{', '}']).to_ascii_lowercase();
+    (!id.is_empty() && id.chars().any(|c| c != '0' && c != '-')).then_some(id)
+}
+
+/// Union-find over activity IDs.
+#[derive(Default)]
+struct Activities {
+    ids: HashMap<String, usize>,
+    parent: Vec<usize>,
+}
+
+impl Activities {
+    fn slot(&mut self, id: String) -> usize {
+        let next = self.parent.len();
+        let slot = *self.ids.entry(id).or_insert(next);
+        if slot == next {
+            self.parent.push(next);
+        }
+        slot
+    }
+
+    fn root(&mut self, mut a: usize) -> usize {
+        while self.parent[a] != a {
+            self.parent[a] = self.parent[self.parent[a]];
+            a = self.parent[a];
+        }
+        a
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.root(a), self.root(b));
+        if a != b {
+            self.parent[b] = a;
+        }
+    }
+}
+
+/// Group `events` (any order) into activity chains of at least
+/// `min_events` events.
+///
+/// Returns chains newest first, capped at [`MAX_ACTIVITY_CHAINS`].
+pub fn group(events: &[EventRecord], min_events: usize) -> Vec<ActivityChain> {
+    let mut activities = Activities::default();
+    let mut event_slot: Vec<(usize, usize)> = Vec::new();
+    for (i, e) in events.iter().enumerate() {
+        let Some(id) = e.activity_id.as_deref().and_then(normalise) else {
+            continue;
+        };
+        let slot = activities.slot(id);
+        if let Some(related) = e.related_activity_id.as_deref().and_then(normalise) {
+            let related = activities.slot(related);
+            activities.union(related, slot);
+        }
+        event_slot.push((i, slot));
+    }
+
+    let mut by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, slot) in event_slot {
+        by_root.entry(activities.root(slot)).or_default().push(i);
+    }
+    let mut ids_by_root: HashMap<usize, Vec<String>> = HashMap::new();
+    let ids: Vec<(String, usize)> = activities
+        .ids
+        .iter()
+        .map(|(k, &v)| (k.clone(), v))
+        .collect();
+    for (id, slot) in ids {
+        ids_by_root
+            .entry(activities.root(slot))
+            .or_default()
+            .push(id);
+    }
+
+    let mut chains: Vec<ActivityChain> = by_root
+        .into_iter()
+        .filter(|(_, members)| members.len() >= min_events.max(1))
+        .map(|(root, mut members)| {
+            members.sort_by_key(|&i| events[i].timestamp);
+            let mut activity_ids = ids_by_root.remove(&root).unwrap_or_default();
+            activity_ids.sort();
+            let providers: BTreeSet<&str> = members
+                .iter()
+                .map(|&i| events[i].provider_name.as_str())
+                .collect();
+            let channels: BTreeSet<&str> = members
+                .iter()
+                .map(|&i| events[i].channel.as_str())
+                .collect();
+            ActivityChain {
+                activity_ids,
+                start: events[members[0]].timestamp,
+                end: events[members[members.len() - 1]].timestamp,
+                events: members,
+                providers: providers.into_iter().map(str::to_owned).collect(),
+                channels: channels.into_iter().map(str::to_owned).collect(),
+            }
+        })
+        .collect();
+    chains.sort_by_key(|c| std::cmp::Reverse(c.start));
+    chains.truncate(MAX_ACTIVITY_CHAINS);
+    chains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(
+        provider: &str,
+        secs: i64,
+        activity: Option<&str>,
+        related: Option<&str>,
+    ) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: 1,
+            provider_name: provider.into(),
+            timestamp: EventRecord::test_time(secs),
+            activity_id: activity.map(str::to_owned),
+            related_activity_id: related.map(str::to_owned),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn follows_related_activity_links() {
+        let events = vec![
+            event("GroupPolicy", 30, Some("{CCCC}"), Some("{BBBB}")),
+            event("GroupPolicy", 0, Some("{AAAA}"), None),
+            event("Kerberos", 10, Some("{bbbb}"), Some("{AAAA}")),
+            event("Other", 5, Some("{DDDD}"), None),
+            event("GroupPolicy", 40, Some("{AAAA}"), None),
+            event("NoActivity", 1, None, None),
+        ];
+        let chains = group(&events, 2);
+        assert_eq!(chains.len(), 1);
+        let c = &chains[0];
+        assert_eq!(c.events, vec![1, 2, 0, 4]);
+        assert_eq!(c.activity_ids, vec!["aaaa", "bbbb", "cccc"]);
+        assert_eq!(c.providers, vec!["GroupPolicy", "Kerberos"]);
+        assert_eq!(c.duration(), Duration::seconds(40));
+        // Singletons are kept when asked for.
+        assert_eq!(group(&events, 1).len(), 2);
+    }
+
+    #[test]
+    fn ignores_null_activity() {
+        let zero = "{00000000-0000-0000-0000-000000000000}";
+        let events = vec![
+            event("A", 0, Some(zero), None),
+            event("B", 1, Some(zero), None),
+        ];
+        assert!(group(&events, 1).is_empty());
+    }
+}
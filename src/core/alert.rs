@@ -0,0 +1,418 @@
+//! Threshold and rate alerts evaluated on live-tail events.
+//!
+//! An [`AlertRule`] names a saved [`CompoundPreset`] and fires either on
+//! any new matching event or when more than `count` matching events fall
+//! within a sliding `window_minutes` window. [`AlertMonitor`] keeps the
+//! per-rule windows between live-tail polls. [`dispatch`] performs the
+//! side effects of a fired alert (alert log line, optional command);
+//! [`AlertDispatcher`] runs it on a single background worker.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+use crossbeam_channel::{Sender, TrySendError};
+
+use crate::core::event_record::EventRecord;
+use crate::core::filter_group;
+use crate::core::filter_preset::{default_true, CompoundPreset};
+use crate::util::constants;
+
+/// When an alert rule fires.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum AlertTrigger {
+    /// Any new event matching the preset.
+    #[default]
+    AnyMatch,
+    /// More than `count` matching events within `window_minutes`.
+    Threshold { count: usize, window_minutes: i64 },
+}
+
+/// A persisted alert rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct AlertRule {
+    /// Display name, also written to the alert log.
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Name of the filter preset events must match.
+    pub preset: String,
+    #[serde(default)]
+    pub trigger: AlertTrigger,
+    /// Local command run when the alert fires, with the triggering event
+    /// as JSON on stdin. Empty = none.
+    #[serde(default)]
+    pub command: String,
+}
+
+impl AlertRule {
+    /// A new enabled any-match rule for `preset`.
+    pub fn new(name: &str, preset: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            enabled: true,
+            preset: preset.to_owned(),
+            trigger: AlertTrigger::AnyMatch,
+            command: String::new(),
+        }
+    }
+}
+
+/// One alert that fired.
+#[derive(Debug, Clone)]
+pub struct FiredAlert {
+    /// Name of the rule.
+    pub rule: String,
+    /// Human-readable description, e.g. `"12 events in 5 min"`.
+    pub message: String,
+    /// The event that triggered the alert (the first match for any-match
+    /// rules, the one crossing the threshold otherwise).
+    pub event: EventRecord,
+    /// Command configured on the rule (may be empty).
+    pub command: String,
+    /// Wall-clock time the alert fired.
+    pub fired_at: DateTime<Utc>,
+}
+
+/// Sliding-window state for threshold rules, keyed by the rule's position
+/// and a hash of its definition, so rules sharing a name keep separate
+/// windows and an edited rule starts a fresh one.
+#[derive(Debug, Default)]
+pub struct AlertMonitor {
+    windows: HashMap<(usize, u64), VecDeque<DateTime<Utc>>>,
+}
+
+/// Hash of everything that defines `rule`.
+fn rule_hash(rule: &AlertRule) -> u64 {
+    let mut h = DefaultHasher::new();
+    rule.hash(&mut h);
+    h.finish()
+}
+
+impl AlertMonitor {
+    /// Evaluate `events` (a freshly tailed batch) against every enabled
+    /// rule. Rules whose preset no longer exists are skipped.
+    ///
+    /// Any-match rules fire at most once per batch, reporting how many
+    /// events matched. Threshold rules fire each time the window exceeds
+    /// the count, after which the window starts over.
+    pub fn evaluate(
+        &mut self,
+        rules: &[AlertRule],
+        presets: &[CompoundPreset],
+        events: &[EventRecord],
+    ) -> Vec<FiredAlert> {
+        let mut fired = Vec::new();
+        let mut order: Vec<&EventRecord> = events.iter().collect();
+        order.sort_by_key(|e| e.timestamp);
+
+        // Drop windows of rules that were removed or edited.
+        let keys: Vec<(usize, u64)> = rules
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (i, rule_hash(r)))
+            .collect();
+        self.windows.retain(|k, _| keys.contains(k));
+
+        for (rule, &key) in rules.iter().zip(&keys).filter(|(r, _)| r.enabled) {
+            let Some(preset) = presets.iter().find(|p| p.name == rule.preset) else {
+                continue;
+            };
+            let (primary, groups) = preset.to_filters();
+            let mut matching = order
+                .iter()
+                .copied()
+                .filter(|e| filter_group::matches_groups(&primary, &groups, e));
+
+            match rule.trigger {
+                AlertTrigger::AnyMatch => {
+                    let Some(first) = matching.next() else {
+                        continue;
+                    };
+                    let n = 1 + matching.count();
+                    fired.push(FiredAlert {
+                        rule: rule.name.clone(),
+                        message: if n == 1 {
+                            format!("event {} matched '{}'", first.event_id, rule.preset)
+                        } else {
+                            format!("{n} new events matched '{}'", rule.preset)
+                        },
+                        event: first.clone(),
+                        command: rule.command.clone(),
+                        fired_at: Utc::now(),
+                    });
+                }
+                AlertTrigger::Threshold {
+                    count,
+                    window_minutes,
+                } => {
+                    let window = Duration::minutes(window_minutes.max(1));
+                    let times = self.windows.entry(key).or_default();
+                    for event in matching {
+                        times.push_back(event.timestamp);
+                        while times.front().is_some_and(|&t| event.timestamp - t > window) {
+                            times.pop_front();
+                        }
+                        if times.len() > count {
+                            fired.push(FiredAlert {
+                                rule: rule.name.clone(),
+                                message: format!(
+                                    "{} events matched '{}' within {} min",
+                                    times.len(),
+                                    rule.preset,
+                                    window.num_minutes()
+                                ),
+                                event: event.clone(),
+                                command: rule.command.clone(),
+                                fired_at: Utc::now(),
+                            });
+                            times.clear();
+                        }
+                    }
+                }
+            }
+        }
+        fired
+    }
+
+    /// Forget all window state (e.g. after a preset was overwritten).
+    pub fn reset(&mut self) {
+        self.windows.clear();
+    }
+}
+
+/// Single background worker that [`dispatch`]es fired alerts in order.
+///
+/// The queue is bounded: while a slow or hanging command blocks the
+/// worker, further alerts are logged and dropped instead of piling up
+/// threads.
+#[derive(Debug)]
+pub struct AlertDispatcher {
+    tx: Sender<(FiredAlert, Option<PathBuf>)>,
+}
+
+impl AlertDispatcher {
+    /// Start the worker. It exits when the dispatcher is dropped.
+    pub fn spawn() -> Self {
+        let (tx, rx) = crossbeam_channel::bounded::<(FiredAlert, Option<PathBuf>)>(
+            constants::MAX_PENDING_ALERT_DISPATCHES,
+        );
+        std::thread::spawn(move || {
+            for (alert, log_path) in rx {
+                dispatch(&alert, log_path.as_deref());
+            }
+        });
+        Self { tx }
+    }
+
+    /// Queue `alert` for logging to `log_path` and running its command.
+    pub fn send(&self, alert: FiredAlert, log_path: Option<PathBuf>) {
+        match self.tx.try_send((alert, log_path)) {
+            Ok(()) => {}
+            Err(TrySendError::Full((alert, _))) => tracing::warn!(
+                "Alert queue full (is a command hanging?); '{}' not dispatched",
+                alert.rule
+            ),
+            Err(TrySendError::Disconnected((alert, _))) => {
+                tracing::warn!("Alert worker stopped; '{}' not dispatched", alert.rule)
+            }
+        }
+    }
+}
+
+/// Default alert log location: `%LOCALAPPDATA%\EventSleuth\logs\alerts.log`.
+pub fn default_alert_log_path() -> Option<PathBuf> {
+    let local_app_data = std::env::var("LOCALAPPDATA").ok()?;
+    Some(
+        PathBuf::from(local_app_data)
+            .join(constants::APP_DATA_DIR)
+            .join(constants::LOG_DIR)
+            .join(constants::ALERT_LOG_FILE_NAME),
+    )
+}
+
+/// One alert-log line (tab separated: fired time, rule, message, event
+/// time, event ID, provider, computer).
+pub fn format_log_line(alert: &FiredAlert) -> String {
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        alert.fired_at.to_rfc3339(),
+        alert.rule,
+        alert.message,
+        alert.event.timestamp.to_rfc3339(),
+        alert.event.event_id,
+        alert.event.provider_name,
+        alert.event.computer,
+    )
+}
+
+/// Append the alert to `log_path` (if any) and run its command (if any)
+/// with the event JSON on stdin. Blocking; call from a worker thread.
+pub fn dispatch(alert: &FiredAlert, log_path: Option<&Path>) {
+    if let Some(path) = log_path {
+        let written = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| writeln!(f, "{}", format_log_line(alert)));
+        if let Err(e) = written {
+            tracing::warn!("Failed to write alert log {}: {}", path.display(), e);
+        }
+    }
+
+    let command = alert.command.trim();
+    if command.is_empty() {
+        return;
+    }
+    if let Err(e) = run_command(command, &alert.event) {
+        tracing::warn!("Alert command for '{}' failed: {}", alert.rule, e);
+    }
+}
+
+/// Run `command` through the system shell, writing `event` as JSON to its
+/// stdin, and wait for it to exit.
+fn run_command(command: &str, event: &EventRecord) -> std::io::Result<()> {
+    let mut cmd = if cfg!(windows) {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", command]);
+        c
+    } else {
+        let mut c = std::process::Command::new("sh");
+        c.args(["-c", command]);
+        c
+    };
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console for every alert.
+        cmd.creation_flags(0x0800_0000);
+    }
+    let mut child = cmd
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_vec(event).map_err(std::io::Error::other)?;
+        // A command that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(&json);
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        tracing::debug!("Alert command exited with {}", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filter::FilterState;
+
+    fn event(id: u32, secs: i64) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level: 0,
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            timestamp: EventRecord::test_time(secs),
+            computer: "DC01".into(),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn presets() -> Vec<CompoundPreset> {
+        let mut f = FilterState {
+            event_id_input: "4625".into(),
+            ..Default::default()
+        };
+        f.parse_event_ids();
+        vec![CompoundPreset::from_filters("failures", &f, &[])]
+    }
+
+    #[test]
+    fn any_match_fires_once_per_batch() {
+        let rules = [AlertRule::new("fail", "failures")];
+        let mut m = AlertMonitor::default();
+        let fired = m.evaluate(
+            &rules,
+            &presets(),
+            &[event(4624, 0), event(4625, 1), event(4625, 2)],
+        );
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].event.timestamp, event(0, 1).timestamp);
+        assert!(fired[0].message.starts_with("2 new events"));
+        assert!(m.evaluate(&rules, &presets(), &[event(4624, 3)]).is_empty());
+    }
+
+    #[test]
+    fn threshold_uses_sliding_window_across_batches() {
+        let mut rule = AlertRule::new("burst", "failures");
+        rule.trigger = AlertTrigger::Threshold {
+            count: 2,
+            window_minutes: 1,
+        };
+        let rules = [rule];
+        let mut m = AlertMonitor::default();
+        assert!(m
+            .evaluate(&rules, &presets(), &[event(4625, 0), event(4625, 10)])
+            .is_empty());
+        // Third match within a minute: more than 2 -> fire.
+        let fired = m.evaluate(&rules, &presets(), &[event(4625, 20)]);
+        assert_eq!(fired.len(), 1);
+        // Window restarted; spread-out events never exceed the count.
+        assert!(m
+            .evaluate(
+                &rules,
+                &presets(),
+                &[event(4625, 100), event(4625, 200), event(4625, 300)]
+            )
+            .is_empty());
+    }
+
+    #[test]
+    fn disabled_and_orphaned_rules_are_skipped() {
+        let mut disabled = AlertRule::new("a", "failures");
+        disabled.enabled = false;
+        let orphan = AlertRule::new("b", "deleted preset");
+        let mut m = AlertMonitor::default();
+        assert!(m
+            .evaluate(&[disabled, orphan], &presets(), &[event(4625, 0)])
+            .is_empty());
+    }
+
+    #[test]
+    fn same_name_rules_keep_separate_windows_and_edits_restart_them() {
+        let mut rule = AlertRule::new("burst", "failures");
+        rule.trigger = AlertTrigger::Threshold {
+            count: 4,
+            window_minutes: 1,
+        };
+        let mut m = AlertMonitor::default();
+        // Three matches each: neither copy exceeds 4 on its own.
+        let batch = [event(4625, 0), event(4625, 10), event(4625, 20)];
+        assert!(m
+            .evaluate(&[rule.clone(), rule.clone()], &presets(), &batch)
+            .is_empty());
+        // Lowering the count is an edit: the earlier matches are gone.
+        rule.trigger = AlertTrigger::Threshold {
+            count: 1,
+            window_minutes: 1,
+        };
+        assert!(m
+            .evaluate(&[rule], &presets(), &[event(4625, 30)])
+            .is_empty());
+    }
+
+    #[test]
+    fn missing_optional_fields_use_defaults() {
+        let rule: AlertRule = serde_json::from_str(r#"{"name":"x","preset":"p"}"#).unwrap();
+        assert!(rule.enabled);
+        assert_eq!(rule.trigger, AlertTrigger::AnyMatch);
+        assert!(rule.command.is_empty());
+    }
+}
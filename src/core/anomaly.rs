@@ -0,0 +1,342 @@
+//! Statistical anomaly detection over the loaded events.
+//!
+//! Two kinds of findings:
+//! - **Volume anomalies**: the loaded window is cut into equal time
+//!   buckets and every series (all events, each provider, each provider +
+//!   event ID pair) gets a mean / standard deviation baseline; buckets
+//!   whose count is more than `threshold` deviations away are flagged.
+//! - **Rare events**: (provider, event ID) pairs that occur only a handful
+//!   of times, or that first appear in the second half of the window.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::{MAX_ANOMALIES, MAX_ANOMALY_BUCKETS};
+
+/// Candidate bucket widths in seconds, finest first.
+const BUCKET_WIDTHS: &[i64] = &[60, 300, 900, 3600, 6 * 3600, 86_400, 7 * 86_400];
+
+/// Fewer buckets than this give no usable baseline.
+pub(crate) const MIN_BASELINE_BUCKETS: usize = 8;
+
+/// Lowest deviation threshold offered; the report keeps every volume
+/// anomaly down to it.
+pub const MIN_THRESHOLD: f64 = 1.5;
+
+/// Buckets (spikes) or baselines (drops) below this count are ignored.
+const MIN_VOLUME: usize = 5;
+
+/// Pairs seen at most this many times are rare.
+pub const RARE_MAX_COUNT: usize = 3;
+
+/// A time series the baselines are computed for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Series {
+    All,
+    Provider(String),
+    EventId(String, u32),
+}
+
+impl Series {
+    pub fn label(&self) -> String {
+        match self {
+            Self::All => "All events".to_owned(),
+            Self::Provider(p) => p.clone(),
+            Self::EventId(p, id) => format!("{p} / {id}"),
+        }
+    }
+}
+
+/// A bucket whose volume deviates from its series' baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateAnomaly {
+    pub series: Series,
+    pub bucket_start: DateTime<Utc>,
+    pub count: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Deviations from the mean; negative for drops.
+    pub z: f64,
+    /// The series' events in the bucket (empty for drops to zero).
+    pub events: Vec<usize>,
+}
+
+/// A rare or newly appearing (provider, event ID) pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RareEvent {
+    pub provider: String,
+    pub event_id: u32,
+    pub first_seen: DateTime<Utc>,
+    /// First occurrence is in the second half of the window.
+    pub first_seen_late: bool,
+    /// Indices into the event list, in time order.
+    pub events: Vec<usize>,
+}
+
+/// Everything [`analyse`] found.
+#[derive(Debug, Clone, Default)]
+pub struct AnomalyReport {
+    /// Bucket width used for the volume baselines.
+    pub bucket: Duration,
+    pub bucket_count: usize,
+    /// Strongest deviation first, down to [`MIN_THRESHOLD`].
+    pub rate: Vec<RateAnomaly>,
+    /// Rarest first.
+    pub rare: Vec<RareEvent>,
+}
+
+impl AnomalyReport {
+    /// End of the bucket starting at `start`.
+    pub fn bucket_end(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        start + self.bucket
+    }
+
+    /// Volume anomalies at least `threshold` deviations from their
+    /// baseline, strongest first.
+    pub fn rate_above(&self, threshold: f64) -> &[RateAnomaly] {
+        let n = self.rate.partition_point(|a| a.z.abs() >= threshold);
+        &self.rate[..n]
+    }
+}
+
+/// Run both analyses over `events`. Volume anomalies are kept down to
+/// [`MIN_THRESHOLD`] deviations; [`AnomalyReport::rate_above`] narrows them
+/// to the threshold in use, so changing it needs no rebuild.
+pub fn analyse(events: &[EventRecord]) -> AnomalyReport {
+    let mut report = AnomalyReport::default();
+    let (Some(min), Some(max)) = (
+        events.iter().map(|e| e.timestamp).min(),
+        events.iter().map(|e| e.timestamp).max(),
+    ) else {
+        return report;
+    };
+    let span = (max - min).num_seconds().max(1);
+    let width = BUCKET_WIDTHS
+        .iter()
+        .copied()
+        .find(|w| span / w < MAX_ANOMALY_BUCKETS as i64)
+        .unwrap_or(BUCKET_WIDTHS[BUCKET_WIDTHS.len() - 1]);
+    let origin =
+        DateTime::from_timestamp(min.timestamp().div_euclid(width) * width, 0).unwrap_or(min);
+    let bucket_count = ((max - origin).num_seconds() / width + 1) as usize;
+    report.bucket = Duration::seconds(width);
+    report.bucket_count = bucket_count;
+
+    let mut order: Vec<usize> = (0..events.len()).collect();
+    order.sort_by_key(|&i| events[i].timestamp);
+
+    // (provider, event ID) → bucket → events (sparse, time ordered). The
+    // per-provider and total series are summed from these afterwards.
+    let mut pairs: HashMap<(&str, u32), BTreeMap<usize, Vec<usize>>> = HashMap::new();
+    for &i in &order {
+        let e = &events[i];
+        let b = ((e.timestamp - origin).num_seconds() / width) as usize;
+        pairs
+            .entry((e.provider_name.as_str(), e.event_id))
+            .or_default()
+            .entry(b)
+            .or_default()
+            .push(i);
+    }
+
+    if bucket_count >= MIN_BASELINE_BUCKETS {
+        let grid = Grid {
+            origin,
+            width,
+            count: bucket_count,
+        };
+        let mut total: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        let mut providers: HashMap<&str, BTreeMap<usize, Vec<usize>>> = HashMap::new();
+        for (&(provider, event_id), buckets) in &pairs {
+            for (&b, members) in buckets {
+                total.entry(b).or_default().extend(members);
+                providers
+                    .entry(provider)
+                    .or_default()
+                    .entry(b)
+                    .or_default()
+                    .extend(members);
+            }
+            let key = Series::EventId(provider.to_owned(), event_id);
+            flag_buckets(&mut report.rate, key, buckets, &grid);
+        }
+        let by_time = |i: &usize| (events[*i].timestamp, *i);
+        for (provider, mut buckets) in providers {
+            buckets.values_mut().for_each(|v| v.sort_by_key(by_time));
+            flag_buckets(
+                &mut report.rate,
+                Series::Provider(provider.to_owned()),
+                &buckets,
+                &grid,
+            );
+        }
+        total.values_mut().for_each(|v| v.sort_by_key(by_time));
+        flag_buckets(&mut report.rate, Series::All, &total, &grid);
+
+        report.rate.sort_by(|a, b| {
+            b.z.abs()
+                .total_cmp(&a.z.abs())
+                .then_with(|| a.bucket_start.cmp(&b.bucket_start))
+                .then_with(|| a.series.cmp(&b.series))
+        });
+        report.rate.truncate(MAX_ANOMALIES);
+    }
+
+    let midpoint = min + (max - min) / 2;
+    // New arrivals only mean something when there was a before.
+    let has_history = max > min;
+    report.rare = pairs
+        .into_iter()
+        .filter_map(|((provider, event_id), buckets)| {
+            let members: Vec<usize> = buckets.into_values().flatten().collect();
+            let first_seen = events[members[0]].timestamp;
+            let first_seen_late = has_history && first_seen > midpoint;
+            (members.len() <= RARE_MAX_COUNT || first_seen_late).then(|| RareEvent {
+                provider: provider.to_owned(),
+                event_id,
+                first_seen,
+                first_seen_late,
+                events: members,
+            })
+        })
+        .collect();
+    report.rare.sort_by(|a, b| {
+        a.events
+            .len()
+            .cmp(&b.events.len())
+            .then_with(|| b.first_seen.cmp(&a.first_seen))
+            .then_with(|| a.provider.cmp(&b.provider))
+    });
+    report.rare.truncate(MAX_ANOMALIES);
+    report
+}
+
+/// The time buckets the loaded window is cut into.
+struct Grid {
+    origin: DateTime<Utc>,
+    /// Bucket width in seconds.
+    width: i64,
+    count: usize,
+}
+
+impl Grid {
+    fn start(&self, bucket: usize) -> DateTime<Utc> {
+        self.origin + Duration::seconds(bucket as i64 * self.width)
+    }
+}
+
+/// Push the buckets of one series that deviate at least [`MIN_THRESHOLD`]
+/// from its baseline.
+fn flag_buckets(
+    out: &mut Vec<RateAnomaly>,
+    key: Series,
+    buckets: &BTreeMap<usize, Vec<usize>>,
+    grid: &Grid,
+) {
+    let n = grid.count as f64;
+    let total: usize = buckets.values().map(Vec::len).sum();
+    let mean = total as f64 / n;
+    let sq: f64 = buckets.values().map(|v| (v.len() as f64).powi(2)).sum();
+    // Floor the deviation so near-constant series don't flag noise.
+    let std_dev = (sq / n - mean * mean).max(0.0).sqrt().max(1.0);
+    for (&b, members) in buckets {
+        let z = (members.len() as f64 - mean) / std_dev;
+        if z >= MIN_THRESHOLD && members.len() >= MIN_VOLUME {
+            out.push(RateAnomaly {
+                series: key.clone(),
+                bucket_start: grid.start(b),
+                count: members.len(),
+                mean,
+                std_dev,
+                z,
+                events: members.clone(),
+            });
+        }
+    }
+    if mean < MIN_VOLUME as f64 {
+        return;
+    }
+    for b in 0..grid.count {
+        let count = buckets.get(&b).map_or(0, Vec::len);
+        let z = (count as f64 - mean) / std_dev;
+        if z <= -MIN_THRESHOLD {
+            out.push(RateAnomaly {
+                series: key.clone(),
+                bucket_start: grid.start(b),
+                count,
+                mean,
+                std_dev,
+                z,
+                events: buckets.get(&b).cloned().unwrap_or_default(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(provider: &str, id: u32, secs: i64) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: id,
+            provider_name: provider.into(),
+            timestamp: EventRecord::test_time(secs),
+            ..EventRecord::test_base()
+        }
+    }
+
+    /// A day of one event every half hour.
+    fn steady() -> Vec<EventRecord> {
+        (0..48).map(|i| event("Steady", 1, i * 1800)).collect()
+    }
+
+    #[test]
+    fn flags_volume_spike() {
+        let mut events = steady();
+        // 40 extra events in hour 10.
+        events.extend((0..40).map(|i| event("Noisy", 7, 10 * 3600 + i)));
+        let report = analyse(&events);
+        let rate = report.rate_above(3.0);
+        let top = &rate[0];
+        assert_eq!(
+            top.bucket_start.timestamp() % report.bucket.num_seconds(),
+            0
+        );
+        assert!(top.z >= 3.0);
+        assert!(top.count >= 40);
+        assert!(rate
+            .iter()
+            .any(|a| a.series == Series::EventId("Noisy".into(), 7)));
+        assert!(rate
+            .iter()
+            .all(|a| a.series != Series::Provider("Steady".into())));
+        // A higher threshold is a prefix of the same report.
+        assert!(report.rate_above(100.0).is_empty());
+        assert!(report.rate_above(MIN_THRESHOLD).len() >= rate.len());
+    }
+
+    #[test]
+    fn flags_rare_and_late_pairs() {
+        let mut events = steady();
+        events.push(event("Rare", 99, 100));
+        // Frequent, but only from hour 20 on.
+        events.extend((0..10).map(|i| event("Late", 5, 20 * 3600 + i * 60)));
+        let report = analyse(&events);
+        assert_eq!(report.rare.len(), 2);
+        assert_eq!(report.rare[0].provider, "Rare");
+        assert!(!report.rare[0].first_seen_late);
+        assert_eq!(report.rare[1].provider, "Late");
+        assert!(report.rare[1].first_seen_late);
+        assert_eq!(report.rare[1].events.len(), 10);
+    }
+
+    #[test]
+    fn empty_input_is_empty_report() {
+        let report = analyse(&[]);
+        assert!(report.rate.is_empty() && report.rare.is_empty());
+    }
+}
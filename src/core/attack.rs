@@ -0,0 +1,512 @@
+//! MITRE ATT&CK tagging.
+//!
+//! Techniques reach events two ways: through the `attack.tNNNN` tags of
+//! the detection rules (built-in, Sigma, correlation) that matched them,
+//! and through a small built-in knowledge base of event IDs that indicate
+//! a technique on their own (log cleared, service installed, ...).
+//! [`AttackMap`] collects both into per-technique event lists and the
+//! tactic → technique layout shown in the matrix view.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::core::event_record::EventRecord;
+
+/// An ATT&CK Enterprise tactic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tactic {
+    pub id: &'static str,
+    /// Sigma tag spelling, e.g. `credential_access`.
+    pub short: &'static str,
+    pub name: &'static str,
+}
+
+/// Enterprise tactics in matrix (kill-chain) order.
+pub const TACTICS: &[Tactic] = &[
+    Tactic {
+        id: "TA0043",
+        short: "reconnaissance",
+        name: "Reconnaissance",
+    },
+    Tactic {
+        id: "TA0042",
+        short: "resource_development",
+        name: "Resource Development",
+    },
+    Tactic {
+        id: "TA0001",
+        short: "initial_access",
+        name: "Initial Access",
+    },
+    Tactic {
+        id: "TA0002",
+        short: "execution",
+        name: "Execution",
+    },
+    Tactic {
+        id: "TA0003",
+        short: "persistence",
+        name: "Persistence",
+    },
+    Tactic {
+        id: "TA0004",
+        short: "privilege_escalation",
+        name: "Privilege Escalation",
+    },
+    Tactic {
+        id: "TA0005",
+        short: "defense_evasion",
+        name: "Defense Evasion",
+    },
+    Tactic {
+        id: "TA0006",
+        short: "credential_access",
+        name: "Credential Access",
+    },
+    Tactic {
+        id: "TA0007",
+        short: "discovery",
+        name: "Discovery",
+    },
+    Tactic {
+        id: "TA0008",
+        short: "lateral_movement",
+        name: "Lateral Movement",
+    },
+    Tactic {
+        id: "TA0009",
+        short: "collection",
+        name: "Collection",
+    },
+    Tactic {
+        id: "TA0011",
+        short: "command_and_control",
+        name: "Command and Control",
+    },
+    Tactic {
+        id: "TA0010",
+        short: "exfiltration",
+        name: "Exfiltration",
+    },
+    Tactic {
+        id: "TA0040",
+        short: "impact",
+        name: "Impact",
+    },
+];
+
+/// Pseudo-tactic for techniques whose tactic is unknown.
+pub const UNMAPPED_TACTIC: &str = "unmapped";
+
+/// `(technique ID, name, tactics)` for techniques commonly seen in Windows
+/// event logs. Sub-techniques not listed fall back to their parent.
+const TECHNIQUES: &[(&str, &str, &[&str])] = &[
+    ("T1003", "OS Credential Dumping", &["credential_access"]),
+    ("T1003.001", "LSASS Memory", &["credential_access"]),
+    ("T1021", "Remote Services", &["lateral_movement"]),
+    (
+        "T1021.001",
+        "Remote Desktop Protocol",
+        &["lateral_movement"],
+    ),
+    (
+        "T1021.002",
+        "SMB/Windows Admin Shares",
+        &["lateral_movement"],
+    ),
+    (
+        "T1021.006",
+        "Windows Remote Management",
+        &["lateral_movement"],
+    ),
+    (
+        "T1047",
+        "Windows Management Instrumentation",
+        &["execution"],
+    ),
+    (
+        "T1053",
+        "Scheduled Task/Job",
+        &["execution", "persistence", "privilege_escalation"],
+    ),
+    (
+        "T1053.005",
+        "Scheduled Task",
+        &["execution", "persistence", "privilege_escalation"],
+    ),
+    (
+        "T1055",
+        "Process Injection",
+        &["defense_evasion", "privilege_escalation"],
+    ),
+    ("T1059", "Command and Scripting Interpreter", &["execution"]),
+    ("T1059.001", "PowerShell", &["execution"]),
+    ("T1059.003", "Windows Command Shell", &["execution"]),
+    ("T1070", "Indicator Removal", &["defense_evasion"]),
+    (
+        "T1070.001",
+        "Clear Windows Event Logs",
+        &["defense_evasion"],
+    ),
+    (
+        "T1078",
+        "Valid Accounts",
+        &[
+            "defense_evasion",
+            "persistence",
+            "privilege_escalation",
+            "initial_access",
+        ],
+    ),
+    ("T1087", "Account Discovery", &["discovery"]),
+    (
+        "T1098",
+        "Account Manipulation",
+        &["persistence", "privilege_escalation"],
+    ),
+    ("T1105", "Ingress Tool Transfer", &["command_and_control"]),
+    ("T1110", "Brute Force", &["credential_access"]),
+    ("T1110.001", "Password Guessing", &["credential_access"]),
+    ("T1110.003", "Password Spraying", &["credential_access"]),
+    ("T1112", "Modify Registry", &["defense_evasion"]),
+    ("T1136", "Create Account", &["persistence"]),
+    ("T1136.001", "Local Account", &["persistence"]),
+    ("T1136.002", "Domain Account", &["persistence"]),
+    (
+        "T1218",
+        "System Binary Proxy Execution",
+        &["defense_evasion"],
+    ),
+    (
+        "T1543",
+        "Create or Modify System Process",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1543.003",
+        "Windows Service",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1546",
+        "Event Triggered Execution",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1546.003",
+        "WMI Event Subscription",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1547",
+        "Boot or Logon Autostart Execution",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1547.001",
+        "Registry Run Keys / Startup Folder",
+        &["persistence", "privilege_escalation"],
+    ),
+    (
+        "T1550",
+        "Use Alternate Authentication Material",
+        &["defense_evasion", "lateral_movement"],
+    ),
+    (
+        "T1550.002",
+        "Pass the Hash",
+        &["defense_evasion", "lateral_movement"],
+    ),
+    (
+        "T1558",
+        "Steal or Forge Kerberos Tickets",
+        &["credential_access"],
+    ),
+    ("T1558.003", "Kerberoasting", &["credential_access"]),
+    ("T1562", "Impair Defenses", &["defense_evasion"]),
+    ("T1562.001", "Disable or Modify Tools", &["defense_evasion"]),
+    (
+        "T1562.002",
+        "Disable Windows Event Logging",
+        &["defense_evasion"],
+    ),
+    ("T1569", "System Services", &["execution"]),
+    ("T1569.002", "Service Execution", &["execution"]),
+    ("T1570", "Lateral Tool Transfer", &["lateral_movement"]),
+    ("T1204", "User Execution", &["execution"]),
+    ("T1486", "Data Encrypted for Impact", &["impact"]),
+    ("T1490", "Inhibit System Recovery", &["impact"]),
+    ("T1489", "Service Stop", &["impact"]),
+    ("T1531", "Account Access Removal", &["impact"]),
+];
+
+/// Built-in knowledge base: `(channel, event ID, technique)` for events
+/// that indicate a technique by themselves.
+const EVENT_TECHNIQUES: &[(&str, u32, &str)] = &[
+    ("Security", 1102, "T1070.001"),
+    ("System", 104, "T1070.001"),
+    ("Security", 4719, "T1562.002"),
+    ("Security", 4720, "T1136.001"),
+    ("Security", 4724, "T1098"),
+    ("Security", 4728, "T1098"),
+    ("Security", 4732, "T1098"),
+    ("Security", 4756, "T1098"),
+    ("Security", 4625, "T1110"),
+    ("Security", 4771, "T1110"),
+    ("Security", 4697, "T1543.003"),
+    ("System", 7045, "T1543.003"),
+    ("Security", 4698, "T1053.005"),
+    (
+        "Microsoft-Windows-TaskScheduler/Operational",
+        106,
+        "T1053.005",
+    ),
+    (
+        "Microsoft-Windows-PowerShell/Operational",
+        4104,
+        "T1059.001",
+    ),
+    (
+        "Microsoft-Windows-WMI-Activity/Operational",
+        5861,
+        "T1546.003",
+    ),
+    (
+        "Microsoft-Windows-Windows Defender/Operational",
+        5001,
+        "T1562.001",
+    ),
+    ("Microsoft-Windows-Sysmon/Operational", 8, "T1055"),
+];
+
+/// Name of a technique, falling back to the parent of an unknown
+/// sub-technique.
+pub fn technique_name(id: &str) -> Option<&'static str> {
+    lookup(id).map(|(_, name, _)| *name)
+}
+
+/// Tactics (Sigma spelling) a technique belongs to; empty if unknown.
+pub fn technique_tactics(id: &str) -> &'static [&'static str] {
+    lookup(id).map(|(_, _, t)| *t).unwrap_or_default()
+}
+
+fn lookup(id: &str) -> Option<&'static (&'static str, &'static str, &'static [&'static str])> {
+    let find = |id: &str| {
+        TECHNIQUES
+            .iter()
+            .find(|(t, _, _)| t.eq_ignore_ascii_case(id))
+    };
+    find(id).or_else(|| find(id.split('.').next()?))
+}
+
+/// Split rule ATT&CK tags (already stripped of `attack.`) into technique
+/// IDs (upper-cased, e.g. `T1059.001`) and tactic short names. Group and
+/// software tags (`g0016`, `s0002`) are ignored.
+pub fn parse_tags<'a>(tags: impl IntoIterator<Item = &'a str>) -> (Vec<String>, Vec<String>) {
+    let mut techniques = Vec::new();
+    let mut tactics = Vec::new();
+    for tag in tags {
+        let lower = tag.to_ascii_lowercase();
+        let is_technique = lower.starts_with('t')
+            && lower[1..]
+                .split('.')
+                .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+        if is_technique {
+            techniques.push(lower.to_ascii_uppercase());
+        } else if TACTICS.iter().any(|t| t.short == lower) {
+            tactics.push(lower);
+        }
+    }
+    (techniques, tactics)
+}
+
+/// Techniques implied by `event` alone (built-in knowledge base).
+pub fn event_techniques(event: &EventRecord) -> impl Iterator<Item = &'static str> + '_ {
+    EVENT_TECHNIQUES
+        .iter()
+        .filter(|(channel, id, _)| *id == event.event_id && event.channel == *channel)
+        .map(|(_, _, t)| *t)
+}
+
+/// Techniques observed in the current event list.
+#[derive(Debug, Default)]
+pub struct AttackMap {
+    /// Technique ID → sorted, deduplicated event indices.
+    pub techniques: BTreeMap<String, Vec<usize>>,
+    /// Tactic short name (or [`UNMAPPED_TACTIC`]) → techniques seen under it.
+    pub tactics: BTreeMap<String, BTreeSet<String>>,
+    by_event: HashMap<usize, BTreeSet<String>>,
+}
+
+impl AttackMap {
+    /// Build the map from rule hits and the knowledge base.
+    ///
+    /// `rule_hits` yields each rule's ATT&CK tags (without `attack.`) with
+    /// the indices of the events it matched. A known technique appears
+    /// under the rule's tactic tags that the knowledge base also lists
+    /// for it (all of its knowledge-base tactics when none match); an
+    /// unknown one appears under the rule's tactic tags as given.
+    pub fn build<'a, T>(
+        events: &[EventRecord],
+        rule_hits: impl IntoIterator<Item = (T, &'a [usize])>,
+    ) -> Self
+    where
+        T: IntoIterator<Item = &'a str>,
+    {
+        let mut map = Self::default();
+        for (tags, hits) in rule_hits {
+            if hits.is_empty() {
+                continue;
+            }
+            let (techniques, tactics) = parse_tags(tags);
+            for technique in techniques {
+                map.place(&technique, &tactics);
+                for &i in hits {
+                    map.tag(&technique, i);
+                }
+            }
+        }
+        for (i, event) in events.iter().enumerate() {
+            for technique in event_techniques(event) {
+                map.place(technique, &[]);
+                map.tag(technique, i);
+            }
+        }
+        for events in map.techniques.values_mut() {
+            events.sort_unstable();
+            events.dedup();
+        }
+        map
+    }
+
+    fn place(&mut self, technique: &str, rule_tactics: &[String]) {
+        // A rule tagged `persistence` + `execution` + `t1569.002` must not
+        // list Service Execution under Persistence.
+        let kb = technique_tactics(technique);
+        let mut tactics: Vec<&str> = if kb.is_empty() {
+            rule_tactics.iter().map(String::as_str).collect()
+        } else {
+            rule_tactics
+                .iter()
+                .map(String::as_str)
+                .filter(|t| kb.contains(t))
+                .collect()
+        };
+        if tactics.is_empty() {
+            tactics = kb.to_vec();
+        }
+        if tactics.is_empty() {
+            tactics.push(UNMAPPED_TACTIC);
+        }
+        for tactic in tactics {
+            self.tactics
+                .entry(tactic.to_owned())
+                .or_default()
+                .insert(technique.to_owned());
+        }
+    }
+
+    fn tag(&mut self, technique: &str, event: usize) {
+        self.techniques
+            .entry(technique.to_owned())
+            .or_default()
+            .push(event);
+        self.by_event
+            .entry(event)
+            .or_default()
+            .insert(technique.to_owned());
+    }
+
+    /// Techniques attached to event `idx`, sorted.
+    pub fn event_tags(&self, idx: usize) -> Vec<String> {
+        self.by_event
+            .get(&idx)
+            .map(|t| t.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of events carrying at least one technique.
+    pub fn tagged_event_count(&self) -> usize {
+        self.by_event.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(channel: &str, id: u32) -> EventRecord {
+        EventRecord {
+            channel: channel.into(),
+            event_id: id,
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn tags_split_into_techniques_and_tactics() {
+        let (techniques, tactics) = parse_tags([
+            "execution",
+            "t1059.001",
+            "g0016",
+            "T1110",
+            "car.2016-04-002",
+        ]);
+        assert_eq!(techniques, vec!["T1059.001", "T1110"]);
+        assert_eq!(tactics, vec!["execution"]);
+    }
+
+    #[test]
+    fn unknown_sub_technique_falls_back_to_parent() {
+        assert_eq!(
+            technique_name("T1059.999"),
+            Some("Command and Scripting Interpreter")
+        );
+        assert_eq!(technique_tactics("t1110.003"), &["credential_access"]);
+        assert!(technique_name("T9999").is_none());
+    }
+
+    #[test]
+    fn map_merges_rule_hits_and_knowledge_base() {
+        let events = vec![
+            event("Security", 1102),
+            event("Security", 4688),
+            event("System", 7045),
+        ];
+        let rule_tags = ["defense_evasion", "t1070.001"];
+        let custom = ["t1234"];
+        let map = AttackMap::build(
+            &events,
+            [
+                (rule_tags.to_vec(), &[0usize][..]),
+                (custom.to_vec(), &[1, 2][..]),
+            ],
+        );
+        // Rule and knowledge base agree on event 0: tagged once.
+        assert_eq!(map.techniques["T1070.001"], vec![0]);
+        assert_eq!(map.techniques["T1543.003"], vec![2]);
+        assert_eq!(map.event_tags(2), vec!["T1234", "T1543.003"]);
+        assert!(map.tactics["persistence"].contains("T1543.003"));
+        assert!(map.tactics[UNMAPPED_TACTIC].contains("T1234"));
+        assert_eq!(map.tagged_event_count(), 3);
+    }
+
+    #[test]
+    fn known_techniques_only_appear_under_their_own_tactics() {
+        let events = vec![event("Application", 1)];
+        let psexec = ["persistence", "execution", "t1569.002", "t1543.003"];
+        let custom = ["impact", "t4242"];
+        let map = AttackMap::build(
+            &events,
+            [
+                (psexec.to_vec(), &[0usize][..]),
+                (custom.to_vec(), &[0][..]),
+            ],
+        );
+        assert!(map.tactics["execution"].contains("T1569.002"));
+        assert!(!map.tactics["persistence"].contains("T1569.002"));
+        assert!(map.tactics["persistence"].contains("T1543.003"));
+        assert!(!map.tactics["execution"].contains("T1543.003"));
+        // Unknown technique: the rule's tactics are all there is.
+        assert!(map.tactics["impact"].contains("T4242"));
+    }
+}
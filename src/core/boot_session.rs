@@ -0,0 +1,340 @@
+//! Boot session segmentation from System log boot / shutdown markers.
+//!
+//! Boot markers (Kernel-General 12, EventLog 6005 / 6009) open a session;
+//! shutdown markers (Kernel-General 13, EventLog 6006) close it cleanly.
+//! A session that ends with a new boot but no shutdown marker, or that a
+//! later 6008 / Kernel-Power 41 reports as unexpected, is dirty. User32
+//! 1074 supplies the initiator and reason of planned shutdowns, 1001 and
+//! Kernel-Power 41 the bugcheck codes of crashes, and 6013 the uptime
+//! Windows itself reported.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::{BOOT_MARKER_WINDOW_SECS, MAX_BOOT_SESSIONS};
+
+/// How a boot session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownKind {
+    /// Orderly shutdown markers were logged.
+    Clean,
+    /// Power loss, crash or hang: no shutdown markers, or reported as
+    /// unexpected at the next boot.
+    Dirty,
+    /// Latest session with no shutdown seen (still running when the log
+    /// was captured).
+    Running,
+}
+
+impl ShutdownKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Clean => "Clean",
+            Self::Dirty => "Dirty",
+            Self::Running => "Running",
+        }
+    }
+}
+
+/// Details of a User32 1074 planned shutdown or restart.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlannedShutdown {
+    /// Initiating process (`C:\Windows\system32\winlogon.exe (WS01)`).
+    pub process: String,
+    pub user: String,
+    pub reason: String,
+    /// `restart`, `power off`, ...
+    pub kind: String,
+    pub comment: String,
+}
+
+/// One boot-to-shutdown span of a computer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootSession {
+    pub computer: String,
+    /// First boot marker; `None` when the log starts mid-session.
+    pub start: Option<DateTime<Utc>>,
+    /// Last shutdown marker, if any.
+    pub end: Option<DateTime<Utc>>,
+    /// Last System event seen in the session.
+    pub last_seen: DateTime<Utc>,
+    /// Start of the following session; the session owns every event of
+    /// its computer before this.
+    pub next_boot: Option<DateTime<Utc>>,
+    pub shutdown: ShutdownKind,
+    pub planned: Option<PlannedShutdown>,
+    /// Bugcheck codes (`0x0000009f`) reported for this session's crash.
+    pub bugchecks: Vec<String>,
+    /// Uptime from the last 6013 of the session.
+    pub reported_uptime: Option<Duration>,
+    /// Indices of the marker events, in time order.
+    pub markers: Vec<usize>,
+}
+
+impl BootSession {
+    fn new(computer: &str, start: Option<DateTime<Utc>>, at: DateTime<Utc>) -> Self {
+        Self {
+            computer: computer.to_owned(),
+            start,
+            end: None,
+            last_seen: at,
+            next_boot: None,
+            shutdown: ShutdownKind::Running,
+            planned: None,
+            bugchecks: Vec::new(),
+            reported_uptime: None,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Boot to shutdown (or to the last event seen for dirty and running
+    /// sessions).
+    pub fn uptime(&self) -> Option<Duration> {
+        Some(self.end.unwrap_or(self.last_seen) - self.start?)
+    }
+
+    /// Whether `event` falls inside this session.
+    pub fn contains(&self, event: &EventRecord) -> bool {
+        event.computer.eq_ignore_ascii_case(&self.computer)
+            && self.start.is_none_or(|s| event.timestamp >= s)
+            && self.next_boot.is_none_or(|n| event.timestamp < n)
+    }
+}
+
+/// The boot / shutdown marker an event represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    Boot,
+    Shutdown,
+    /// Reported at boot: the previous shutdown was unexpected.
+    Unexpected,
+    Planned,
+    Bugcheck,
+    Uptime,
+}
+
+fn marker(e: &EventRecord) -> Option<Marker> {
+    if e.channel != "System" {
+        return None;
+    }
+    let provider = e.provider_name.as_str();
+    Some(match (provider, e.event_id) {
+        ("Microsoft-Windows-Kernel-General", 12) => Marker::Boot,
+        ("Microsoft-Windows-Kernel-General", 13) => Marker::Shutdown,
+        ("Microsoft-Windows-Kernel-Power", 41) => Marker::Unexpected,
+        ("User32", 1074) => Marker::Planned,
+        ("Microsoft-Windows-WER-SystemErrorReporting" | "BugCheck", 1001) => Marker::Bugcheck,
+        ("EventLog", 6005 | 6009) => Marker::Boot,
+        ("EventLog", 6006) => Marker::Shutdown,
+        ("EventLog", 6008) => Marker::Unexpected,
+        ("EventLog", 6013) => Marker::Uptime,
+        _ => return None,
+    })
+}
+
+/// First token of a 1001 `param1` (`0x0000009f (0x3, ...)`).
+fn bugcheck_from_report(e: &EventRecord) -> Option<String> {
+    let code = e.data("param1")?.split_whitespace().next()?;
+    Some(code.to_ascii_lowercase())
+}
+
+/// Kernel-Power 41 `BugcheckCode` (decimal; 0 when there was none).
+fn bugcheck_from_power(e: &EventRecord) -> Option<String> {
+    let code: u64 = e.data("BugcheckCode")?.parse().ok()?;
+    (code != 0).then(|| format!("0x{code:08x}"))
+}
+
+/// Split the System log of every computer in `events` (any order) into
+/// boot sessions.
+///
+/// Returns sessions newest first, capped at [`MAX_BOOT_SESSIONS`].
+pub fn segment(events: &[EventRecord]) -> Vec<BootSession> {
+    let mut order: Vec<usize> = (0..events.len())
+        .filter(|&i| events[i].channel == "System")
+        .collect();
+    order.sort_by_key(|&i| events[i].timestamp);
+
+    let window = Duration::seconds(BOOT_MARKER_WINDOW_SECS);
+    let mut done: Vec<BootSession> = Vec::new();
+    // Lower-cased computer → (finished sessions, current session).
+    let mut per_computer: HashMap<String, (Vec<BootSession>, Option<BootSession>)> = HashMap::new();
+
+    for i in order {
+        let e = &events[i];
+        let (finished, current) = per_computer
+            .entry(e.computer.to_ascii_lowercase())
+            .or_default();
+        let kind = marker(e);
+
+        if kind == Some(Marker::Boot) {
+            // 12, 6009 and 6005 all mark the same boot; only a boot marker
+            // well after the session started (or after a shutdown) opens a
+            // new one.
+            let same_boot = current.as_ref().is_some_and(|s| {
+                s.end.is_none() && s.start.is_some_and(|st| e.timestamp - st <= window)
+            });
+            if !same_boot {
+                if let Some(mut prev) = current.take() {
+                    if prev.end.is_none() {
+                        prev.shutdown = ShutdownKind::Dirty;
+                    }
+                    prev.next_boot = Some(e.timestamp);
+                    finished.push(prev);
+                }
+                *current = Some(BootSession::new(
+                    &e.computer,
+                    Some(e.timestamp),
+                    e.timestamp,
+                ));
+            }
+        }
+
+        let session =
+            current.get_or_insert_with(|| BootSession::new(&e.computer, None, e.timestamp));
+        session.last_seen = e.timestamp;
+        let Some(kind) = kind else {
+            continue;
+        };
+        session.markers.push(i);
+
+        match kind {
+            Marker::Boot => {}
+            Marker::Shutdown => {
+                session.end = Some(e.timestamp);
+                session.shutdown = ShutdownKind::Clean;
+            }
+            Marker::Planned => {
+                let field = |n: &str| e.data(n).unwrap_or_default().to_owned();
+                session.planned = Some(PlannedShutdown {
+                    process: field("param1"),
+                    reason: field("param3"),
+                    kind: field("param5"),
+                    comment: field("param6"),
+                    user: field("param7"),
+                });
+            }
+            Marker::Uptime => {
+                session.reported_uptime = e
+                    .data("Data_5")
+                    .and_then(|s| s.parse().ok())
+                    .map(Duration::seconds);
+            }
+            // Logged during the next boot about the previous session.
+            Marker::Unexpected | Marker::Bugcheck => {
+                let Some(prev) = finished.last_mut() else {
+                    continue;
+                };
+                prev.markers.push(i);
+                let code = if kind == Marker::Bugcheck {
+                    bugcheck_from_report(e)
+                } else {
+                    prev.shutdown = ShutdownKind::Dirty;
+                    bugcheck_from_power(e)
+                };
+                if let Some(code) = code.filter(|c| !prev.bugchecks.contains(c)) {
+                    prev.bugchecks.push(code);
+                }
+            }
+        }
+    }
+
+    for (finished, current) in per_computer.into_values() {
+        done.extend(finished);
+        done.extend(current);
+    }
+    done.sort_by_key(|s| std::cmp::Reverse(s.start.unwrap_or(s.last_seen)));
+    done.truncate(MAX_BOOT_SESSIONS);
+    done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(provider: &str, id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: id,
+            provider_name: provider.into(),
+            timestamp: EventRecord::test_time(secs),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    const KG: &str = "Microsoft-Windows-Kernel-General";
+
+    #[test]
+    fn splits_clean_and_dirty_sessions() {
+        let events = vec![
+            // Boot 1, planned restart.
+            event(KG, 12, 0, &[]),
+            event("EventLog", 6005, 20, &[]),
+            event("Service Control Manager", 7036, 100, &[]),
+            event(
+                "User32",
+                1074,
+                3600,
+                &[
+                    ("param1", r"C:\Windows\system32\wuauclt.exe (WS01)"),
+                    ("param3", "Operating System: Upgrade (Planned)"),
+                    ("param5", "restart"),
+                    ("param7", "NT AUTHORITY\\SYSTEM"),
+                ],
+            ),
+            event("EventLog", 6006, 3610, &[]),
+            event(KG, 13, 3615, &[]),
+            // Boot 2, crashes.
+            event(KG, 12, 3700, &[]),
+            event("EventLog", 6013, 7000, &[("Data_5", "3300")]),
+            // Boot 3 reports the crash of boot 2.
+            event(KG, 12, 9000, &[]),
+            event("EventLog", 6008, 9030, &[]),
+            event(
+                "Microsoft-Windows-Kernel-Power",
+                41,
+                9031,
+                &[("BugcheckCode", "159")],
+            ),
+            event(
+                "Microsoft-Windows-WER-SystemErrorReporting",
+                1001,
+                9060,
+                &[("param1", "0x0000009f (0x3, 0xffff)")],
+            ),
+        ];
+        let sessions = segment(&events);
+        assert_eq!(sessions.len(), 3);
+        let (third, second, first) = (&sessions[0], &sessions[1], &sessions[2]);
+
+        assert_eq!(first.shutdown, ShutdownKind::Clean);
+        assert_eq!(first.uptime(), Some(Duration::seconds(3615)));
+        let planned = first.planned.as_ref().unwrap();
+        assert_eq!(planned.kind, "restart");
+        assert_eq!(planned.user, "NT AUTHORITY\\SYSTEM");
+        assert!(first.contains(&events[2]));
+        assert!(!first.contains(&events[6]));
+
+        assert_eq!(second.shutdown, ShutdownKind::Dirty);
+        assert_eq!(second.bugchecks, vec!["0x0000009f"]);
+        assert_eq!(second.reported_uptime, Some(Duration::seconds(3300)));
+        assert_eq!(second.uptime(), Some(Duration::seconds(3300)));
+
+        assert_eq!(third.shutdown, ShutdownKind::Running);
+        assert!(third.contains(&events[11]));
+    }
+
+    #[test]
+    fn log_starting_mid_session_has_no_start() {
+        let sessions = segment(&[
+            event("Service Control Manager", 7036, 0, &[]),
+            event(KG, 13, 50, &[]),
+        ]);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].start, None);
+        assert_eq!(sessions[0].shutdown, ShutdownKind::Clean);
+        assert!(sessions[0].uptime().is_none());
+    }
+}
@@ -0,0 +1,579 @@
+//! Brute-force and password-spray detection from failed logons.
+//!
+//! Failures come from 4625 (logon failed), 4771 (Kerberos pre-auth
+//! failed) and 4776 (NTLM credential validation with a non-zero status).
+//! They are grouped by source (IP address, else workstation) and by target
+//! account, and scanned with a sliding window for *bursts* (many failures
+//! against one account) and *sprays* (one source failing against many
+//! accounts). A successful 4624 for the same account (bursts) or from the
+//! same source (sprays) shortly after a finding is flagged, since that is
+//! what turns noise into an incident.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_BRUTE_FORCE_FINDINGS;
+
+/// Security event IDs read as failed logons.
+pub const FAILURE_EVENT_IDS: &[u32] = &[4625, 4771, 4776];
+
+/// Sliding-window detection thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Window length in seconds; a success this long after a finding
+    /// still counts as following it.
+    pub window_secs: i64,
+    /// Failures against one account within the window for a burst.
+    pub burst: usize,
+    /// Distinct accounts failed by one source within the window for a
+    /// spray.
+    pub spray: usize,
+}
+
+impl Default for Thresholds {
+    /// Default: 10 failures or 5 accounts within 5 minutes.
+    fn default() -> Self {
+        Self {
+            window_secs: 300,
+            burst: 10,
+            spray: 5,
+        }
+    }
+}
+
+/// One failed authentication.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// Index into the event list.
+    pub event: usize,
+    pub time: DateTime<Utc>,
+    pub event_id: u32,
+    /// Target user name as logged.
+    pub account: String,
+    /// Source IP, else workstation name, else `(unknown)`.
+    pub source: String,
+    /// Status code as logged (`0xC000006D`, `0x18`, ...).
+    pub status: String,
+    /// SubStatus (4625 only) when it carries more detail than `status`.
+    pub sub_status: Option<String>,
+}
+
+impl Failure {
+    /// The most specific code and its description.
+    pub fn reason(&self) -> (&str, &'static str) {
+        let code = self.sub_status.as_deref().unwrap_or(&self.status);
+        (code, decode_status(self.event_id, code))
+    }
+}
+
+/// Failures sharing a source or an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureGroup {
+    pub key: String,
+    pub failures: usize,
+    /// Distinct accounts (for source groups) or sources (for account
+    /// groups).
+    pub distinct: usize,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    /// Most common failure reason: code and description.
+    pub top_reason: (String, &'static str),
+    /// Failure event indices, in time order.
+    pub events: Vec<usize>,
+}
+
+/// What a finding describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FindingKind {
+    /// Many failures against one account.
+    Burst,
+    /// One source failing against many accounts.
+    Spray,
+}
+
+impl FindingKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FindingKind::Burst => "Brute force",
+            FindingKind::Spray => "Password spray",
+        }
+    }
+}
+
+/// A stretch of time in which a burst or spray threshold was met.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// The account (bursts) or source (sprays).
+    pub key: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub failures: usize,
+    /// Distinct sources (bursts) or accounts (sprays) involved.
+    pub distinct: usize,
+    /// Failure event indices, in time order.
+    pub events: Vec<usize>,
+    /// A 4624 that followed the finding within the window.
+    pub success: Option<usize>,
+}
+
+/// The full failed-logon report.
+#[derive(Debug, Clone, Default)]
+pub struct BruteForceReport {
+    /// All failures, in time order.
+    pub failures: Vec<Failure>,
+    /// Most failures first.
+    pub by_source: Vec<FailureGroup>,
+    pub by_account: Vec<FailureGroup>,
+    /// Newest first, capped at [`MAX_BRUTE_FORCE_FINDINGS`].
+    pub findings: Vec<Finding>,
+    /// `(code, description, count)` over the failure reasons, most
+    /// frequent first.
+    pub reasons: Vec<(String, &'static str, usize)>,
+}
+
+/// Parse a status code such as `0xC000006A` or `0x18`.
+fn parse_code(code: &str) -> Option<u32> {
+    let hex = code
+        .trim()
+        .strip_prefix("0x")
+        .or_else(|| code.trim().strip_prefix("0X"))?;
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Describe a failure status code. 4771 carries Kerberos error codes;
+/// 4625 and 4776 carry NTSTATUS values.
+pub fn decode_status(event_id: u32, code: &str) -> &'static str {
+    let Some(value) = parse_code(code) else {
+        return "Unknown status";
+    };
+    if event_id == 4771 {
+        return match value {
+            0x6 => "Unknown user (KDC_ERR_C_PRINCIPAL_UNKNOWN)",
+            0x12 => "Account disabled, expired or locked out",
+            0x17 => "Password expired",
+            0x18 => "Wrong password (pre-authentication failed)",
+            0x25 => "Clock skew too great",
+            _ => "Kerberos error",
+        };
+    }
+    match value {
+        0xC000_0064 => "Unknown user name",
+        0xC000_006A => "Wrong password",
+        0xC000_006C => "Password policy not met",
+        0xC000_006D => "Bad user name or password",
+        0xC000_006E => "Account restriction",
+        0xC000_006F => "Outside allowed logon hours",
+        0xC000_0070 => "Workstation not allowed",
+        0xC000_0071 => "Password expired",
+        0xC000_0072 => "Account disabled",
+        0xC000_0133 => "Clock out of sync with DC",
+        0xC000_015B => "Logon type not granted",
+        0xC000_0192 => "NetLogon service not started",
+        0xC000_0193 => "Account expired",
+        0xC000_0224 => "Password must change at next logon",
+        0xC000_0234 => "Account locked out",
+        0xC000_0413 => "Blocked by authentication firewall",
+        _ => "Other failure",
+    }
+}
+
+/// Source recorded for failures with no IP or workstation.
+const UNKNOWN_SOURCE: &str = "(unknown)";
+
+/// Source of an authentication event: IP (IPv4-mapped prefix removed),
+/// else workstation, else [`UNKNOWN_SOURCE`].
+fn source_of(event: &EventRecord) -> String {
+    if let Some(ip) = event.recorded_data("IpAddress") {
+        return ip.strip_prefix("::ffff:").unwrap_or(ip).to_owned();
+    }
+    event
+        .recorded_data("WorkstationName")
+        .or_else(|| event.recorded_data("Workstation"))
+        .map_or_else(|| UNKNOWN_SOURCE.to_owned(), str::to_owned)
+}
+
+/// Read a failed logon from `event`, if it is one.
+fn failure(idx: usize, event: &EventRecord) -> Option<Failure> {
+    if event.channel != "Security" || !FAILURE_EVENT_IDS.contains(&event.event_id) {
+        return None;
+    }
+    let account = event.recorded_data("TargetUserName")?.to_owned();
+    let status = event
+        .recorded_data("Status")
+        .or_else(|| event.recorded_data("FailureCode"))
+        .unwrap_or("0x0")
+        .to_owned();
+    // 4776 logs every validation, successful ones with status 0.
+    if event.event_id == 4776 && parse_code(&status) == Some(0) {
+        return None;
+    }
+    let sub_status = event
+        .recorded_data("SubStatus")
+        .filter(|s| parse_code(s).is_some_and(|v| v != 0))
+        .map(str::to_owned);
+    Some(Failure {
+        event: idx,
+        time: event.timestamp,
+        event_id: event.event_id,
+        account,
+        source: source_of(event),
+        status,
+        sub_status,
+    })
+}
+
+/// Group `failures` (time ordered) by `key`, counting distinct `other`.
+fn group_by(
+    failures: &[Failure],
+    key: impl Fn(&Failure) -> &str,
+    other: impl Fn(&Failure) -> &str,
+) -> Vec<FailureGroup> {
+    struct Acc<'a> {
+        key: &'a str,
+        others: HashSet<String>,
+        reasons: HashMap<(&'a str, &'static str), usize>,
+        members: Vec<&'a Failure>,
+    }
+    let mut groups: HashMap<String, Acc<'_>> = HashMap::new();
+    for f in failures {
+        let acc = groups.entry(key(f).to_lowercase()).or_insert_with(|| Acc {
+            key: key(f),
+            others: HashSet::new(),
+            reasons: HashMap::new(),
+            members: Vec::new(),
+        });
+        acc.others.insert(other(f).to_lowercase());
+        *acc.reasons.entry(f.reason()).or_default() += 1;
+        acc.members.push(f);
+    }
+    let mut out: Vec<FailureGroup> = groups
+        .into_values()
+        .map(|acc| FailureGroup {
+            key: acc.key.to_owned(),
+            failures: acc.members.len(),
+            distinct: acc.others.len(),
+            first: acc.members[0].time,
+            last: acc.members[acc.members.len() - 1].time,
+            top_reason: acc
+                .reasons
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map_or((String::new(), ""), |((code, text), _)| {
+                    (code.to_owned(), text)
+                }),
+            events: acc.members.iter().map(|f| f.event).collect(),
+        })
+        .collect();
+    out.sort_by(|a, b| b.failures.cmp(&a.failures).then_with(|| a.key.cmp(&b.key)));
+    out
+}
+
+/// Merge the windows of `members` (time ordered) in which `measure` of
+/// the window reaches `threshold` into findings. `distinct_of` keys the
+/// distinct-value count a spray measures; bursts count failures and pass
+/// `None`.
+fn scan(
+    kind: FindingKind,
+    key: &str,
+    members: &[&Failure],
+    window: Duration,
+    threshold: usize,
+    distinct_of: Option<fn(&Failure) -> String>,
+) -> Vec<Finding> {
+    // Qualifying windows [left, right], merged when they overlap.
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    let mut in_window: HashMap<String, usize> = HashMap::new();
+    let mut left = 0;
+    for right in 0..members.len() {
+        if let Some(distinct_of) = distinct_of {
+            *in_window.entry(distinct_of(members[right])).or_default() += 1;
+        }
+        while members[right].time - members[left].time > window {
+            if let Some(distinct_of) = distinct_of {
+                let k = distinct_of(members[left]);
+                if let Some(n) = in_window.get_mut(&k) {
+                    *n -= 1;
+                    if *n == 0 {
+                        in_window.remove(&k);
+                    }
+                }
+            }
+            left += 1;
+        }
+        let measure = match kind {
+            FindingKind::Burst => right - left + 1,
+            FindingKind::Spray => in_window.len(),
+        };
+        if measure >= threshold {
+            match spans.last_mut() {
+                Some(last) if left <= last.1 => last.1 = right,
+                _ => spans.push((left, right)),
+            }
+        }
+    }
+
+    spans
+        .into_iter()
+        .map(|(l, r)| {
+            let part = &members[l..=r];
+            let distinct: HashSet<String> = part
+                .iter()
+                .map(|f| match kind {
+                    FindingKind::Burst => f.source.to_lowercase(),
+                    FindingKind::Spray => f.account.to_lowercase(),
+                })
+                .collect();
+            Finding {
+                kind,
+                key: key.to_owned(),
+                start: part[0].time,
+                end: part[part.len() - 1].time,
+                failures: part.len(),
+                distinct: distinct.len(),
+                events: part.iter().map(|f| f.event).collect(),
+                success: None,
+            }
+        })
+        .collect()
+}
+
+/// Build the failed-logon report over `events` (any order).
+pub fn analyse(events: &[EventRecord], thresholds: Thresholds) -> BruteForceReport {
+    let mut failures: Vec<Failure> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| failure(i, e))
+        .collect();
+    failures.sort_by_key(|f| (f.time, f.event));
+
+    // Successful logons as (time, account, source, index), time ordered.
+    let mut successes: Vec<(DateTime<Utc>, String, String, usize)> = events
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| e.channel == "Security" && e.event_id == 4624)
+        .filter_map(|(i, e)| {
+            let account = e.recorded_data("TargetUserName")?.to_lowercase();
+            Some((e.timestamp, account, source_of(e).to_lowercase(), i))
+        })
+        .collect();
+    successes.sort();
+
+    let window = Duration::seconds(thresholds.window_secs.max(1));
+    let mut by_account: HashMap<String, Vec<&Failure>> = HashMap::new();
+    let mut by_source: HashMap<String, Vec<&Failure>> = HashMap::new();
+    for f in &failures {
+        by_account
+            .entry(f.account.to_lowercase())
+            .or_default()
+            .push(f);
+        // Sourceless failures from unrelated clients would look like a
+        // spray from one "(unknown)" host.
+        if f.source != UNKNOWN_SOURCE {
+            by_source
+                .entry(f.source.to_lowercase())
+                .or_default()
+                .push(f);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for members in by_account.values() {
+        findings.extend(scan(
+            FindingKind::Burst,
+            &members[0].account,
+            members,
+            window,
+            thresholds.burst.max(1),
+            None,
+        ));
+    }
+    for members in by_source.values() {
+        findings.extend(scan(
+            FindingKind::Spray,
+            &members[0].source,
+            members,
+            window,
+            thresholds.spray.max(1),
+            Some(|f| f.account.to_lowercase()),
+        ));
+    }
+
+    for finding in &mut findings {
+        let (account, source) = match finding.kind {
+            FindingKind::Burst => (Some(finding.key.to_lowercase()), None),
+            FindingKind::Spray => (None, Some(finding.key.to_lowercase())),
+        };
+        // Only a success after the last failure follows the finding.
+        let from = successes.partition_point(|s| s.0 < finding.end);
+        finding.success = successes[from..]
+            .iter()
+            .take_while(|s| s.0 <= finding.end + window)
+            .find(|s| {
+                account.as_ref().is_none_or(|a| *a == s.1)
+                    && source.as_ref().is_none_or(|src| *src == s.2)
+            })
+            .map(|s| s.3);
+    }
+    findings.sort_by(|a, b| b.start.cmp(&a.start).then_with(|| a.key.cmp(&b.key)));
+    findings.truncate(MAX_BRUTE_FORCE_FINDINGS);
+
+    let mut reasons: HashMap<(String, &'static str), usize> = HashMap::new();
+    for f in &failures {
+        let (code, text) = f.reason();
+        *reasons
+            .entry((code.to_uppercase().replace("0X", "0x"), text))
+            .or_default() += 1;
+    }
+    let mut reasons: Vec<(String, &'static str, usize)> =
+        reasons.into_iter().map(|((c, t), n)| (c, t, n)).collect();
+    reasons.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+    BruteForceReport {
+        by_source: group_by(&failures, |f| &f.source, |f| &f.account),
+        by_account: group_by(&failures, |f| &f.account, |f| &f.source),
+        failures,
+        findings,
+        reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level: 0,
+            level_name: String::new(),
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            timestamp: EventRecord::test_time(secs),
+            computer: "DC01".into(),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn failed(secs: i64, user: &str, ip: &str) -> EventRecord {
+        event(
+            4625,
+            secs,
+            &[
+                ("TargetUserName", user),
+                ("IpAddress", ip),
+                ("Status", "0xC000006D"),
+                ("SubStatus", "0xC000006A"),
+            ],
+        )
+    }
+
+    #[test]
+    fn burst_followed_by_success() {
+        let thresholds = Thresholds {
+            window_secs: 60,
+            burst: 5,
+            spray: 5,
+        };
+        let mut events: Vec<EventRecord> =
+            (0..6).map(|i| failed(i * 5, "alice", "10.0.0.5")).collect();
+        // Spread out: never 5 within a minute.
+        events.extend((0..5).map(|i| failed(1_000 + i * 100, "bob", "10.0.0.7")));
+        events.push(event(
+            4624,
+            40,
+            &[("TargetUserName", "Alice"), ("IpAddress", "10.0.0.5")],
+        ));
+
+        let report = analyse(&events, thresholds);
+        assert_eq!(report.failures.len(), 11);
+        assert_eq!(report.findings.len(), 1);
+        let f = &report.findings[0];
+        assert_eq!(f.kind, FindingKind::Burst);
+        assert_eq!(f.key, "alice");
+        assert_eq!(f.failures, 6);
+        assert_eq!(f.success, Some(11));
+
+        assert_eq!(report.by_account[0].key, "alice");
+        assert_eq!(report.by_account[0].top_reason.0, "0xC000006A");
+        assert_eq!(report.reasons[0].1, "Wrong password");
+        assert_eq!(report.reasons[0].2, 11);
+    }
+
+    #[test]
+    fn spray_from_one_source() {
+        let users = ["ann", "ben", "cat", "dan", "eve", "fay"];
+        let mut events: Vec<EventRecord> = users
+            .iter()
+            .enumerate()
+            .map(|(i, u)| {
+                event(
+                    4771,
+                    i as i64 * 3,
+                    &[
+                        ("TargetUserName", u),
+                        ("IpAddress", "::ffff:192.168.1.50"),
+                        ("Status", "0x18"),
+                    ],
+                )
+            })
+            .collect();
+        // Successful NTLM validation is not a failure.
+        events.push(event(
+            4776,
+            30,
+            &[
+                ("TargetUserName", "ann"),
+                ("Workstation", "WS01"),
+                ("Status", "0x0"),
+            ],
+        ));
+
+        let report = analyse(&events, Thresholds::default());
+        assert_eq!(report.failures.len(), 6);
+        assert_eq!(report.by_source[0].key, "192.168.1.50");
+        assert_eq!(report.by_source[0].distinct, 6);
+        assert_eq!(report.findings.len(), 1);
+        let f = &report.findings[0];
+        assert_eq!(f.kind, FindingKind::Spray);
+        assert_eq!(f.distinct, 6);
+        assert!(f.success.is_none());
+        assert_eq!(
+            decode_status(4771, "0x18"),
+            "Wrong password (pre-authentication failed)"
+        );
+    }
+
+    #[test]
+    fn success_inside_burst_and_unknown_sources_are_ignored() {
+        let thresholds = Thresholds {
+            window_secs: 60,
+            burst: 5,
+            spray: 3,
+        };
+        let mut events: Vec<EventRecord> =
+            (0..6).map(|i| failed(i * 5, "alice", "10.0.0.5")).collect();
+        // A success between the failures does not follow the burst.
+        events.push(event(
+            4624,
+            12,
+            &[("TargetUserName", "alice"), ("IpAddress", "10.0.0.5")],
+        ));
+        // Different accounts failing with no source recorded: not a spray.
+        for (i, user) in ["ann", "ben", "cat", "dan"].iter().enumerate() {
+            events.push(event(
+                4625,
+                100 + i as i64,
+                &[("TargetUserName", user), ("IpAddress", "-")],
+            ));
+        }
+
+        let report = analyse(&events, thresholds);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].kind, FindingKind::Burst);
+        assert_eq!(report.findings[0].success, None);
+    }
+}
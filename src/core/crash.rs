@@ -0,0 +1,517 @@
+//! Application crash and hang aggregation.
+//!
+//! Application Error 1000, Application Hang 1002, Windows Error Reporting
+//! 1001 (crash and hang buckets only) and .NET Runtime 1026 are parsed from
+//! their EventData into [`CrashRecord`]s, then grouped by application and
+//! by application + faulting module with counts, first / last occurrence
+//! and a per-day trend. Named EventData (current Windows) is preferred,
+//! falling back to the positional `Data_N` layout of older logs.
+//!
+//! WER 1001 usually repeats a crash already logged as 1000 / 1026, so
+//! reports are opt-in to keep counts honest.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::core::event_record::EventRecord;
+
+/// Source of a crash record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrashKind {
+    /// Application Error 1000.
+    Crash,
+    /// Application Hang 1002.
+    Hang,
+    /// .NET Runtime 1026 (unhandled managed exception).
+    Managed,
+    /// Windows Error Reporting 1001.
+    Report,
+}
+
+impl CrashKind {
+    pub const ALL: [CrashKind; 4] = [Self::Crash, Self::Hang, Self::Managed, Self::Report];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Crash => "Crash",
+            Self::Hang => "Hang",
+            Self::Managed => ".NET exception",
+            Self::Report => "WER report",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One parsed crash, hang or report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashRecord {
+    /// Index into the event list.
+    pub event: usize,
+    pub time: DateTime<Utc>,
+    pub kind: CrashKind,
+    pub computer: String,
+    pub app: String,
+    pub app_version: String,
+    /// Faulting module; empty for hangs and managed exceptions.
+    pub module: String,
+    pub module_version: String,
+    /// `0xc0000005`, a managed exception type, or empty.
+    pub exception: String,
+    /// Fault offset within the module (IL offset for CLR20r3).
+    pub offset: String,
+}
+
+/// Records sharing an application (and, for module groups, a faulting
+/// module).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashGroup {
+    pub app: String,
+    /// Empty for application groups.
+    pub module: String,
+    /// Records per kind, indexed like [`CrashKind::ALL`].
+    pub kinds: [usize; 4],
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    /// Distinct application versions, sorted.
+    pub versions: Vec<String>,
+    /// Most common exception code or type.
+    pub top_exception: String,
+    pub computers: usize,
+    /// Records per UTC day, in date order.
+    pub daily: Vec<(NaiveDate, usize)>,
+    /// Event indices, in time order.
+    pub events: Vec<usize>,
+}
+
+impl CrashGroup {
+    pub fn count(&self) -> usize {
+        self.events.len()
+    }
+}
+
+/// Everything the crash window shows.
+#[derive(Debug, Clone, Default)]
+pub struct CrashReport {
+    /// All records, in time order.
+    pub records: Vec<CrashRecord>,
+    /// Most affected application first.
+    pub by_app: Vec<CrashGroup>,
+    /// Most frequent application + module pair first.
+    pub by_module: Vec<CrashGroup>,
+    /// First and last day with a record.
+    pub days: Option<(NaiveDate, NaiveDate)>,
+}
+
+/// Description of a common exception code (`0xc0000005`, `c0000005`).
+pub fn decode_exception(code: &str) -> Option<&'static str> {
+    let hex = code
+        .trim()
+        .trim_start_matches("0x")
+        .trim_start_matches("0X");
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    Some(match value {
+        0x8000_0003 => "Breakpoint",
+        0xC000_0005 => "Access violation",
+        0xC000_001D => "Illegal instruction",
+        0xC000_0094 => "Integer divide by zero",
+        0xC000_00FD => "Stack overflow",
+        0xC000_0135 => "DLL not found",
+        0xC000_0142 => "DLL initialisation failed",
+        0xC000_0374 => "Heap corruption",
+        0xC000_0409 => "Stack buffer overrun (fail-fast)",
+        0xC000_0417 => "Invalid CRT parameter",
+        0xC06D_007E => "Delay-load module not found",
+        0xE043_4352 => "Unhandled .NET exception",
+        0xE06D_7363 => "Unhandled C++ exception",
+        0x4000_0015 => "Fatal application exit",
+        _ => return None,
+    })
+}
+
+/// `c0000005` and `0xC0000005` both become `0xc0000005`.
+fn normalise_code(code: &str) -> String {
+    let hex = code
+        .trim()
+        .trim_start_matches("0x")
+        .trim_start_matches("0X");
+    if !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        format!("0x{}", hex.to_ascii_lowercase())
+    } else {
+        code.trim().to_owned()
+    }
+}
+
+/// A named field, else its positional `Data_N` equivalent.
+fn field(e: &EventRecord, name: &str, position: usize) -> String {
+    e.data(name)
+        .or_else(|| e.data(&format!("Data_{position}")))
+        .unwrap_or_default()
+        .trim()
+        .to_owned()
+}
+
+fn record(idx: usize, e: &EventRecord, kind: CrashKind) -> CrashRecord {
+    CrashRecord {
+        event: idx,
+        time: e.timestamp,
+        kind,
+        computer: e.computer.clone(),
+        app: String::new(),
+        app_version: String::new(),
+        module: String::new(),
+        module_version: String::new(),
+        exception: String::new(),
+        offset: String::new(),
+    }
+}
+
+/// Application Error 1000.
+fn app_error(idx: usize, e: &EventRecord) -> CrashRecord {
+    CrashRecord {
+        app: field(e, "AppName", 1),
+        app_version: field(e, "AppVersion", 2),
+        module: field(e, "ModuleName", 4),
+        module_version: field(e, "ModuleVersion", 5),
+        exception: normalise_code(&field(e, "ExceptionCode", 7)),
+        offset: field(e, "FaultingOffset", 8),
+        ..record(idx, e, CrashKind::Crash)
+    }
+}
+
+/// Application Hang 1002.
+fn app_hang(idx: usize, e: &EventRecord) -> CrashRecord {
+    CrashRecord {
+        app: field(e, "AppName", 1),
+        app_version: field(e, "AppVersion", 2),
+        ..record(idx, e, CrashKind::Hang)
+    }
+}
+
+/// Windows Error Reporting 1001: `Data_3` is the bucket type and
+/// `Data_6`.. the P1..P10 parameters. Buckets other than application
+/// crashes and hangs are ignored.
+fn wer_report(idx: usize, e: &EventRecord) -> Option<CrashRecord> {
+    let name = e.data("EventName").or_else(|| e.data("Data_3"))?;
+    let p = |n: usize| {
+        e.data(&format!("P{n}"))
+            .or_else(|| e.data(&format!("Data_{}", n + 5)))
+            .unwrap_or_default()
+            .trim()
+            .to_owned()
+    };
+    let mut r = CrashRecord {
+        app: p(1),
+        app_version: p(2),
+        ..record(idx, e, CrashKind::Report)
+    };
+    match name {
+        "APPCRASH" | "MoAppCrash" => {
+            r.module = p(4);
+            r.module_version = p(5);
+            r.exception = normalise_code(&p(7));
+            r.offset = p(8);
+        }
+        // Buffer overrun reports put the offset before the code.
+        "BEX" | "BEX64" => {
+            r.module = p(4);
+            r.module_version = p(5);
+            r.offset = p(7);
+            r.exception = normalise_code(&p(8));
+        }
+        "CLR20r3" => {
+            r.module = p(4);
+            r.module_version = p(5);
+            r.offset = p(8);
+            r.exception = p(9);
+        }
+        n if n.starts_with("AppHang") || n.starts_with("MoAppHang") => {}
+        _ => return None,
+    }
+    Some(r)
+}
+
+/// .NET Runtime 1026: a single free-text block of `Key: value` lines.
+fn dotnet_exception(idx: usize, e: &EventRecord) -> Option<CrashRecord> {
+    let text = e
+        .event_data
+        .first()
+        .map(|(_, v)| v.as_str())
+        .unwrap_or(&e.message);
+    let mut r = record(idx, e, CrashKind::Managed);
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Application" => r.app = value.to_owned(),
+            "Framework Version" | "CoreCLR Version" if r.app_version.is_empty() => {
+                r.app_version = value.to_owned();
+            }
+            // `System.IO.FileNotFoundException: Could not load ...`
+            "Exception Info" => {
+                r.exception = value
+                    .split(':')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_owned();
+            }
+            _ => {}
+        }
+    }
+    (!r.app.is_empty()).then_some(r)
+}
+
+/// Parse one event if it is a crash, hang or crash report.
+pub fn parse(idx: usize, e: &EventRecord) -> Option<CrashRecord> {
+    let r = match (e.provider_name.as_str(), e.event_id) {
+        ("Application Error", 1000) => app_error(idx, e),
+        ("Application Hang", 1002) => app_hang(idx, e),
+        ("Windows Error Reporting", 1001) => wer_report(idx, e)?,
+        (".NET Runtime", 1026) => dotnet_exception(idx, e)?,
+        _ => return None,
+    };
+    (!r.app.is_empty()).then_some(r)
+}
+
+fn group_by(records: &[CrashRecord], by_module: bool) -> Vec<CrashGroup> {
+    let mut order: Vec<(String, String)> = Vec::new();
+    let mut members: HashMap<(String, String), Vec<&CrashRecord>> = HashMap::new();
+    for r in records {
+        let module = if by_module { r.module.as_str() } else { "" };
+        let key = (r.app.to_lowercase(), module.to_lowercase());
+        members
+            .entry(key.clone())
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(r);
+    }
+
+    let mut out: Vec<CrashGroup> = order
+        .into_iter()
+        .map(|key| {
+            let rs = &members[&key];
+            let mut kinds = [0; 4];
+            let mut versions: Vec<String> = Vec::new();
+            let mut exceptions: HashMap<&str, usize> = HashMap::new();
+            let mut computers: HashSet<&str> = HashSet::new();
+            let mut daily: Vec<(NaiveDate, usize)> = Vec::new();
+            for r in rs {
+                kinds[r.kind.index()] += 1;
+                if !r.app_version.is_empty() && !versions.contains(&r.app_version) {
+                    versions.push(r.app_version.clone());
+                }
+                if !r.exception.is_empty() {
+                    *exceptions.entry(&r.exception).or_default() += 1;
+                }
+                computers.insert(&r.computer);
+                let day = r.time.date_naive();
+                match daily.last_mut() {
+                    Some((d, n)) if *d == day => *n += 1,
+                    _ => daily.push((day, 1)),
+                }
+            }
+            versions.sort();
+            let top_exception = exceptions
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                .map(|(e, _)| e.to_owned())
+                .unwrap_or_default();
+            CrashGroup {
+                // First-seen spelling.
+                app: rs[0].app.clone(),
+                module: if by_module {
+                    rs[0].module.clone()
+                } else {
+                    String::new()
+                },
+                kinds,
+                first: rs[0].time,
+                last: rs[rs.len() - 1].time,
+                versions,
+                top_exception,
+                computers: computers.len(),
+                daily,
+                events: rs.iter().map(|r| r.event).collect(),
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        b.count()
+            .cmp(&a.count())
+            .then_with(|| a.app.to_lowercase().cmp(&b.app.to_lowercase()))
+            .then_with(|| a.module.cmp(&b.module))
+    });
+    out
+}
+
+/// Parse and aggregate every crash in `events`. WER 1001 reports are only
+/// included when `include_reports` is set.
+pub fn analyse(events: &[EventRecord], include_reports: bool) -> CrashReport {
+    let mut records: Vec<CrashRecord> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(i, e)| parse(i, e))
+        .filter(|r| include_reports || r.kind != CrashKind::Report)
+        .collect();
+    records.sort_by_key(|r| (r.time, r.event));
+
+    let days = records
+        .first()
+        .zip(records.last())
+        .map(|(a, b)| (a.time.date_naive(), b.time.date_naive()));
+    CrashReport {
+        by_app: group_by(&records, false),
+        by_module: group_by(&records, true),
+        records,
+        days,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(provider: &str, id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "Application".into(),
+            event_id: id,
+            level: 2,
+            level_name: "Error".into(),
+            provider_name: provider.into(),
+            timestamp: EventRecord::test_time(secs),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn positional(values: &[&str]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("Data_{}", i + 1), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_named_and_positional_layouts() {
+        let named = event(
+            "Application Error",
+            1000,
+            0,
+            &[
+                ("AppName", "outlook.exe"),
+                ("AppVersion", "16.0.1"),
+                ("ModuleName", "mso.dll"),
+                ("ModuleVersion", "16.0.2"),
+                ("ExceptionCode", "c0000005"),
+                ("FaultingOffset", "00000000001a2b3c"),
+            ],
+        );
+        let r = parse(0, &named).unwrap();
+        assert_eq!(
+            (r.app.as_str(), r.module.as_str()),
+            ("outlook.exe", "mso.dll")
+        );
+        assert_eq!(r.exception, "0xc0000005");
+        assert_eq!(decode_exception(&r.exception), Some("Access violation"));
+
+        let mut wer = event("Windows Error Reporting", 1001, 0, &[]);
+        wer.event_data = positional(&[
+            "1234",
+            "1",
+            "BEX64",
+            "Not available",
+            "0",
+            "app.exe",
+            "1.0",
+            "5f00",
+            "bad.dll",
+            "2.0",
+            "6a00",
+            "0000000000012345",
+            "c0000409",
+        ]);
+        let r = parse(1, &wer).unwrap();
+        assert_eq!(r.kind, CrashKind::Report);
+        assert_eq!(r.module, "bad.dll");
+        assert_eq!(r.offset, "0000000000012345");
+        assert_eq!(r.exception, "0xc0000409");
+
+        wer.event_data[2].1 = "WindowsUpdateFailure3".into();
+        assert_eq!(parse(1, &wer), None);
+
+        let dotnet = event(
+            ".NET Runtime",
+            1026,
+            0,
+            &[(
+                "Data_1",
+                "Application: tool.exe\nCoreCLR Version: 8.0.1\n\
+                 Description: The process was terminated due to an unhandled exception.\n\
+                 Exception Info: System.IO.FileNotFoundException: Could not load file\n   at Main()",
+            )],
+        );
+        let r = parse(2, &dotnet).unwrap();
+        assert_eq!(r.app, "tool.exe");
+        assert_eq!(r.app_version, "8.0.1");
+        assert_eq!(r.exception, "System.IO.FileNotFoundException");
+    }
+
+    #[test]
+    fn groups_by_app_and_module() {
+        let crash = |secs, app: &str, module: &str| {
+            event(
+                "Application Error",
+                1000,
+                secs,
+                &[
+                    ("AppName", app),
+                    ("AppVersion", "1.0"),
+                    ("ModuleName", module),
+                    ("ExceptionCode", "c0000005"),
+                ],
+            )
+        };
+        let mut hang = event("Application Hang", 1002, 200_000, &[]);
+        hang.event_data = positional(&["App.exe", "1.1"]);
+        let mut wer = event("Windows Error Reporting", 1001, 10, &[]);
+        wer.event_data = positional(&["1", "1", "APPCRASH", "", "", "app.exe", "1.0"]);
+        let events = vec![
+            crash(0, "app.exe", "ntdll.dll"),
+            crash(100, "app.exe", "ntdll.dll"),
+            crash(90_000, "app.exe", "foo.dll"),
+            crash(500, "other.exe", "ntdll.dll"),
+            hang,
+            wer,
+        ];
+
+        let report = analyse(&events, false);
+        assert_eq!(report.records.len(), 5);
+        let app = &report.by_app[0];
+        assert_eq!(app.app, "app.exe");
+        assert_eq!(app.count(), 4);
+        assert_eq!(app.kinds, [3, 1, 0, 0]);
+        assert_eq!(app.versions, ["1.0", "1.1"]);
+        assert_eq!(app.top_exception, "0xc0000005");
+        assert_eq!(app.daily.len(), 3);
+        assert_eq!(app.events, [0, 1, 2, 4]);
+
+        let pair = &report.by_module[0];
+        assert_eq!(
+            (pair.app.as_str(), pair.module.as_str()),
+            ("app.exe", "ntdll.dll")
+        );
+        assert_eq!(pair.count(), 2);
+        assert_eq!(report.by_module.len(), 4);
+
+        assert_eq!(analyse(&events, true).records.len(), 6);
+    }
+}
@@ -0,0 +1,325 @@
+//! Two-dimensional pivot tables ("event ID × computer", "provider × day").
+//!
+//! Each filtered event is counted in the cell addressed by its value on
+//! the row and column [`Dimension`]s. Events lacking a field land in a
+//! `(none)` category. Only the most frequent [`MAX_CROSSTAB_ROWS`] /
+//! [`MAX_CROSSTAB_COLUMNS`] values keep their own row or column; the rest
+//! share an `(other)` one, so totals stay exact.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::TimeZone;
+
+use crate::core::event_record::EventRecord;
+use crate::core::pivot::PivotField;
+use crate::util::constants::{MAX_CROSSTAB_COLUMNS, MAX_CROSSTAB_ROWS};
+
+/// Category for events without a value on a dimension.
+pub const NONE_LABEL: &str = "(none)";
+
+/// Category shared by values beyond the row / column cap.
+pub const OTHER_LABEL: &str = "(other)";
+
+/// What a pivot table axis groups by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dimension {
+    /// A header field or EventData key.
+    Field(PivotField),
+    /// Calendar day of the timestamp.
+    Day,
+    /// Calendar hour of the timestamp.
+    Hour,
+}
+
+impl Dimension {
+    /// Dimensions offered in the axis pickers, EventData keys aside.
+    pub fn builtin() -> Vec<Dimension> {
+        vec![
+            Dimension::Field(PivotField::EventId),
+            Dimension::Field(PivotField::Level),
+            Dimension::Field(PivotField::Provider),
+            Dimension::Field(PivotField::Channel),
+            Dimension::Field(PivotField::Computer),
+            Dimension::Field(PivotField::UserSid),
+            Dimension::Field(PivotField::ProcessId),
+            Dimension::Day,
+            Dimension::Hour,
+        ]
+    }
+
+    pub fn label(&self) -> Cow<'_, str> {
+        match self {
+            Dimension::Field(PivotField::EventData(key)) => format!("EventData: {key}").into(),
+            Dimension::Field(field) => field.label(),
+            Dimension::Day => "Day".into(),
+            Dimension::Hour => "Hour".into(),
+        }
+    }
+
+    /// The event's category on this dimension, with times in `tz`.
+    fn value_of<'a, Tz: TimeZone>(&self, event: &'a EventRecord, tz: &Tz) -> Option<Cow<'a, str>>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let local = || event.timestamp.with_timezone(tz);
+        match self {
+            Dimension::Field(field) => field.value_of(event),
+            Dimension::Day => Some(local().format("%Y-%m-%d").to_string().into()),
+            Dimension::Hour => Some(local().format("%Y-%m-%d %H:00").to_string().into()),
+        }
+    }
+}
+
+/// How to order rows or columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Sort {
+    /// By category, numerically when both are numbers.
+    #[default]
+    Label,
+    /// By total, largest first.
+    Total,
+    /// Rows only: by the count in this column, largest first.
+    Column(usize),
+}
+
+/// A computed pivot table.
+#[derive(Debug, Clone, Default)]
+pub struct CrossTab {
+    pub rows: Vec<String>,
+    pub cols: Vec<String>,
+    /// Events behind each non-empty `(row, col)` cell.
+    cells: HashMap<(usize, usize), Vec<usize>>,
+    pub row_totals: Vec<usize>,
+    pub col_totals: Vec<usize>,
+    pub total: usize,
+    /// Largest single cell count (for heat colouring).
+    pub max_cell: usize,
+}
+
+/// Categories of `dim` over `indices`: the ordered category list (capped,
+/// with `(other)` last when needed) and each event's category index.
+fn categorize<Tz: TimeZone>(
+    events: &[EventRecord],
+    indices: &[usize],
+    dim: &Dimension,
+    cap: usize,
+    tz: &Tz,
+) -> (Vec<String>, Vec<usize>)
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let values: Vec<Cow<'_, str>> = indices
+        .iter()
+        .map(|&i| {
+            dim.value_of(&events[i], tz)
+                .unwrap_or(Cow::Borrowed(NONE_LABEL))
+        })
+        .collect();
+    let mut freq: HashMap<&str, usize> = HashMap::new();
+    for v in &values {
+        *freq.entry(v).or_default() += 1;
+    }
+    let mut by_freq: Vec<(&str, usize)> = freq.into_iter().collect();
+    by_freq.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let overflow = by_freq.len() > cap;
+    let kept = if overflow { cap - 1 } else { by_freq.len() };
+    let mut labels: Vec<String> = by_freq[..kept].iter().map(|(v, _)| v.to_string()).collect();
+    let position: HashMap<&str, usize> = by_freq[..kept]
+        .iter()
+        .enumerate()
+        .map(|(i, (v, _))| (*v, i))
+        .collect();
+    if overflow {
+        labels.push(OTHER_LABEL.to_owned());
+    }
+    let assigned = values
+        .iter()
+        .map(|v| position.get(v.as_ref()).copied().unwrap_or(kept))
+        .collect();
+    (labels, assigned)
+}
+
+/// Count `indices` (positions into `events`) by `row` × `col`, with
+/// times in `tz`.
+pub fn build<Tz: TimeZone>(
+    events: &[EventRecord],
+    indices: &[usize],
+    row: &Dimension,
+    col: &Dimension,
+    tz: &Tz,
+) -> CrossTab
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let (rows, row_of) = categorize(events, indices, row, MAX_CROSSTAB_ROWS, tz);
+    let (cols, col_of) = categorize(events, indices, col, MAX_CROSSTAB_COLUMNS, tz);
+    let mut table = CrossTab {
+        row_totals: vec![0; rows.len()],
+        col_totals: vec![0; cols.len()],
+        rows,
+        cols,
+        total: indices.len(),
+        ..Default::default()
+    };
+    for (k, &idx) in indices.iter().enumerate() {
+        let (r, c) = (row_of[k], col_of[k]);
+        table.cells.entry((r, c)).or_default().push(idx);
+        table.row_totals[r] += 1;
+        table.col_totals[c] += 1;
+    }
+    table.max_cell = table.cells.values().map(Vec::len).max().unwrap_or(0);
+    table
+}
+
+/// Compare category labels, numerically when both parse as numbers.
+fn compare_labels(a: &str, b: &str) -> Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(x), Ok(y)) => x.cmp(&y),
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+impl CrossTab {
+    /// Events in cell `(row, col)`.
+    pub fn count(&self, row: usize, col: usize) -> usize {
+        self.cells.get(&(row, col)).map_or(0, Vec::len)
+    }
+
+    /// Events in the given row and / or column; `None` means any.
+    pub fn events(&self, row: Option<usize>, col: Option<usize>) -> Vec<usize> {
+        let mut out: Vec<usize> = self
+            .cells
+            .iter()
+            .filter(|((r, c), _)| row.is_none_or(|x| x == *r) && col.is_none_or(|x| x == *c))
+            .flat_map(|(_, v)| v.iter().copied())
+            .collect();
+        out.sort_unstable();
+        out
+    }
+
+    /// Display order of the rows.
+    pub fn row_order(&self, sort: Sort) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        match sort {
+            Sort::Label => order.sort_by(|&a, &b| compare_labels(&self.rows[a], &self.rows[b])),
+            Sort::Total => order.sort_by_key(|&r| std::cmp::Reverse(self.row_totals[r])),
+            Sort::Column(c) => order.sort_by_key(|&r| std::cmp::Reverse(self.count(r, c))),
+        }
+        order
+    }
+
+    /// Display order of the columns ([`Sort::Column`] keeps build order).
+    pub fn col_order(&self, sort: Sort) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.cols.len()).collect();
+        match sort {
+            Sort::Label => order.sort_by(|&a, &b| compare_labels(&self.cols[a], &self.cols[b])),
+            Sort::Total => order.sort_by_key(|&c| std::cmp::Reverse(self.col_totals[c])),
+            Sort::Column(_) => {}
+        }
+        order
+    }
+
+    /// The table as text records in the given order: a header row, one
+    /// row per category and a totals row, each ending in a total column.
+    pub fn to_records(&self, corner: &str, rows: &[usize], cols: &[usize]) -> Vec<Vec<String>> {
+        let mut out = Vec::with_capacity(rows.len() + 2);
+        let mut header = vec![corner.to_owned()];
+        header.extend(cols.iter().map(|&c| self.cols[c].clone()));
+        header.push("Total".to_owned());
+        out.push(header);
+        for &r in rows {
+            let mut line = vec![self.rows[r].clone()];
+            line.extend(cols.iter().map(|&c| self.count(r, c).to_string()));
+            line.push(self.row_totals[r].to_string());
+            out.push(line);
+        }
+        let mut totals = vec!["Total".to_owned()];
+        totals.extend(cols.iter().map(|&c| self.col_totals[c].to_string()));
+        totals.push(self.total.to_string());
+        out.push(totals);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(id: u32, computer: &str, secs: i64, ip: Option<&str>) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level: 0,
+            level_name: String::new(),
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            timestamp: EventRecord::test_time(secs),
+            computer: computer.into(),
+            event_data: ip
+                .map(|ip| vec![("IpAddress".into(), ip.into())])
+                .unwrap_or_default(),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn counts_totals_and_drill_down() {
+        let events = vec![
+            event(4625, "DC01", 0, Some("10.0.0.5")),
+            event(4625, "DC01", 10, Some("10.0.0.5")),
+            event(4624, "WS07", 20, None),
+            event(10, "WS07", 30, Some("10.0.0.9")),
+        ];
+        let t = build(
+            &events,
+            &[0, 1, 2, 3],
+            &Dimension::Field(PivotField::EventId),
+            &Dimension::Field(PivotField::EventData("IpAddress".into())),
+            &Utc,
+        );
+        assert_eq!(t.total, 4);
+        let r4625 = t.rows.iter().position(|r| r == "4625").unwrap();
+        let c5 = t.cols.iter().position(|c| c == "10.0.0.5").unwrap();
+        let none = t.cols.iter().position(|c| c == NONE_LABEL).unwrap();
+        assert_eq!(t.count(r4625, c5), 2);
+        assert_eq!(t.max_cell, 2);
+        assert_eq!(t.col_totals[none], 1);
+        assert_eq!(t.events(Some(r4625), Some(c5)), vec![0, 1]);
+        assert_eq!(t.events(None, Some(none)), vec![2]);
+
+        // Numeric label order: 10 before 4624 before 4625.
+        let rows: Vec<&str> = t
+            .row_order(Sort::Label)
+            .iter()
+            .map(|&r| t.rows[r].as_str())
+            .collect();
+        assert_eq!(rows, ["10", "4624", "4625"]);
+        assert_eq!(t.row_order(Sort::Total)[0], r4625);
+
+        let records = t.to_records("ID", &t.row_order(Sort::Label), &t.col_order(Sort::Label));
+        assert_eq!(records.len(), 5);
+        assert_eq!(records.last().unwrap().last().unwrap(), "4");
+    }
+
+    #[test]
+    fn overflow_values_share_other() {
+        let events: Vec<EventRecord> = (0..MAX_CROSSTAB_COLUMNS + 5)
+            .map(|i| event(1, &format!("HOST{i:03}"), 0, None))
+            .collect();
+        let indices: Vec<usize> = (0..events.len()).collect();
+        let t = build(
+            &events,
+            &indices,
+            &Dimension::Day,
+            &Dimension::Field(PivotField::Computer),
+            &Utc,
+        );
+        assert_eq!(t.cols.len(), MAX_CROSSTAB_COLUMNS);
+        assert_eq!(t.cols.last().map(String::as_str), Some(OTHER_LABEL));
+        assert_eq!(t.col_totals.iter().sum::<usize>(), events.len());
+        assert_eq!(t.rows, vec!["2023-11-14".to_owned()]);
+    }
+}
@@ -0,0 +1,167 @@
+//! Folding of consecutive duplicate events.
+//!
+//! Two events are duplicates when they agree on every field selected in a
+//! [`DuplicateKey`]; runs of duplicates that sit next to each other in the
+//! current table order collapse into one [`Run`].
+
+use chrono::{DateTime, Utc};
+
+use crate::core::event_record::EventRecord;
+
+/// Fields that must match for two events to count as duplicates.
+///
+/// Persisted to eframe storage with the table layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DuplicateKey {
+    pub provider: bool,
+    pub event_id: bool,
+    /// Rendered message text.
+    pub message: bool,
+    /// All EventData name / value pairs.
+    pub event_data: bool,
+}
+
+impl Default for DuplicateKey {
+    /// Default: provider, event ID and message.
+    fn default() -> Self {
+        Self {
+            provider: true,
+            event_id: true,
+            message: true,
+            event_data: false,
+        }
+    }
+}
+
+impl DuplicateKey {
+    /// Number of fields selected for comparison.
+    pub fn compared_fields(&self) -> usize {
+        [self.provider, self.event_id, self.message, self.event_data]
+            .into_iter()
+            .filter(|&f| f)
+            .count()
+    }
+
+    /// Whether `a` and `b` agree on every selected field. An empty key
+    /// matches nothing, so it never folds events together.
+    pub fn same(&self, a: &EventRecord, b: &EventRecord) -> bool {
+        self.compared_fields() > 0
+            && (!self.provider || a.provider_name == b.provider_name)
+            && (!self.event_id || a.event_id == b.event_id)
+            && (!self.message || a.message == b.message)
+            && (!self.event_data || a.event_data == b.event_data)
+    }
+}
+
+/// A run of consecutive duplicates in the table order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    /// Position of the run's first event in the index list.
+    pub start: usize,
+    pub len: usize,
+    /// Earliest and latest timestamps in the run.
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+impl Run {
+    /// Whether position `pos` of the index list belongs to this run.
+    pub fn contains(&self, pos: usize) -> bool {
+        (self.start..self.start + self.len).contains(&pos)
+    }
+}
+
+/// Split `indices` (positions into `events`, in display order) into runs of
+/// consecutive duplicates under `key`. Every position belongs to exactly
+/// one run; unique events form runs of one, as does every event under a
+/// key with no fields selected.
+pub fn collapse_runs(events: &[EventRecord], indices: &[usize], key: DuplicateKey) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    let mut prev: Option<&EventRecord> = None;
+    for (pos, &idx) in indices.iter().enumerate() {
+        let e = &events[idx];
+        match runs.last_mut() {
+            Some(run) if prev.is_some_and(|p| key.same(p, e)) => {
+                run.len += 1;
+                run.first = run.first.min(e.timestamp);
+                run.last = run.last.max(e.timestamp);
+            }
+            _ => runs.push(Run {
+                start: pos,
+                len: 1,
+                first: e.timestamp,
+                last: e.timestamp,
+            }),
+        }
+        prev = Some(e);
+    }
+    runs
+}
+
+/// Index of the run containing position `pos` (runs are sorted by start).
+pub fn run_of(runs: &[Run], pos: usize) -> Option<usize> {
+    let i = runs.partition_point(|r| r.start <= pos).checked_sub(1)?;
+    runs[i].contains(pos).then_some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, secs: i64, message: &str) -> EventRecord {
+        EventRecord {
+            channel: "Application".into(),
+            event_id: id,
+            provider_name: "Retry".into(),
+            timestamp: EventRecord::test_time(secs),
+            message: message.into(),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn folds_only_adjacent_duplicates() {
+        let events = vec![
+            event(1, 0, "retrying"),
+            event(1, 5, "retrying"),
+            event(1, 9, "retrying"),
+            event(2, 10, "gave up"),
+            event(1, 20, "retrying"),
+        ];
+        let runs = collapse_runs(&events, &[0, 1, 2, 3, 4], DuplicateKey::default());
+        assert_eq!(runs.len(), 3);
+        assert_eq!((runs[0].start, runs[0].len), (0, 3));
+        assert_eq!((runs[0].last - runs[0].first).num_seconds(), 9);
+        assert_eq!((runs[2].start, runs[2].len), (4, 1));
+        assert_eq!(run_of(&runs, 2), Some(0));
+        assert_eq!(run_of(&runs, 3), Some(1));
+        assert_eq!(run_of(&runs, 5), None);
+    }
+
+    #[test]
+    fn key_selects_compared_fields() {
+        let events = vec![event(1, 0, "a"), event(1, 1, "b")];
+        let by_id = DuplicateKey {
+            message: false,
+            ..DuplicateKey::default()
+        };
+        assert_eq!(collapse_runs(&events, &[0, 1], by_id).len(), 1);
+        assert_eq!(
+            collapse_runs(&events, &[0, 1], DuplicateKey::default()).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn empty_key_folds_nothing() {
+        let events = vec![event(1, 0, "a"), event(2, 1, "b"), event(3, 2, "c")];
+        let none = DuplicateKey {
+            provider: false,
+            event_id: false,
+            message: false,
+            event_data: false,
+        };
+        assert_eq!(none.compared_fields(), 0);
+        assert_eq!(collapse_runs(&events, &[0, 1, 2], none).len(), 3);
+    }
+}
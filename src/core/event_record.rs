@@ -65,6 +65,10 @@ pub struct EventRecord {
     /// Correlation Activity ID, if present.
     pub activity_id: Option<String>,
 
+    /// Correlation RelatedActivityID (the activity that started this
+    /// one), if present.
+    pub related_activity_id: Option<String>,
+
     /// User SID string, if present.
     pub user_sid: Option<String>,
 
@@ -90,6 +94,24 @@ impl EventRecord {
         }
     }
 
+    /// Value of the EventData field `name` (case-insensitive), if present
+    /// and non-empty.
+    pub fn data(&self, name: &str) -> Option<&str> {
+        self.event_data
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Like [`Self::data`], but also treats `-` (and whitespace) as absent:
+    /// Security events write `-` for values that were not recorded.
+    pub fn recorded_data(&self, name: &str) -> Option<&str> {
+        self.data(name)
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && *v != "-")
+    }
+
     /// Returns a one-line summary suitable for the table's message column.
     ///
     /// If the formatted message is empty, falls back to the first event data
@@ -104,3 +126,45 @@ impl EventRecord {
         }
     }
 }
+
+#[cfg(test)]
+impl EventRecord {
+    /// Blank Information-level event from `WS01` at [`Self::test_time`]`(0)`.
+    /// Unit tests set only the fields they care about with struct-update
+    /// syntax: `EventRecord { event_id: 4624, ..EventRecord::test_base() }`.
+    pub(crate) fn test_base() -> Self {
+        Self {
+            raw_xml: String::new(),
+            channel: String::new(),
+            event_id: 0,
+            level: 4,
+            level_name: "Information".into(),
+            provider_name: String::new(),
+            timestamp: Self::test_time(0),
+            computer: "WS01".into(),
+            message: String::new(),
+            process_id: 0,
+            thread_id: 0,
+            task: 0,
+            opcode: 0,
+            keywords: 0,
+            activity_id: None,
+            related_activity_id: None,
+            user_sid: None,
+            event_data: Vec::new(),
+        }
+    }
+
+    /// `secs` after 2023-11-14 22:13:20 UTC (Unix time 1_700_000_000).
+    pub(crate) fn test_time(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    /// `(name, value)` pairs as owned `event_data`.
+    pub(crate) fn test_data(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+}
@@ -0,0 +1,166 @@
+//! EventData field value distributions ("stack counting").
+//!
+//! For every EventData key seen in a set of events: how many events carry
+//! it, how many distinct values it takes, and its most and least frequent
+//! values. The rare tail is where least-frequency-of-occurrence hunting
+//! looks for the one odd service path or logon process.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::{MAX_FIELD_DISTINCT_VALUES, MAX_FIELD_VALUES_SHOWN};
+
+/// Value distribution of one EventData key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSummary {
+    pub key: String,
+    /// Events carrying the key.
+    pub present: usize,
+    /// Distinct values seen (stops growing at
+    /// [`MAX_FIELD_DISTINCT_VALUES`]; see `capped`).
+    pub distinct: usize,
+    /// Values beyond the distinct cap were not tracked, so `distinct`
+    /// and `bottom` are approximate.
+    pub capped: bool,
+    /// Most frequent values, most frequent first.
+    pub top: Vec<(String, usize)>,
+    /// Least frequent values, rarest first.
+    pub bottom: Vec<(String, usize)>,
+}
+
+/// Distributions of every key over a set of events.
+#[derive(Debug, Clone, Default)]
+pub struct FieldProfile {
+    /// Events profiled.
+    pub total: usize,
+    /// One entry per key, most common key first.
+    pub fields: Vec<FieldSummary>,
+}
+
+impl FieldSummary {
+    /// Share of profiled events carrying the key, in percent.
+    pub fn coverage(&self, total: usize) -> f64 {
+        if total == 0 {
+            0.0
+        } else {
+            self.present as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+/// Profile the EventData of `events[i]` for each `i` in `indices`. Meant to
+/// run off the UI thread on a shared snapshot of the event list.
+pub fn profile(events: &[EventRecord], indices: &[usize]) -> FieldProfile {
+    struct Acc<'a> {
+        present: usize,
+        values: HashMap<&'a str, usize>,
+        capped: bool,
+    }
+    let mut keys: HashMap<&str, Acc<'_>> = HashMap::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+
+    for &i in indices {
+        seen.clear();
+        for (key, value) in &events[i].event_data {
+            // A key repeated within one event counts once.
+            if !seen.insert(key) {
+                continue;
+            }
+            let acc = keys.entry(key).or_insert_with(|| Acc {
+                present: 0,
+                values: HashMap::new(),
+                capped: false,
+            });
+            acc.present += 1;
+            if let Some(n) = acc.values.get_mut(value.as_str()) {
+                *n += 1;
+            } else if acc.values.len() < MAX_FIELD_DISTINCT_VALUES {
+                acc.values.insert(value, 1);
+            } else {
+                acc.capped = true;
+            }
+        }
+    }
+
+    let mut fields: Vec<FieldSummary> = keys
+        .into_iter()
+        .map(|(key, acc)| {
+            let mut values: Vec<(&str, usize)> = acc.values.into_iter().collect();
+            values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            let owned = |v: &[(&str, usize)]| -> Vec<(String, usize)> {
+                v.iter().map(|(s, n)| (s.to_string(), *n)).collect()
+            };
+            let shown = MAX_FIELD_VALUES_SHOWN.min(values.len());
+            let mut bottom = owned(&values[values.len() - shown..]);
+            bottom.reverse();
+            FieldSummary {
+                key: key.to_owned(),
+                present: acc.present,
+                distinct: values.len(),
+                capped: acc.capped,
+                top: owned(&values[..shown]),
+                bottom,
+            }
+        })
+        .collect();
+    fields.sort_by(|a, b| b.present.cmp(&a.present).then_with(|| a.key.cmp(&b.key)));
+
+    FieldProfile {
+        total: indices.len(),
+        fields,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pairs: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            event_data: EventRecord::test_data(pairs),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn profile_all(events: &[EventRecord]) -> FieldProfile {
+        let indices: Vec<usize> = (0..events.len()).collect();
+        profile(events, &indices)
+    }
+
+    #[test]
+    fn coverage_distinct_and_rare_values() {
+        let mut events = Vec::new();
+        for _ in 0..5 {
+            events.push(event(&[("LogonType", "3"), ("ProcessName", "lsass.exe")]));
+        }
+        events.push(event(&[
+            ("LogonType", "10"),
+            ("ProcessName", "C:\\Temp\\x.exe"),
+        ]));
+        events.push(event(&[("LogonType", "3")]));
+        events.push(event(&[]));
+
+        let p = profile_all(&events);
+        assert_eq!(p.total, 8);
+        let logon = &p.fields[0];
+        assert_eq!(logon.key, "LogonType");
+        assert_eq!(logon.present, 7);
+        assert_eq!(logon.distinct, 2);
+        assert_eq!(logon.top[0], ("3".to_owned(), 6));
+        assert_eq!(logon.bottom[0], ("10".to_owned(), 1));
+        assert!((logon.coverage(p.total) - 87.5).abs() < 1e-9);
+
+        let process = &p.fields[1];
+        assert_eq!(process.present, 6);
+        assert_eq!(process.bottom[0].0, "C:\\Temp\\x.exe");
+        assert!(!process.capped);
+    }
+
+    #[test]
+    fn repeated_key_counts_once_per_event() {
+        let events = vec![event(&[("Data_1", "a"), ("Data_1", "b")])];
+        let p = profile_all(&events);
+        assert_eq!(p.fields[0].present, 1);
+        assert_eq!(p.fields[0].distinct, 1);
+    }
+}
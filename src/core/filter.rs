@@ -4,12 +4,19 @@
 //! Filtering is performed in-memory against the loaded event list, with
 //! checks ordered cheapest-first for short-circuit efficiency.
 //!
-//! [`FilterPreset`] lives in the sibling [`super::filter_preset`] module
-//! and is re-exported here for convenience.
+//! [`FilterPreset`] lives in the sibling [`super::filter_preset`] module.
+//! The substring/whole-word matchers and [`SearchScope`] live in
+//! [`super::text_search`]; `SearchScope` is re-exported here for convenience.
 
 use crate::core::event_record::EventRecord;
+use crate::core::pivot::FieldClause;
+use crate::core::text_search::{
+    contains_case_insensitive, contains_word, contains_word_case_insensitive,
+};
 use std::collections::HashSet;
 
+pub use crate::core::text_search::SearchScope;
+
 /// Compiled regex for text search, when regex mode is enabled.
 ///
 /// Wrapped in `Option` because compilation may fail for invalid patterns.
@@ -41,10 +48,26 @@ pub struct FilterState {
     /// Provider/source name substring filter (case-insensitive).
     pub provider_filter: String,
 
-    /// Free-form text search — matched against message, provider name,
-    /// event data values, and raw XML.
+    /// Free-form text search — matched against the fields selected by
+    /// `search_scope` (by default: message, provider name, event data, and
+    /// raw XML).
     pub text_search: String,
 
+    /// Which event fields the text search looks at.
+    pub search_scope: SearchScope,
+
+    /// Comma-separated EventData key names searched when `search_scope` is
+    /// [`SearchScope::SpecificKeys`] (e.g. `"TargetUserName, IpAddress"`).
+    pub search_keys: String,
+
+    /// Pre-computed lowercase, trimmed key names parsed from `search_keys`.
+    /// Updated by [`update_search_cache`]. Empty means "any key".
+    pub search_keys_lower: Vec<String>,
+
+    /// Whether the search text must match a whole word (no letter, digit,
+    /// or underscore directly before or after the match).
+    pub whole_word: bool,
+
     /// Pre-computed lowercase version of `text_search` for efficient
     /// case-insensitive matching. Updated by [`update_search_cache`].
     pub text_search_lower: String,
@@ -53,6 +76,10 @@ pub struct FilterState {
     /// case-insensitive matching. Updated by [`update_search_cache`].
     pub provider_filter_lower: String,
 
+    /// Exact-match field clauses added by "filter to" / "exclude" pivots
+    /// (see [`crate::core::pivot`]). All must pass.
+    pub field_clauses: Vec<FieldClause>,
+
     /// Start of time range filter. `None` = no lower bound.
     pub time_from_input: String,
 
@@ -71,7 +98,8 @@ pub struct FilterState {
     /// Whether text search uses regex patterns instead of literal substrings.
     pub use_regex: bool,
 
-    /// Compiled regex for the current `text_search` when `use_regex` is true.
+    /// Compiled regex for the current `text_search` when `use_regex` is true,
+    /// wrapped in `\b...\b` when `whole_word` is also set.
     /// `None` if the pattern is empty or invalid.
     pub compiled_regex: CompiledRegex,
 }
@@ -86,8 +114,13 @@ impl Default for FilterState {
             levels: [true; 6],
             provider_filter: String::new(),
             text_search: String::new(),
+            search_scope: SearchScope::AllFields,
+            search_keys: String::new(),
+            search_keys_lower: Vec::new(),
+            whole_word: false,
             text_search_lower: String::new(),
             provider_filter_lower: String::new(),
+            field_clauses: Vec::new(),
             time_from_input: String::new(),
             time_to_input: String::new(),
             time_from: None,
@@ -99,34 +132,6 @@ impl Default for FilterState {
     }
 }
 
-/// Case-insensitive substring search without heap allocation for ASCII content.
-///
-/// Assumes `needle_lower` is already fully lowercased. Uses a fast byte-level
-/// comparison for ASCII-only haystacks (typical of Windows Event Log data),
-/// falling back to `to_lowercase().contains()` only when non-ASCII is detected.
-fn contains_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
-    if needle_lower.is_empty() {
-        return true;
-    }
-    let n = needle_lower.as_bytes();
-    let h = haystack.as_bytes();
-    if n.len() > h.len() {
-        return false;
-    }
-    // Fast path: byte-level ASCII comparison (zero-alloc, covers ~99% of event log data)
-    let found = h
-        .windows(n.len())
-        .any(|w| w.iter().zip(n).all(|(a, b)| a.to_ascii_lowercase() == *b));
-    if found {
-        return true;
-    }
-    // Slow path: full Unicode lowering only if haystack contains non-ASCII
-    if !haystack.is_ascii() {
-        return haystack.to_lowercase().contains(needle_lower);
-    }
-    false
-}
-
 impl FilterState {
     /// Re-parse the raw `event_id_input` string into the `include_ids` and
     /// `exclude_ids` sets. Call this whenever the input field changes.
@@ -209,16 +214,25 @@ impl FilterState {
     pub fn update_search_cache(&mut self) {
         self.text_search_lower = self.text_search.to_lowercase();
         self.provider_filter_lower = self.provider_filter.to_lowercase();
+        self.search_keys_lower = self
+            .search_keys
+            .split(',')
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
 
         // Compile regex if in regex mode
         if self.use_regex && !self.text_search.is_empty() {
-            let pattern_result = if self.case_sensitive {
-                regex::RegexBuilder::new(&self.text_search).build()
+            // Whole-word mode wraps the user's pattern in word boundaries;
+            // the non-capturing group keeps alternations like `a|b` intact.
+            let pattern = if self.whole_word {
+                format!(r"\b(?:{})\b", self.text_search)
             } else {
-                regex::RegexBuilder::new(&self.text_search)
-                    .case_insensitive(true)
-                    .build()
+                self.text_search.clone()
             };
+            let pattern_result = regex::RegexBuilder::new(&pattern)
+                .case_insensitive(!self.case_sensitive)
+                .build();
             self.compiled_regex = pattern_result.ok();
         } else {
             self.compiled_regex = None;
@@ -238,7 +252,8 @@ impl FilterState {
     /// 2. Event ID (hash set lookup)
     /// 3. Time range (comparison)
     /// 4. Provider substring
-    /// 5. Text search (most expensive)
+    /// 5. Field clauses
+    /// 6. Text search (most expensive)
     pub fn matches(&self, event: &EventRecord) -> bool {
         // 1. Level filter — O(1) array index
         let level_idx = (event.level as usize).min(5);
@@ -273,7 +288,12 @@ impl FilterState {
             return false;
         }
 
-        // 5. Text search — most expensive, checked last
+        // 5. Pivot clauses -- exact compare per clause
+        if !self.field_clauses.iter().all(|c| c.matches(event)) {
+            return false;
+        }
+
+        // 6. Text search — most expensive, checked last
         if !self.text_search.is_empty() {
             let matches = if self.use_regex {
                 self.text_search_regex(event)
@@ -290,30 +310,45 @@ impl FilterState {
         true
     }
 
-    /// Case-sensitive text search across event fields.
-    fn text_search_case_sensitive(&self, event: &EventRecord) -> bool {
-        let q = &self.text_search;
-        if event.message.contains(q) {
-            return true;
-        }
-        if event.provider_name.contains(q) {
-            return true;
-        }
-        if event.channel.contains(q) {
-            return true;
-        }
-        for (k, v) in &event.event_data {
-            if k.contains(q) || v.contains(q) {
-                return true;
+    /// Apply `pred` to each field covered by `search_scope`, returning
+    /// `true` on the first match.
+    ///
+    /// Fields are visited cheapest-first; for [`SearchScope::AllFields`]
+    /// the expensive raw XML is checked last.
+    fn any_field_in_scope(&self, event: &EventRecord, pred: impl Fn(&str) -> bool) -> bool {
+        match self.search_scope {
+            SearchScope::AllFields => {
+                pred(&event.message)
+                    || pred(&event.provider_name)
+                    || pred(&event.channel)
+                    || event.event_data.iter().any(|(k, v)| pred(k) || pred(v))
+                    || pred(&event.raw_xml)
             }
+            SearchScope::MessageOnly => pred(&event.message),
+            SearchScope::EventDataValues => event.event_data.iter().any(|(_, v)| pred(v)),
+            SearchScope::SpecificKeys => event.event_data.iter().any(|(k, v)| {
+                (self.search_keys_lower.is_empty()
+                    || self
+                        .search_keys_lower
+                        .iter()
+                        .any(|want| k.eq_ignore_ascii_case(want)))
+                    && pred(v)
+            }),
+            SearchScope::Xml => pred(&event.raw_xml),
         }
-        if event.raw_xml.contains(q) {
-            return true;
+    }
+
+    /// Case-sensitive text search across the fields in scope.
+    fn text_search_case_sensitive(&self, event: &EventRecord) -> bool {
+        let q = self.text_search.as_str();
+        if self.whole_word {
+            self.any_field_in_scope(event, |s| contains_word(s, q))
+        } else {
+            self.any_field_in_scope(event, |s| s.contains(q))
         }
-        false
     }
 
-    /// Regex-based text search across event fields.
+    /// Regex-based text search across the fields in scope.
     ///
     /// Uses the pre-compiled regex from [`compiled_regex`]. Returns `false`
     /// if the regex failed to compile (invalid pattern).
@@ -322,54 +357,22 @@ impl FilterState {
             Some(re) => re,
             None => return false, // invalid regex pattern
         };
-        if re.is_match(&event.message) {
-            return true;
-        }
-        if re.is_match(&event.provider_name) {
-            return true;
-        }
-        if re.is_match(&event.channel) {
-            return true;
-        }
-        for (k, v) in &event.event_data {
-            if re.is_match(k) || re.is_match(v) {
-                return true;
-            }
-        }
-        if re.is_match(&event.raw_xml) {
-            return true;
-        }
-        false
+        self.any_field_in_scope(event, |s| re.is_match(s))
     }
 
-    /// Case-insensitive text search across event fields.
+    /// Case-insensitive text search across the fields in scope.
     ///
     /// Uses [`contains_case_insensitive`] for zero-allocation matching on
-    /// ASCII content (typical of Windows Event Log data). Fields are checked
-    /// cheapest-first with early return on match. The `text_search_lower`
-    /// cache (updated by [`update_search_cache`]) avoids re-lowercasing the
-    /// needle on every event.
+    /// ASCII content (typical of Windows Event Log data). The
+    /// `text_search_lower` cache (updated by [`update_search_cache`])
+    /// avoids re-lowercasing the needle on every event.
     fn text_search_case_insensitive(&self, event: &EventRecord) -> bool {
         let q = self.text_search_lower.as_str();
-        if contains_case_insensitive(&event.message, q) {
-            return true;
-        }
-        if contains_case_insensitive(&event.provider_name, q) {
-            return true;
-        }
-        if contains_case_insensitive(&event.channel, q) {
-            return true;
-        }
-        for (k, v) in &event.event_data {
-            if contains_case_insensitive(k, q) || contains_case_insensitive(v, q) {
-                return true;
-            }
-        }
-        // raw_xml search is expensive, do it last
-        if contains_case_insensitive(&event.raw_xml, q) {
-            return true;
+        if self.whole_word {
+            self.any_field_in_scope(event, |s| contains_word_case_insensitive(s, q))
+        } else {
+            self.any_field_in_scope(event, |s| contains_case_insensitive(s, q))
         }
-        false
     }
 
     /// Returns `true` if all filters are at their default (pass-all) state.
@@ -378,6 +381,7 @@ impl FilterState {
             && self.levels.iter().all(|&v| v)
             && self.provider_filter.is_empty()
             && self.text_search.is_empty()
+            && self.field_clauses.is_empty()
             && self.time_from.is_none()
             && self.time_to.is_none()
     }
@@ -400,12 +404,58 @@ impl FilterState {
         if !self.text_search.is_empty() {
             n += 1;
         }
+        if !self.field_clauses.is_empty() {
+            n += 1;
+        }
         if self.time_from.is_some() || self.time_to.is_some() {
             n += 1;
         }
         n
     }
 
+    /// Build a short one-line description of the active criteria, e.g.
+    /// `ID 4625 · Error,Warning · provider "Security"`.
+    ///
+    /// Returns `"All events"` when no criteria are set.
+    pub fn summary(&self) -> String {
+        const LEVEL_SHORT: [&str; 6] = ["Always", "Crit", "Error", "Warn", "Info", "Verbose"];
+        let mut parts: Vec<String> = Vec::new();
+        if !self.event_id_input.trim().is_empty() {
+            parts.push(format!("ID {}", self.event_id_input.trim()));
+        }
+        if !self.levels.iter().all(|&v| v) {
+            let on: Vec<&str> = (0..6)
+                .filter(|&i| self.levels[i])
+                .map(|i| LEVEL_SHORT[i])
+                .collect();
+            parts.push(if on.is_empty() {
+                "no levels".to_owned()
+            } else {
+                on.join(",")
+            });
+        }
+        if !self.provider_filter.is_empty() {
+            parts.push(format!("provider \"{}\"", self.provider_filter));
+        }
+        if !self.text_search.is_empty() {
+            let kind = if self.use_regex { "regex" } else { "text" };
+            parts.push(format!("{kind} \"{}\"", self.text_search));
+        }
+        parts.extend(self.field_clauses.iter().map(FieldClause::describe));
+        if !self.time_from_input.is_empty() || !self.time_to_input.is_empty() {
+            parts.push(format!(
+                "time {}..{}",
+                self.time_from_input.trim(),
+                self.time_to_input.trim()
+            ));
+        }
+        if parts.is_empty() {
+            "All events".to_owned()
+        } else {
+            parts.join(" \u{00B7} ")
+        }
+    }
+
     /// Reset all filters to their default (pass-all) state.
     pub fn clear(&mut self) {
         *self = Self::default();
@@ -423,6 +473,23 @@ impl FilterState {
         self.time_to_input.clear();
     }
 
+    /// Restrict the time range to `from..=to`, filling the input fields
+    /// (as local time) and re-parsing them so both stay in sync.
+    pub fn set_time_window(
+        &mut self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) {
+        let fmt = |t: chrono::DateTime<chrono::Utc>| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        self.time_from_input = fmt(from);
+        self.time_to_input = fmt(to);
+        self.parse_time_range();
+    }
+
     /// Apply a "Today" preset: from midnight local time today to now.
     pub fn apply_today_preset(&mut self) {
         let today_local = chrono::Local::now().date_naive().and_hms_opt(0, 0, 0);
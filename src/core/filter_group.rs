@@ -0,0 +1,184 @@
+//! Compound filtering: several [`FilterState`] groups combined with OR.
+//!
+//! The app's primary [`FilterState`] is always an include group. Extra
+//! [`FilterGroup`]s either widen the result set (include groups are ORed
+//! with the primary) or carve events out of it (exclude groups). This
+//! expresses questions such as "4625 from any provider OR Error level from
+//! Service Control Manager" that a single AND-only `FilterState` cannot.
+
+use super::event_record::EventRecord;
+use super::filter::FilterState;
+
+/// How a [`FilterGroup`] contributes to the combined result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum GroupMode {
+    /// Events matching this group are shown (ORed with other include groups).
+    #[default]
+    Include,
+    /// Events matching this group are hidden, even if an include group matches.
+    Exclude,
+}
+
+impl GroupMode {
+    /// Display label used in the filter panel.
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupMode::Include => "Include (OR)",
+            GroupMode::Exclude => "Exclude",
+        }
+    }
+}
+
+/// One additional criteria group alongside the primary filter.
+#[derive(Debug, Clone, Default)]
+pub struct FilterGroup {
+    /// Include or exclude semantics.
+    pub mode: GroupMode,
+    /// Disabled groups are kept for editing but ignored when matching.
+    pub enabled: bool,
+    /// The group's criteria, ANDed together like the primary filter.
+    pub state: FilterState,
+}
+
+impl FilterGroup {
+    /// Create an enabled, pass-all group with the given mode.
+    pub fn new(mode: GroupMode) -> Self {
+        Self {
+            mode,
+            enabled: true,
+            state: FilterState::default(),
+        }
+    }
+
+    /// Returns `true` if this group takes part in matching.
+    ///
+    /// A group with no criteria matches every event, so an empty exclude
+    /// group would hide everything and an empty include group would make
+    /// every other group pointless. Both are treated as inactive.
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.state.is_empty()
+    }
+}
+
+/// Test `event` against the primary filter plus any extra groups.
+///
+/// The event passes when it matches the primary filter **or** any active
+/// include group, **and** matches no active exclude group.
+pub fn matches_groups(primary: &FilterState, groups: &[FilterGroup], event: &EventRecord) -> bool {
+    let included = primary.matches(event)
+        || groups
+            .iter()
+            .filter(|g| g.mode == GroupMode::Include && g.is_active())
+            .any(|g| g.state.matches(event));
+    if !included {
+        return false;
+    }
+    !groups
+        .iter()
+        .filter(|g| g.mode == GroupMode::Exclude && g.is_active())
+        .any(|g| g.state.matches(event))
+}
+
+/// Count the extra groups that currently affect matching. Include groups
+/// only widen the primary filter, so they add nothing while it is empty
+/// (it already matches everything).
+pub fn active_group_count(primary: &FilterState, groups: &[FilterGroup]) -> usize {
+    let widens = !primary.is_empty();
+    groups
+        .iter()
+        .filter(|g| g.is_active() && (widens || g.mode == GroupMode::Exclude))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, level: u8, provider: &str) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: id,
+            level,
+            level_name: EventRecord::level_to_name(level).into(),
+            provider_name: provider.into(),
+            computer: "PC".into(),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn ids(input: &str) -> FilterState {
+        let mut f = FilterState {
+            event_id_input: input.into(),
+            ..Default::default()
+        };
+        f.parse_event_ids();
+        f
+    }
+
+    #[test]
+    fn include_groups_are_ored_with_primary() {
+        let primary = ids("4625");
+        let mut scm = FilterGroup::new(GroupMode::Include);
+        scm.state.levels = [false, false, true, false, false, false];
+        scm.state.provider_filter = "Service Control Manager".into();
+        scm.state.parse_event_ids();
+        let groups = vec![scm];
+
+        assert!(matches_groups(
+            &primary,
+            &groups,
+            &event(4625, 4, "Security")
+        ));
+        assert!(matches_groups(
+            &primary,
+            &groups,
+            &event(7000, 2, "Service Control Manager")
+        ));
+        assert!(!matches_groups(
+            &primary,
+            &groups,
+            &event(7036, 4, "Service Control Manager")
+        ));
+    }
+
+    #[test]
+    fn exclude_group_removes_matches() {
+        let primary = FilterState::default();
+        let mut noise = FilterGroup::new(GroupMode::Exclude);
+        noise.state = ids("7036");
+        let groups = vec![noise];
+
+        assert!(!matches_groups(&primary, &groups, &event(7036, 4, "SCM")));
+        assert!(matches_groups(&primary, &groups, &event(7040, 4, "SCM")));
+    }
+
+    #[test]
+    fn empty_or_disabled_groups_are_ignored() {
+        let primary = ids("4625");
+        let empty_exclude = FilterGroup::new(GroupMode::Exclude);
+        let mut disabled = FilterGroup::new(GroupMode::Include);
+        disabled.state = ids("1102");
+        disabled.enabled = false;
+        let groups = vec![empty_exclude, disabled];
+
+        assert!(matches_groups(&primary, &groups, &event(4625, 4, "P")));
+        assert!(!matches_groups(&primary, &groups, &event(1102, 4, "P")));
+        assert_eq!(active_group_count(&primary, &groups), 0);
+    }
+
+    #[test]
+    fn include_groups_do_not_count_under_empty_primary() {
+        let mut include = FilterGroup::new(GroupMode::Include);
+        include.state = ids("1102");
+        let mut exclude = FilterGroup::new(GroupMode::Exclude);
+        exclude.state = ids("4634");
+        let groups = vec![include, exclude];
+
+        let primary = FilterState::default();
+        // An empty primary already matches everything.
+        assert!(matches_groups(&primary, &groups, &event(7036, 4, "P")));
+        assert!(!matches_groups(&primary, &groups, &event(4634, 4, "P")));
+        assert_eq!(active_group_count(&primary, &groups), 1);
+        assert_eq!(active_group_count(&ids("4625"), &groups), 2);
+    }
+}
@@ -0,0 +1,199 @@
+//! Undo/redo history of filter configurations.
+//!
+//! Each entry is a [`CompoundPreset`] snapshot of the primary filter and
+//! its extra groups, together with a one-line summary and the number of
+//! events it matched. The app records a snapshot whenever it re-applies
+//! the filter; identical consecutive snapshots are collapsed, and edits
+//! arriving in quick succession (e.g. typing with pauses just over the
+//! debounce delay) are coalesced into one entry.
+
+use crate::core::filter_preset::CompoundPreset;
+use std::time::{Duration, Instant};
+
+/// Maximum number of entries kept; the oldest are dropped first.
+pub const MAX_FILTER_HISTORY: usize = 50;
+
+/// Edits recorded closer together than this replace the newest entry
+/// instead of adding a new one.
+pub const HISTORY_COALESCE: Duration = Duration::from_millis(1_000);
+
+/// One recorded filter configuration.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    /// Primary filter plus extra groups.
+    pub snapshot: CompoundPreset,
+    /// Short description shown in the history dropdown.
+    pub summary: String,
+    /// Number of events the filter matched when last applied.
+    pub result_count: usize,
+}
+
+/// Linear undo/redo stack of [`HistoryEntry`]s.
+#[derive(Debug, Default)]
+pub struct FilterHistory {
+    entries: Vec<HistoryEntry>,
+    /// Index of the entry matching the current filter.
+    cursor: usize,
+    /// When the newest entry was last written by an edit; `None` after
+    /// an undo/redo so the next edit always starts a new entry.
+    last_record: Option<Instant>,
+}
+
+impl FilterHistory {
+    /// Record the filter that was just applied.
+    ///
+    /// If it equals the current entry, only the result count is refreshed
+    /// (this is what happens after an undo or a reload). Otherwise any redo
+    /// entries are discarded and the snapshot is appended, or replaces the
+    /// newest entry when within [`HISTORY_COALESCE`] of the previous edit.
+    pub fn record(&mut self, snapshot: CompoundPreset, summary: String, result_count: usize) {
+        let now = Instant::now();
+        if let Some(current) = self.entries.get_mut(self.cursor) {
+            if current.snapshot.groups == snapshot.groups {
+                current.result_count = result_count;
+                return;
+            }
+        }
+
+        let entry = HistoryEntry {
+            snapshot,
+            summary,
+            result_count,
+        };
+        let coalesce = self.cursor + 1 == self.entries.len()
+            && self.cursor > 0
+            && self
+                .last_record
+                .is_some_and(|t| now.duration_since(t) < HISTORY_COALESCE);
+
+        if coalesce {
+            self.entries[self.cursor] = entry;
+        } else {
+            self.entries.truncate(self.cursor + 1);
+            self.entries.push(entry);
+            if self.entries.len() > MAX_FILTER_HISTORY {
+                self.entries.remove(0);
+            }
+            self.cursor = self.entries.len() - 1;
+        }
+        self.last_record = Some(now);
+    }
+
+    /// Step back one entry, returning the snapshot to restore.
+    pub fn undo(&mut self) -> Option<&CompoundPreset> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.jump_to(self.cursor - 1)
+    }
+
+    /// Step forward one entry, returning the snapshot to restore.
+    pub fn redo(&mut self) -> Option<&CompoundPreset> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.jump_to(self.cursor + 1)
+    }
+
+    /// Move to entry `idx` (e.g. picked from the dropdown).
+    pub fn jump_to(&mut self, idx: usize) -> Option<&CompoundPreset> {
+        if idx >= self.entries.len() {
+            return None;
+        }
+        self.cursor = idx;
+        self.last_record = None;
+        Some(&self.entries[idx].snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+
+    /// All entries, oldest first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Index of the entry matching the current filter.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::filter::FilterState;
+
+    fn snap(ids: &str) -> CompoundPreset {
+        let f = FilterState {
+            event_id_input: ids.into(),
+            ..Default::default()
+        };
+        CompoundPreset::from_filters("", &f, &[])
+    }
+
+    /// Record without coalescing by clearing the edit timestamp first.
+    fn record(h: &mut FilterHistory, ids: &str, count: usize) {
+        h.last_record = None;
+        h.record(snap(ids), ids.into(), count);
+    }
+
+    #[test]
+    fn undo_redo_walks_entries() {
+        let mut h = FilterHistory::default();
+        record(&mut h, "", 100);
+        record(&mut h, "4625", 10);
+        record(&mut h, "4624", 20);
+
+        assert_eq!(h.undo(), Some(&snap("4625")));
+        assert_eq!(h.undo(), Some(&snap("")));
+        assert!(h.undo().is_none());
+        assert_eq!(h.redo(), Some(&snap("4625")));
+    }
+
+    #[test]
+    fn new_edit_discards_redo_tail() {
+        let mut h = FilterHistory::default();
+        record(&mut h, "", 100);
+        record(&mut h, "1", 1);
+        h.undo();
+        record(&mut h, "2", 2);
+        assert_eq!(h.entries().len(), 2);
+        assert!(!h.can_redo());
+    }
+
+    #[test]
+    fn same_snapshot_updates_count_only() {
+        let mut h = FilterHistory::default();
+        record(&mut h, "4625", 10);
+        record(&mut h, "4625", 12);
+        assert_eq!(h.entries().len(), 1);
+        assert_eq!(h.entries()[0].result_count, 12);
+    }
+
+    #[test]
+    fn rapid_edits_coalesce() {
+        let mut h = FilterHistory::default();
+        record(&mut h, "", 100);
+        h.record(snap("4"), "4".into(), 5);
+        h.record(snap("46"), "46".into(), 4);
+        h.record(snap("4625"), "4625".into(), 3);
+        assert_eq!(h.entries().len(), 2);
+        assert_eq!(h.entries()[1].summary, "4625");
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut h = FilterHistory::default();
+        for i in 0..(MAX_FILTER_HISTORY + 5) {
+            record(&mut h, &i.to_string(), i);
+        }
+        assert_eq!(h.entries().len(), MAX_FILTER_HISTORY);
+        assert_eq!(h.cursor(), MAX_FILTER_HISTORY - 1);
+    }
+}
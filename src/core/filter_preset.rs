@@ -2,15 +2,23 @@
 //!
 //! [`FilterPreset`] captures the user-visible subset of [`super::filter::FilterState`]
 //! and is serialised/deserialised via `serde` for persistent storage.
+//! [`CompoundPreset`] is the current on-disk format: an ordered list of
+//! [`GroupPreset`]s whose first entry is the primary filter. Older
+//! single-group `FilterPreset`s convert into it via `From`.
 
-use super::filter::FilterState;
+use super::filter::{FilterState, SearchScope};
+use super::filter_group::{FilterGroup, GroupMode};
+use super::pivot::FieldClause;
 
 /// A named, serialisable snapshot of the user-visible filter fields.
 ///
 /// Unlike [`FilterState`], this omits derived/parsed caches
 /// (`include_ids`, `exclude_ids`, `time_from`, `time_to`) which are
 /// recomputed from the input strings when the preset is loaded.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+///
+/// Fields added after the first release carry `#[serde(default)]` so
+/// presets saved by older versions still deserialise.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct FilterPreset {
     /// Display name shown in the preset list.
     pub name: String,
@@ -30,6 +38,18 @@ pub struct FilterPreset {
     pub case_sensitive: bool,
     /// Whether text search uses regex instead of substring matching.
     pub use_regex: bool,
+    /// Which event fields the text search covers.
+    #[serde(default)]
+    pub search_scope: SearchScope,
+    /// Comma-separated EventData keys for [`SearchScope::SpecificKeys`].
+    #[serde(default)]
+    pub search_keys: String,
+    /// Whole-word text matching flag.
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Exact-match clauses added by field pivots.
+    #[serde(default)]
+    pub field_clauses: Vec<FieldClause>,
 }
 
 impl FilterPreset {
@@ -45,6 +65,10 @@ impl FilterPreset {
             time_to_input: state.time_to_input.clone(),
             case_sensitive: state.case_sensitive,
             use_regex: state.use_regex,
+            search_scope: state.search_scope,
+            search_keys: state.search_keys.clone(),
+            whole_word: state.whole_word,
+            field_clauses: state.field_clauses.clone(),
         }
     }
 
@@ -59,6 +83,10 @@ impl FilterPreset {
             time_to_input: self.time_to_input.clone(),
             case_sensitive: self.case_sensitive,
             use_regex: self.use_regex,
+            search_scope: self.search_scope,
+            search_keys: self.search_keys.clone(),
+            whole_word: self.whole_word,
+            field_clauses: self.field_clauses.clone(),
             ..FilterState::default()
         };
         state.parse_event_ids();
@@ -66,3 +94,87 @@ impl FilterPreset {
         state
     }
 }
+
+/// One serialised criteria group inside a [`CompoundPreset`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GroupPreset {
+    /// Include or exclude semantics. Ignored for the first (primary) group,
+    /// which is always an include group.
+    #[serde(default)]
+    pub mode: GroupMode,
+    /// Whether the group takes part in matching.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// The group's criteria. `criteria.name` is unused.
+    pub criteria: FilterPreset,
+}
+
+/// Serde default helper for boolean fields that default to `true`.
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// A named preset holding the primary filter plus any extra OR/exclude
+/// groups.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CompoundPreset {
+    /// Display name shown in the preset list.
+    pub name: String,
+    /// Criteria groups; index 0 is the primary filter.
+    pub groups: Vec<GroupPreset>,
+}
+
+impl CompoundPreset {
+    /// Capture the primary filter and extra groups under `name`.
+    pub fn from_filters(name: &str, primary: &FilterState, groups: &[FilterGroup]) -> Self {
+        let mut out = Vec::with_capacity(groups.len() + 1);
+        out.push(GroupPreset {
+            mode: GroupMode::Include,
+            enabled: true,
+            criteria: FilterPreset::from_state("", primary),
+        });
+        out.extend(groups.iter().map(|g| GroupPreset {
+            mode: g.mode,
+            enabled: g.enabled,
+            criteria: FilterPreset::from_state("", &g.state),
+        }));
+        Self {
+            name: name.to_owned(),
+            groups: out,
+        }
+    }
+
+    /// Rebuild the fully-parsed primary filter and extra groups.
+    ///
+    /// A preset with no groups yields a pass-all primary filter.
+    pub fn to_filters(&self) -> (FilterState, Vec<FilterGroup>) {
+        let mut iter = self.groups.iter();
+        let primary = iter
+            .next()
+            .map(|g| g.criteria.to_filter_state())
+            .unwrap_or_default();
+        let groups = iter
+            .map(|g| FilterGroup {
+                mode: g.mode,
+                enabled: g.enabled,
+                state: g.criteria.to_filter_state(),
+            })
+            .collect();
+        (primary, groups)
+    }
+}
+
+impl From<FilterPreset> for CompoundPreset {
+    /// Wrap a legacy single-group preset as a compound preset whose only
+    /// group is the primary filter.
+    fn from(preset: FilterPreset) -> Self {
+        Self {
+            name: preset.name.clone(),
+            groups: vec![GroupPreset {
+                mode: GroupMode::Include,
+                enabled: true,
+                criteria: preset,
+            }],
+        }
+    }
+}
@@ -2,27 +2,17 @@
 #![allow(clippy::field_reassign_with_default)]
 
 use super::*;
-use chrono::Utc;
 
 fn make_event(id: u32, level: u8, provider: &str, message: &str) -> EventRecord {
     EventRecord {
-        raw_xml: String::new(),
         channel: "Application".into(),
         event_id: id,
         level,
         level_name: EventRecord::level_to_name(level).into(),
         provider_name: provider.into(),
-        timestamp: Utc::now(),
         computer: "TEST-PC".into(),
         message: message.into(),
-        process_id: 0,
-        thread_id: 0,
-        task: 0,
-        opcode: 0,
-        keywords: 0,
-        activity_id: None,
-        user_sid: None,
-        event_data: vec![],
+        ..EventRecord::test_base()
     }
 }
 
@@ -174,3 +164,136 @@ fn test_preset_preserves_regex_flag() {
     assert!(restored.use_regex);
     assert_eq!(restored.text_search, r"\d+");
 }
+
+// ── Search scope & whole-word tests ─────────────────────────────
+
+fn make_event_with_data(message: &str, data: &[(&str, &str)]) -> EventRecord {
+    let mut e = make_event(4625, 4, "Microsoft-Windows-Security-Auditing", message);
+    e.raw_xml =
+        "<Event><System><Provider Name='x'/></System><EventData></EventData></Event>".into();
+    e.event_data = data
+        .iter()
+        .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+        .collect();
+    e
+}
+
+#[test]
+fn test_scope_all_fields_matches_xml_markup() {
+    let mut f = FilterState::default();
+    f.text_search = "System".into();
+    f.parse_event_ids();
+    // Default scope keeps the original behaviour: XML markup matches.
+    assert!(f.matches(&make_event_with_data("Logon failed", &[])));
+}
+
+#[test]
+fn test_scope_message_only_ignores_xml() {
+    let mut f = FilterState::default();
+    f.text_search = "System".into();
+    f.search_scope = SearchScope::MessageOnly;
+    f.parse_event_ids();
+    assert!(!f.matches(&make_event_with_data("Logon failed", &[])));
+    assert!(f.matches(&make_event_with_data("System restarted", &[])));
+}
+
+#[test]
+fn test_scope_event_data_values_ignores_keys() {
+    let mut f = FilterState::default();
+    f.text_search = "targetusername".into();
+    f.search_scope = SearchScope::EventDataValues;
+    f.parse_event_ids();
+    let e = make_event_with_data("m", &[("TargetUserName", "alice")]);
+    assert!(!f.matches(&e));
+
+    f.text_search = "ALICE".into();
+    f.parse_event_ids();
+    assert!(f.matches(&e));
+}
+
+#[test]
+fn test_scope_specific_keys() {
+    let mut f = FilterState::default();
+    f.text_search = "10.0.0.5".into();
+    f.search_scope = SearchScope::SpecificKeys;
+    f.search_keys = "ipaddress, WorkstationName".into();
+    f.parse_event_ids();
+    let hit = make_event_with_data("m", &[("IpAddress", "10.0.0.5")]);
+    let miss = make_event_with_data("m", &[("SubjectIp", "10.0.0.5")]);
+    assert!(f.matches(&hit));
+    assert!(!f.matches(&miss));
+}
+
+#[test]
+fn test_scope_xml_only() {
+    let mut f = FilterState::default();
+    f.text_search = "EventData".into();
+    f.search_scope = SearchScope::Xml;
+    f.parse_event_ids();
+    assert!(f.matches(&make_event_with_data("nothing", &[])));
+    f.text_search = "nothing".into();
+    f.parse_event_ids();
+    assert!(!f.matches(&make_event_with_data("nothing", &[])));
+}
+
+#[test]
+fn test_whole_word_case_insensitive() {
+    let mut f = FilterState::default();
+    f.text_search = "data".into();
+    f.whole_word = true;
+    f.search_scope = SearchScope::MessageOnly;
+    f.parse_event_ids();
+    assert!(f.matches(&make_event(1, 4, "P", "Invalid data received")));
+    assert!(!f.matches(&make_event(1, 4, "P", "Database offline")));
+}
+
+#[test]
+fn test_whole_word_case_sensitive() {
+    let mut f = FilterState::default();
+    f.text_search = "Data".into();
+    f.whole_word = true;
+    f.case_sensitive = true;
+    f.parse_event_ids();
+    assert!(f.matches(&make_event(1, 4, "P", "Data lost")));
+    assert!(!f.matches(&make_event(1, 4, "P", "data lost")));
+    assert!(!f.matches(&make_event(1, 4, "P", "Database lost")));
+}
+
+#[test]
+fn test_whole_word_regex_keeps_alternation_grouped() {
+    let mut f = FilterState::default();
+    f.use_regex = true;
+    f.whole_word = true;
+    f.text_search = "fail|deny".into();
+    f.parse_event_ids();
+    assert!(f.matches(&make_event(1, 4, "P", "deny access")));
+    assert!(f.matches(&make_event(1, 4, "P", "will fail")));
+    assert!(!f.matches(&make_event(1, 4, "P", "failure")));
+}
+
+#[test]
+fn test_preset_preserves_scope_and_whole_word() {
+    let mut f = FilterState::default();
+    f.search_scope = SearchScope::SpecificKeys;
+    f.search_keys = "TargetUserName".into();
+    f.whole_word = true;
+
+    let restored =
+        crate::core::filter_preset::FilterPreset::from_state("scope", &f).to_filter_state();
+    assert_eq!(restored.search_scope, SearchScope::SpecificKeys);
+    assert_eq!(
+        restored.search_keys_lower,
+        vec!["targetusername".to_string()]
+    );
+    assert!(restored.whole_word);
+}
+
+#[test]
+fn test_summary_lists_active_criteria() {
+    assert_eq!(FilterState::default().summary(), "All events");
+
+    let mut f = FilterState::default();
+    f.event_id_input = "4625".into();
+    f.levels = [false, false, true, false, false, false];
+    assert_eq!(f.summary(), "ID 4625 \u{00B7} Error");
+}
@@ -0,0 +1,544 @@
+//! Indicator-of-compromise (IOC) list import and scanning.
+//!
+//! Indicator lists (IPs, domains, hashes, user names, paths, URLs) are
+//! imported from plain text, CSV or STIX 2.1 JSON and compiled into a
+//! single Aho-Corasick automaton, so every event's message and EventData
+//! values are scanned in one pass regardless of how many indicators are
+//! loaded. Hits are stored as indices into the scanned event list, like
+//! detection hits.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_IOC_INDICATORS;
+use crate::util::error::{EventSleuthError, Result};
+
+/// Indicators shorter than this are ignored: they would match almost
+/// every event.
+const MIN_INDICATOR_LEN: usize = 3;
+
+/// STIX cyber-observable types whose properties are imported as
+/// indicators. Domain objects (identity, malware, report, ...) also carry
+/// a `name`, but it describes the object rather than an observable.
+const STIX_OBSERVABLE_TYPES: &[&str] = &[
+    "ipv4-addr",
+    "ipv6-addr",
+    "domain-name",
+    "url",
+    "file",
+    "directory",
+    "user-account",
+    "process",
+];
+
+/// Extensions that make a dotted bare value a file name rather than a
+/// domain (`payload.exe`). `.com` stays a domain.
+const FILE_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "sys", "scr", "cpl", "ocx", "msi", "ps1", "psm1", "bat", "cmd", "vbs", "vbe",
+    "js", "jse", "wsf", "hta", "lnk", "jar", "docm", "xlsm", "pptm", "iso", "img", "tmp",
+];
+
+/// What an indicator represents. Only used for display; matching is the
+/// same for every kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IocKind {
+    Ip,
+    Domain,
+    Url,
+    Hash,
+    User,
+    Path,
+    Other,
+}
+
+impl IocKind {
+    /// Short label for the results view.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ip => "IP",
+            Self::Domain => "Domain",
+            Self::Url => "URL",
+            Self::Hash => "Hash",
+            Self::User => "User",
+            Self::Path => "Path",
+            Self::Other => "Other",
+        }
+    }
+
+    /// Guess the kind of a bare value (text lists, CSV without a type
+    /// column).
+    pub fn classify(value: &str) -> Self {
+        if value.parse::<std::net::IpAddr>().is_ok() {
+            return Self::Ip;
+        }
+        let lower = value.to_ascii_lowercase();
+        if lower.starts_with("http://") || lower.starts_with("https://") {
+            return Self::Url;
+        }
+        if matches!(value.len(), 32 | 40 | 64 | 128) && value.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Self::Hash;
+        }
+        if value.contains('\\') || value.contains('/') {
+            return Self::Path;
+        }
+        // Checked before the domain heuristic: `payload.exe` is a file.
+        let ext = lower.rsplit_once('.').map(|(_, ext)| ext);
+        if ext.is_some_and(|ext| FILE_EXTENSIONS.contains(&ext)) {
+            return Self::Path;
+        }
+        let domain_like = value.contains('.')
+            && value
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-')
+            && value
+                .rsplit('.')
+                .next()
+                .is_some_and(|tld| tld.len() >= 2 && tld.bytes().all(|b| b.is_ascii_alphabetic()));
+        if domain_like {
+            return Self::Domain;
+        }
+        Self::Other
+    }
+
+    /// Map a CSV type column value (`ip`, `sha256`, `username`, ...).
+    fn from_type_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Some(match name.as_str() {
+            "ip" | "ipv4" | "ipv6" | "ip-dst" | "ip-src" | "ipv4-addr" | "ipv6-addr" => Self::Ip,
+            "domain" | "hostname" | "fqdn" | "domain-name" => Self::Domain,
+            "url" | "uri" => Self::Url,
+            "hash" | "md5" | "sha1" | "sha256" | "sha512" | "imphash" | "filehash" => Self::Hash,
+            "user" | "username" | "account" | "user-account" => Self::User,
+            "path" | "file" | "filename" | "filepath" | "file-path" => Self::Path,
+            _ => return None,
+        })
+    }
+
+    /// Map a STIX object type (and property path) to a kind.
+    fn from_stix(object: &str, property: &str) -> Self {
+        match object {
+            "ipv4-addr" | "ipv6-addr" => Self::Ip,
+            "domain-name" => Self::Domain,
+            "url" => Self::Url,
+            "user-account" => Self::User,
+            "directory" | "process" => Self::Path,
+            "file" if property.starts_with("hashes") => Self::Hash,
+            "file" => Self::Path,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// One indicator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ioc {
+    pub value: String,
+    pub kind: IocKind,
+    /// File name of the list it came from.
+    pub source: String,
+}
+
+impl Ioc {
+    fn new(value: &str, kind: Option<IocKind>, source: &str) -> Option<Self> {
+        let value = refang(value.trim().trim_matches(|c| c == '"' || c == '\''));
+        if value.len() < MIN_INDICATOR_LEN {
+            return None;
+        }
+        Some(Self {
+            kind: kind.unwrap_or_else(|| IocKind::classify(&value)),
+            value,
+            source: source.to_owned(),
+        })
+    }
+}
+
+/// Undo common defanging (`1.2.3[.]4`, `hxxp://`, `evil(.)com`).
+fn refang(value: &str) -> String {
+    let mut out = value
+        .replace("[.]", ".")
+        .replace("(.)", ".")
+        .replace("[:]", ":")
+        .replace("[://]", "://");
+    for (defanged, plain) in [("hxxps://", "https://"), ("hxxp://", "http://")] {
+        if out.len() >= defanged.len() && out[..defanged.len()].eq_ignore_ascii_case(defanged) {
+            out.replace_range(..defanged.len(), plain);
+        }
+    }
+    out
+}
+
+/// Read an indicator list, choosing the parser from the extension
+/// (`.json` / `.stix` → STIX, `.csv` → CSV, anything else → one
+/// indicator per line).
+pub fn load_file(path: &Path) -> Result<Vec<Ioc>> {
+    let text = std::fs::read_to_string(path)?;
+    let source = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    match ext.as_str() {
+        "json" | "stix" => parse_stix(&text, &source),
+        "csv" => parse_csv(&text, &source),
+        _ => Ok(parse_text(&text, &source)),
+    }
+}
+
+/// One indicator per line; blank lines and `#` comments are skipped.
+pub fn parse_text(text: &str, source: &str) -> Vec<Ioc> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| Ioc::new(l, None, source))
+        .collect()
+}
+
+/// CSV with an optional header. The value column is the one named
+/// `value`, `indicator`, `ioc` or `observable` (else the first column);
+/// a `type` column, if present, sets the kind.
+pub fn parse_csv(text: &str, source: &str) -> Result<Vec<Ioc>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .comment(Some(b'#'))
+        .from_reader(text.as_bytes());
+    let mut records = reader.records();
+    let Some(first) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let first = first.map_err(|e| EventSleuthError::IocImport(e.to_string()))?;
+
+    let column = |names: &[&str]| {
+        first
+            .iter()
+            .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+    };
+    let value_col = column(&["value", "indicator", "ioc", "observable"]);
+    let type_col = column(&["type", "kind", "ioc_type", "indicator_type"]);
+    let has_header = value_col.is_some() || type_col.is_some();
+    let value_col = value_col.unwrap_or(0);
+
+    let mut iocs = Vec::new();
+    let mut push = |record: &csv::StringRecord| {
+        let kind = type_col
+            .and_then(|c| record.get(c))
+            .and_then(IocKind::from_type_name);
+        if let Some(ioc) = record
+            .get(value_col)
+            .and_then(|v| Ioc::new(v, kind, source))
+        {
+            iocs.push(ioc);
+        }
+    };
+    if !has_header {
+        push(&first);
+    }
+    for record in records {
+        let record = record.map_err(|e| EventSleuthError::IocImport(e.to_string()))?;
+        push(&record);
+    }
+    Ok(iocs)
+}
+
+/// STIX 2.1 JSON: a bundle (or bare object / array) of `indicator`
+/// objects, whose `pattern` comparisons (`[ipv4-addr:value = '…']`) are
+/// extracted, and cyber-observable objects ([`STIX_OBSERVABLE_TYPES`])
+/// carrying a `value`, `hashes`, `name` or `user_id`. Other objects are
+/// skipped.
+pub fn parse_stix(text: &str, source: &str) -> Result<Vec<Ioc>> {
+    let doc: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| EventSleuthError::IocImport(e.to_string()))?;
+    let objects: Vec<&serde_json::Value> = match &doc {
+        serde_json::Value::Array(items) => items.iter().collect(),
+        obj => match obj.get("objects").and_then(|o| o.as_array()) {
+            Some(items) => items.iter().collect(),
+            None => vec![obj],
+        },
+    };
+
+    // `object-type:property.path = 'value'`, with `\'` and `\\` escapes.
+    let comparison = regex::Regex::new(r"([a-z0-9-]+):([^\s=\]]+)\s*=\s*'((?:[^'\\]|\\.)*)'")
+        .expect("static regex");
+
+    let mut iocs = Vec::new();
+    for obj in objects {
+        let ty = obj.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        if ty == "indicator" {
+            let pattern = obj
+                .get("pattern")
+                .and_then(|p| p.as_str())
+                .unwrap_or_default();
+            for cap in comparison.captures_iter(pattern) {
+                let value = cap[3].replace("\\'", "'").replace("\\\\", "\\");
+                let kind = IocKind::from_stix(&cap[1], &cap[2]);
+                iocs.extend(Ioc::new(&value, Some(kind), source));
+            }
+            continue;
+        }
+        if !STIX_OBSERVABLE_TYPES.contains(&ty) {
+            continue;
+        }
+        for prop in ["value", "name", "user_id", "account_login", "path"] {
+            if let Some(v) = obj.get(prop).and_then(|v| v.as_str()) {
+                iocs.extend(Ioc::new(v, Some(IocKind::from_stix(ty, prop)), source));
+            }
+        }
+        if let Some(hashes) = obj.get("hashes").and_then(|h| h.as_object()) {
+            for v in hashes.values().filter_map(|v| v.as_str()) {
+                iocs.extend(Ioc::new(v, Some(IocKind::Hash), source));
+            }
+        }
+    }
+    Ok(iocs)
+}
+
+/// Loaded indicators, their compiled matcher, and the events they hit.
+#[derive(Debug, Default)]
+pub struct IocScanner {
+    /// Loaded indicators, in import order.
+    pub indicators: Vec<Ioc>,
+    /// Per-indicator hits (parallel to `indicators`): sorted event indices.
+    pub hits: Vec<Vec<usize>>,
+    /// Indicator indices matched by each event with at least one hit.
+    by_event: HashMap<usize, Vec<usize>>,
+    matcher: Option<AhoCorasick>,
+}
+
+impl IocScanner {
+    /// Add indicators (duplicates, compared case-insensitively, are
+    /// dropped) and rebuild the matcher. Existing hits are cleared; the
+    /// caller rescans. Returns how many indicators were added.
+    pub fn add(&mut self, iocs: Vec<Ioc>) -> Result<usize> {
+        let mut seen: HashSet<String> = self
+            .indicators
+            .iter()
+            .map(|i| i.value.to_ascii_lowercase())
+            .collect();
+        let before = self.indicators.len();
+        for ioc in iocs {
+            if self.indicators.len() >= MAX_IOC_INDICATORS {
+                tracing::warn!("IOC limit of {} reached; rest ignored", MAX_IOC_INDICATORS);
+                break;
+            }
+            if seen.insert(ioc.value.to_ascii_lowercase()) {
+                self.indicators.push(ioc);
+            }
+        }
+        let matcher = AhoCorasickBuilder::new()
+            .ascii_case_insensitive(true)
+            .match_kind(MatchKind::Standard)
+            .build(self.indicators.iter().map(|i| &i.value))
+            .map_err(|e| EventSleuthError::IocImport(e.to_string()))?;
+        self.matcher = Some(matcher);
+        self.clear_hits();
+        Ok(self.indicators.len() - before)
+    }
+
+    /// Indicator indices found in `event`'s message or EventData values,
+    /// sorted and deduplicated.
+    ///
+    /// A match only counts when it is not glued to further letters or
+    /// digits, so `10.0.0.1` does not match inside `10.0.0.12`.
+    pub fn matches(&self, event: &EventRecord) -> Vec<usize> {
+        let Some(ac) = &self.matcher else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        let texts = std::iter::once(event.message.as_str())
+            .chain(event.event_data.iter().map(|(_, v)| v.as_str()));
+        for text in texts {
+            let bytes = text.as_bytes();
+            for m in ac.find_overlapping_iter(text) {
+                let before = m.start().checked_sub(1).map(|i| bytes[i]);
+                let after = bytes.get(m.end()).copied();
+                let glued = |b: Option<u8>| b.is_some_and(|b| b.is_ascii_alphanumeric());
+                if !glued(before) && !glued(after) {
+                    found.push(m.pattern().as_usize());
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    /// Scan `events[start..]`, recording hits.
+    pub fn scan(&mut self, events: &[EventRecord], start: usize) {
+        if self.matcher.is_none() {
+            return;
+        }
+        self.hits.resize(self.indicators.len(), Vec::new());
+        for (i, event) in events.iter().enumerate().skip(start) {
+            let found = self.matches(event);
+            if found.is_empty() {
+                continue;
+            }
+            for &ioc in &found {
+                self.hits[ioc].push(i);
+            }
+            self.by_event.insert(i, found);
+        }
+    }
+
+    /// Indicators matched by event `idx`, if any.
+    pub fn event_hits(&self, idx: usize) -> Option<&[usize]> {
+        self.by_event.get(&idx).map(Vec::as_slice)
+    }
+
+    /// Number of events with at least one hit.
+    pub fn hit_event_count(&self) -> usize {
+        self.by_event.len()
+    }
+
+    /// Every event with at least one hit, sorted.
+    pub fn hit_events(&self) -> Vec<usize> {
+        let mut events: Vec<usize> = self.by_event.keys().copied().collect();
+        events.sort_unstable();
+        events
+    }
+
+    /// Re-base hit indices after the `evict` oldest events were dropped.
+    pub fn shift(&mut self, evict: usize) {
+        for hits in &mut self.hits {
+            let keep_from = hits.partition_point(|&i| i < evict);
+            hits.drain(..keep_from);
+            for i in hits.iter_mut() {
+                *i -= evict;
+            }
+        }
+        self.by_event = std::mem::take(&mut self.by_event)
+            .into_iter()
+            .filter_map(|(i, found)| Some((i.checked_sub(evict)?, found)))
+            .collect();
+    }
+
+    /// Forget all hits (the event list was replaced), keeping indicators.
+    pub fn clear_hits(&mut self) {
+        self.hits = vec![Vec::new(); self.indicators.len()];
+        self.by_event.clear();
+    }
+
+    /// Unload every indicator.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(message: &str, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: 4624,
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            message: message.into(),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn text_list_is_refanged_and_classified() {
+        let iocs = parse_text(
+            "# campaign X\n10.1.2[.]3\n\nevil-domain(.)com\nhxxp://bad.example/x\nd41d8cd98f00b204e9800998ecf8427e\nC:\\Temp\\x.exe\npayload.exe\nsvc_backup\nab\n",
+            "list.txt",
+        );
+        let got: Vec<(&str, IocKind)> = iocs.iter().map(|i| (i.value.as_str(), i.kind)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("10.1.2.3", IocKind::Ip),
+                ("evil-domain.com", IocKind::Domain),
+                ("http://bad.example/x", IocKind::Url),
+                ("d41d8cd98f00b204e9800998ecf8427e", IocKind::Hash),
+                ("C:\\Temp\\x.exe", IocKind::Path),
+                ("payload.exe", IocKind::Path),
+                ("svc_backup", IocKind::Other),
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_uses_value_and_type_columns() {
+        let iocs = parse_csv(
+            "type,indicator,comment\nusername,jdoe,phished\nsha256,ABC123,\n",
+            "feed.csv",
+        )
+        .unwrap();
+        assert_eq!(iocs.len(), 2);
+        assert_eq!(
+            (iocs[0].value.as_str(), iocs[0].kind),
+            ("jdoe", IocKind::User)
+        );
+        assert_eq!(iocs[1].kind, IocKind::Hash);
+
+        // No header: first column, kind guessed.
+        let iocs = parse_csv("192.168.5.5,seen twice\n", "raw.csv").unwrap();
+        assert_eq!(iocs[0].kind, IocKind::Ip);
+    }
+
+    #[test]
+    fn stix_patterns_and_observables() {
+        let bundle = r#"{
+            "type": "bundle",
+            "objects": [
+                {"type": "indicator", "pattern_type": "stix",
+                 "pattern": "[ipv4-addr:value = '203.0.113.7'] OR [file:hashes.'SHA-256' = 'aa11bb22cc33']"},
+                {"type": "indicator", "pattern": "[user-account:user_id = 'o\\'brien']"},
+                {"type": "domain-name", "value": "c2.example.net"},
+                {"type": "file", "name": "payload.dll", "hashes": {"MD5": "0123456789abcdef0123456789abcdef"}},
+                {"type": "identity", "name": "ACME Threat Intel"},
+                {"type": "malware", "name": "Emotet", "is_family": true},
+                {"type": "attack-pattern", "name": "Spearphishing Attachment"}
+            ]
+        }"#;
+        let iocs = parse_stix(bundle, "bundle.json").unwrap();
+        let got: Vec<(&str, IocKind)> = iocs.iter().map(|i| (i.value.as_str(), i.kind)).collect();
+        assert_eq!(
+            got,
+            vec![
+                ("203.0.113.7", IocKind::Ip),
+                ("aa11bb22cc33", IocKind::Hash),
+                ("o'brien", IocKind::User),
+                ("c2.example.net", IocKind::Domain),
+                ("payload.dll", IocKind::Path),
+                ("0123456789abcdef0123456789abcdef", IocKind::Hash),
+            ]
+        );
+        assert!(parse_stix("not json", "x.json").is_err());
+    }
+
+    #[test]
+    fn scanner_matches_on_boundaries_case_insensitively() {
+        let mut scanner = IocScanner::default();
+        let added = scanner
+            .add(parse_text("10.0.0.1\nEVIL.example\n10.0.0.1\n", "t"))
+            .unwrap();
+        assert_eq!(added, 2);
+
+        let events = vec![
+            event("Connection from 10.0.0.12", &[]),
+            event("", &[("IpAddress", "10.0.0.1")]),
+            event("lookup evil.example.", &[("IpAddress", "::1")]),
+            event("nothing", &[]),
+        ];
+        scanner.scan(&events, 0);
+        assert_eq!(scanner.hits, vec![vec![1], vec![2]]);
+        assert_eq!(scanner.hit_events(), vec![1, 2]);
+        assert_eq!(scanner.event_hits(2), Some(&[1][..]));
+
+        scanner.shift(2);
+        assert_eq!(scanner.hits, vec![vec![], vec![0]]);
+        assert_eq!(scanner.hit_events(), vec![0]);
+    }
+}
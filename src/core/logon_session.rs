@@ -0,0 +1,328 @@
+//! Logon session reconstruction from Security audit events.
+//!
+//! Pairs successful logons (4624), special privileges (4672), explicit
+//! credential use (4648) and logoffs (4634 / 4647) that share a logon ID
+//! and user name into [`LogonSession`]s; failed logons (4625) become
+//! sessions of their own. Everything is read from the already-parsed
+//! EventData, so it works for live logs and `.evtx` files alike.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_LOGON_SESSIONS;
+
+/// Outcome of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Logged on with no matching logoff in the loaded events.
+    Open,
+    /// Logoff seen.
+    Closed,
+    /// Failed logon attempt (4625).
+    Failed,
+}
+
+/// One reconstructed logon session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogonSession {
+    /// Logon ID (`0x…`, lower-case); `None` for failed attempts.
+    pub logon_id: Option<String>,
+    /// `DOMAIN\user`, or just the user when no domain was logged.
+    pub user: String,
+    pub computer: String,
+    pub logon_type: Option<u32>,
+    pub source_ip: Option<String>,
+    pub workstation: Option<String>,
+    /// Time of the 4624/4625; `None` if the logon predates the loaded
+    /// events.
+    pub start: Option<DateTime<Utc>>,
+    /// Time of the last logoff event.
+    pub end: Option<DateTime<Utc>>,
+    pub status: SessionStatus,
+    /// Special privileges assigned at logon (4672).
+    pub privileges: Vec<String>,
+    /// Explicit credentials used from this session (4648), as
+    /// `user → server`.
+    pub explicit_credentials: Vec<String>,
+    /// Indices into the event list, in time order.
+    pub events: Vec<usize>,
+}
+
+impl LogonSession {
+    fn new(user: String, computer: &str, logon_id: Option<String>) -> Self {
+        Self {
+            logon_id,
+            user,
+            computer: computer.to_owned(),
+            logon_type: None,
+            source_ip: None,
+            workstation: None,
+            start: None,
+            end: None,
+            status: SessionStatus::Open,
+            privileges: Vec::new(),
+            explicit_credentials: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Logon-to-logoff time, when both ends were seen.
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.end? - self.start?)
+    }
+
+    /// Whether special (administrator-equivalent) privileges were assigned.
+    pub fn is_elevated(&self) -> bool {
+        !self.privileges.is_empty()
+    }
+
+    /// A logon that was seen but never closed.
+    pub fn never_closed(&self) -> bool {
+        self.status == SessionStatus::Open && self.start.is_some()
+    }
+
+    /// Earliest known time, used for ordering.
+    pub fn sort_time(&self) -> Option<DateTime<Utc>> {
+        self.start.or(self.end)
+    }
+}
+
+/// Display name of a Windows logon type.
+pub fn logon_type_name(logon_type: u32) -> &'static str {
+    match logon_type {
+        0 => "System",
+        2 => "Interactive",
+        3 => "Network",
+        4 => "Batch",
+        5 => "Service",
+        7 => "Unlock",
+        8 => "NetworkCleartext",
+        9 => "NewCredentials",
+        10 => "RemoteInteractive",
+        11 => "CachedInteractive",
+        12 => "CachedRemoteInteractive",
+        13 => "CachedUnlock",
+        _ => "Other",
+    }
+}
+
+/// Security event IDs consumed by [`reconstruct`].
+pub const SESSION_EVENT_IDS: &[u32] = &[4624, 4625, 4634, 4647, 4648, 4672];
+
+/// `DOMAIN\user` from the given EventData field names.
+fn account(event: &EventRecord, user_field: &str, domain_field: &str) -> Option<String> {
+    let user = event.recorded_data(user_field)?;
+    Some(match event.recorded_data(domain_field) {
+        Some(domain) => format!("{domain}\\{user}"),
+        None => user.to_owned(),
+    })
+}
+
+/// Reconstruct logon sessions from `events` (any order; only Security
+/// events with the IDs in [`SESSION_EVENT_IDS`] are used).
+///
+/// Returns sessions newest first, capped at [`MAX_LOGON_SESSIONS`].
+pub fn reconstruct(events: &[EventRecord]) -> Vec<LogonSession> {
+    let mut order: Vec<usize> = (0..events.len())
+        .filter(|&i| {
+            let e = &events[i];
+            e.channel == "Security" && SESSION_EVENT_IDS.contains(&e.event_id)
+        })
+        .collect();
+    order.sort_by_key(|&i| events[i].timestamp);
+
+    let mut done: Vec<LogonSession> = Vec::new();
+    // (computer, logon ID, user name), lower-cased → open session.
+    let mut open: HashMap<(String, String, String), LogonSession> = HashMap::new();
+
+    for i in order {
+        let e = &events[i];
+        let (id_field, user_field, domain_field) = match e.event_id {
+            4624 | 4625 | 4634 | 4647 => ("TargetLogonId", "TargetUserName", "TargetDomainName"),
+            _ => ("SubjectLogonId", "SubjectUserName", "SubjectDomainName"),
+        };
+        let Some(user) = account(e, user_field, domain_field) else {
+            continue;
+        };
+
+        if e.event_id == 4625 {
+            let mut s = LogonSession::new(user, &e.computer, None);
+            s.status = SessionStatus::Failed;
+            s.start = Some(e.timestamp);
+            s.logon_type = e.data("LogonType").and_then(|t| t.parse().ok());
+            s.source_ip = e.recorded_data("IpAddress").map(str::to_owned);
+            s.workstation = e.recorded_data("WorkstationName").map(str::to_owned);
+            s.events.push(i);
+            done.push(s);
+            continue;
+        }
+
+        let Some(logon_id) = e.recorded_data(id_field).map(|id| id.to_ascii_lowercase()) else {
+            continue;
+        };
+        let name = e.data(user_field).unwrap_or_default().to_ascii_lowercase();
+        let key = (e.computer.to_ascii_lowercase(), logon_id.clone(), name);
+
+        // A second logon with the same key starts a new session.
+        if e.event_id == 4624 && open.get(&key).is_some_and(|s| s.start.is_some()) {
+            done.extend(open.remove(&key));
+        }
+        let session = open
+            .entry(key)
+            .or_insert_with(|| LogonSession::new(user, &e.computer, Some(logon_id)));
+        session.events.push(i);
+
+        match e.event_id {
+            4624 => {
+                session.start = Some(e.timestamp);
+                session.logon_type = e.data("LogonType").and_then(|t| t.parse().ok());
+                session.source_ip = e.recorded_data("IpAddress").map(str::to_owned);
+                session.workstation = e.recorded_data("WorkstationName").map(str::to_owned);
+            }
+            4672 => {
+                session.privileges = e
+                    .data("PrivilegeList")
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect();
+            }
+            4648 => {
+                if let Some(target) = account(e, "TargetUserName", "TargetDomainName") {
+                    let server = e.recorded_data("TargetServerName").unwrap_or_default();
+                    let used = format!("{target} \u{2192} {server}");
+                    if !session.explicit_credentials.contains(&used) {
+                        session.explicit_credentials.push(used);
+                    }
+                }
+            }
+            // 4634 / 4647
+            _ => {
+                session.end = Some(e.timestamp);
+                session.status = SessionStatus::Closed;
+            }
+        }
+    }
+
+    done.extend(open.into_values());
+    done.sort_by_key(|s| std::cmp::Reverse(s.sort_time()));
+    done.truncate(MAX_LOGON_SESSIONS);
+    done
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level: 0,
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            timestamp: EventRecord::test_time(secs),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    /// A Target*-style event (4624/4625/4634/4647) for `CORP\user`.
+    fn target(
+        id: u32,
+        secs: i64,
+        logon_id: &str,
+        user: &str,
+        extra: &[(&str, &str)],
+    ) -> EventRecord {
+        let mut data = vec![
+            ("TargetLogonId", logon_id),
+            ("TargetUserName", user),
+            ("TargetDomainName", "CORP"),
+        ];
+        data.extend_from_slice(extra);
+        event(id, secs, &data)
+    }
+
+    #[test]
+    fn pairs_logon_privileges_and_logoff() {
+        let events = vec![
+            // Logoff listed first: input order must not matter.
+            target(4634, 600, "0x1A2B", "alice", &[]),
+            event(
+                4672,
+                0,
+                &[
+                    ("SubjectLogonId", "0x1a2b"),
+                    ("SubjectUserName", "alice"),
+                    ("SubjectDomainName", "CORP"),
+                    ("PrivilegeList", "SeDebugPrivilege\n\t\tSeBackupPrivilege"),
+                ],
+            ),
+            target(
+                4624,
+                0,
+                "0x1a2b",
+                "alice",
+                &[
+                    ("LogonType", "10"),
+                    ("IpAddress", "10.0.0.5"),
+                    ("WorkstationName", "-"),
+                ],
+            ),
+        ];
+        let sessions = reconstruct(&events);
+        assert_eq!(sessions.len(), 1);
+        let s = &sessions[0];
+        assert_eq!(s.user, "CORP\\alice");
+        assert_eq!(s.logon_id.as_deref(), Some("0x1a2b"));
+        assert_eq!(s.status, SessionStatus::Closed);
+        assert_eq!(s.logon_type.map(logon_type_name), Some("RemoteInteractive"));
+        assert_eq!(s.source_ip.as_deref(), Some("10.0.0.5"));
+        assert!(s.workstation.is_none());
+        assert_eq!(s.privileges, vec!["SeDebugPrivilege", "SeBackupPrivilege"]);
+        assert_eq!(s.duration(), Some(Duration::seconds(600)));
+        assert_eq!(s.events, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn flags_unclosed_and_failed_sessions() {
+        let events = vec![
+            target(4624, 0, "0x10", "bob", &[("LogonType", "3")]),
+            target(4625, 5, "-", "mallory", &[("IpAddress", "203.0.113.9")]),
+            // Same logon ID reused by a new logon: the first never closed.
+            target(4624, 10, "0x10", "bob", &[("LogonType", "3")]),
+            target(4647, 20, "0x10", "bob", &[]),
+        ];
+        let sessions = reconstruct(&events);
+        assert_eq!(sessions.len(), 3);
+        // Newest first.
+        assert_eq!(sessions[0].status, SessionStatus::Closed);
+        assert_eq!(sessions[1].status, SessionStatus::Failed);
+        assert_eq!(sessions[1].source_ip.as_deref(), Some("203.0.113.9"));
+        assert!(sessions[2].never_closed());
+        assert_eq!(sessions[2].events, vec![0]);
+    }
+
+    #[test]
+    fn logoff_without_logon_has_no_start() {
+        let sessions = reconstruct(&[target(4634, 0, "0x99", "carol", &[])]);
+        assert_eq!(sessions[0].start, None);
+        assert!(!sessions[0].never_closed());
+        assert!(sessions[0].duration().is_none());
+    }
+
+    #[test]
+    fn blank_and_dash_values_are_not_recorded() {
+        let sessions = reconstruct(&[target(
+            4624,
+            0,
+            "0x20",
+            "dave",
+            &[("IpAddress", " "), ("WorkstationName", "-")],
+        )]);
+        assert_eq!(sessions[0].source_ip, None);
+        assert_eq!(sessions[0].workstation, None);
+    }
+}
@@ -1,12 +1,42 @@
 //! Core domain modules for EventSleuth.
 //!
 //! Contains the event data model, background reader logic, XML parsing,
-//! channel enumeration, and in-memory filtering.
+//! channel enumeration, in-memory filtering, field pivots, the
+//! Sigma / correlation detection engines, IOC scanning, the
+//! investigation reconstructions (logon sessions, process trees,
+//! activity chains, boot sessions, service timelines), failed-logon and
+//! crash analysis, summary statistics, pivot tables and EventData value
+//! distributions, anomaly detection, and message template mining.
 
+pub mod activity;
+pub mod alert;
+pub mod anomaly;
+pub mod attack;
+pub mod boot_session;
+pub mod brute_force;
+pub mod builtin_rules;
 pub mod channel_enumerator;
+pub mod correlation;
+pub mod crash;
+pub mod crosstab;
+pub mod dedup;
 mod event_format;
 pub mod event_reader;
 pub mod event_record;
+pub mod field_stats;
 pub mod filter;
+pub mod filter_group;
+pub mod filter_history;
 pub mod filter_preset;
+pub mod ioc;
+pub mod logon_session;
+pub mod pivot;
+pub mod process_tree;
+pub mod query_by_example;
+pub mod service;
+pub mod sigma;
+pub mod sigma_condition;
+pub mod stats;
+pub mod template;
+pub mod text_search;
 pub mod xml_parser;
@@ -0,0 +1,276 @@
+//! Field pivots: "filter to this value", "exclude this value", and
+//! "same value within ±N minutes".
+//!
+//! A pivot adds a [`FieldClause`] (or, for levels, adjusts the level
+//! checkboxes) to an existing [`FilterState`] without touching its other
+//! criteria. Clauses are exact, case-insensitive comparisons on a single
+//! header field or EventData value, so they complement the substring-based
+//! provider filter and free-text search.
+
+use crate::core::event_record::EventRecord;
+use crate::core::filter::FilterState;
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+/// An event field that can be pivoted on.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PivotField {
+    EventId,
+    Level,
+    Provider,
+    Channel,
+    Computer,
+    ProcessId,
+    ThreadId,
+    UserSid,
+    ActivityId,
+    RelatedActivityId,
+    /// A named EventData / UserData value.
+    EventData(String),
+}
+
+impl PivotField {
+    /// Human-readable field name for menus and clause chips.
+    pub fn label(&self) -> Cow<'_, str> {
+        match self {
+            PivotField::EventId => "Event ID".into(),
+            PivotField::Level => "Level".into(),
+            PivotField::Provider => "Provider".into(),
+            PivotField::Channel => "Channel".into(),
+            PivotField::Computer => "Computer".into(),
+            PivotField::ProcessId => "Process ID".into(),
+            PivotField::ThreadId => "Thread ID".into(),
+            PivotField::UserSid => "User SID".into(),
+            PivotField::ActivityId => "Activity ID".into(),
+            PivotField::RelatedActivityId => "Related Activity ID".into(),
+            PivotField::EventData(key) => Cow::Borrowed(key.as_str()),
+        }
+    }
+
+    /// The event's value for this field as text, or `None` when the event
+    /// does not carry it (no SID, no such EventData key, ...).
+    ///
+    /// Levels are rendered as their numeric value so they round-trip
+    /// through [`apply_pivot`].
+    pub fn value_of<'a>(&self, event: &'a EventRecord) -> Option<Cow<'a, str>> {
+        match self {
+            PivotField::EventId => Some(event.event_id.to_string().into()),
+            PivotField::Level => Some(event.level.to_string().into()),
+            PivotField::Provider => Some(event.provider_name.as_str().into()),
+            PivotField::Channel => Some(event.channel.as_str().into()),
+            PivotField::Computer => Some(event.computer.as_str().into()),
+            PivotField::ProcessId => Some(event.process_id.to_string().into()),
+            PivotField::ThreadId => Some(event.thread_id.to_string().into()),
+            PivotField::UserSid => event.user_sid.as_deref().map(Cow::Borrowed),
+            PivotField::ActivityId => event.activity_id.as_deref().map(Cow::Borrowed),
+            PivotField::RelatedActivityId => {
+                event.related_activity_id.as_deref().map(Cow::Borrowed)
+            }
+            PivotField::EventData(key) => event
+                .event_data
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| Cow::Borrowed(v.as_str())),
+        }
+    }
+}
+
+/// An exact-match condition on one field, added by a pivot.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FieldClause {
+    /// Which field to compare.
+    pub field: PivotField,
+    /// Value to compare against (case-insensitive).
+    pub value: String,
+    /// `true` = hide events with this value; `false` = show only them.
+    pub exclude: bool,
+}
+
+impl FieldClause {
+    /// Test the clause against `event`.
+    ///
+    /// An event that lacks the field fails an include clause and passes an
+    /// exclude clause.
+    pub fn matches(&self, event: &EventRecord) -> bool {
+        let equal = self
+            .field
+            .value_of(event)
+            .is_some_and(|v| v.eq_ignore_ascii_case(&self.value));
+        equal != self.exclude
+    }
+
+    /// Short description such as `Computer = DC01` or `IpAddress ≠ ::1`.
+    pub fn describe(&self) -> String {
+        let op = if self.exclude { "\u{2260}" } else { "=" };
+        format!("{} {op} {}", self.field.label(), self.value)
+    }
+}
+
+/// What a pivot does with the chosen field value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotAction {
+    /// Keep only events with this value.
+    FilterTo,
+    /// Hide events with this value.
+    Exclude,
+    /// Keep only events with this value within ±N minutes of the source
+    /// event's timestamp.
+    Nearby,
+}
+
+/// A pivot chosen from a context menu, applied by the app after the
+/// frame's UI pass.
+#[derive(Debug, Clone)]
+pub struct PivotRequest {
+    pub field: PivotField,
+    pub value: String,
+    pub action: PivotAction,
+    /// Timestamp of the event the pivot was launched from (used by
+    /// [`PivotAction::Nearby`]).
+    pub at: DateTime<Utc>,
+}
+
+/// Add the clause described by `req` to `filter`, keeping its other
+/// criteria.
+///
+/// Level pivots adjust [`FilterState::levels`] instead of adding a clause
+/// so the Level checkboxes stay the single source of truth for severity.
+/// A clause on the same field and value with the opposite sense is
+/// replaced rather than kept alongside (which would match nothing).
+/// The caller must re-parse the filter afterwards.
+pub fn apply_pivot(filter: &mut FilterState, req: &PivotRequest, window_minutes: i64) {
+    if req.field == PivotField::Level {
+        if let Ok(level) = req.value.parse::<usize>() {
+            let level = level.min(5);
+            match req.action {
+                PivotAction::Exclude => filter.levels[level] = false,
+                PivotAction::FilterTo | PivotAction::Nearby => {
+                    for (i, on) in filter.levels.iter_mut().enumerate() {
+                        *on = i == level;
+                    }
+                }
+            }
+        }
+    } else {
+        let clause = FieldClause {
+            field: req.field.clone(),
+            value: req.value.clone(),
+            exclude: req.action == PivotAction::Exclude,
+        };
+        filter
+            .field_clauses
+            .retain(|c| !(c.field == clause.field && c.value.eq_ignore_ascii_case(&clause.value)));
+        filter.field_clauses.push(clause);
+    }
+
+    if req.action == PivotAction::Nearby {
+        let window = chrono::Duration::minutes(window_minutes.max(1));
+        filter.set_time_window(req.at - window, req.at + window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(computer: &str, ip: &str) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: 4625,
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            computer: computer.into(),
+            event_data: vec![("IpAddress".into(), ip.into())],
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn request(field: PivotField, value: &str, action: PivotAction) -> PivotRequest {
+        PivotRequest {
+            field,
+            value: value.into(),
+            action,
+            at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn filter_to_keeps_existing_criteria() {
+        let mut f = FilterState {
+            event_id_input: "4625".into(),
+            ..Default::default()
+        };
+        apply_pivot(
+            &mut f,
+            &request(PivotField::Computer, "dc01", PivotAction::FilterTo),
+            5,
+        );
+        f.parse_event_ids();
+        assert_eq!(f.event_id_input, "4625");
+        assert!(f.matches(&event("DC01", "10.0.0.1")));
+        assert!(!f.matches(&event("WS01", "10.0.0.1")));
+    }
+
+    #[test]
+    fn exclude_event_data_value_replaces_opposite_clause() {
+        let mut f = FilterState::default();
+        let key = PivotField::EventData("IpAddress".into());
+        apply_pivot(
+            &mut f,
+            &request(key.clone(), "10.0.0.1", PivotAction::FilterTo),
+            5,
+        );
+        apply_pivot(&mut f, &request(key, "10.0.0.1", PivotAction::Exclude), 5);
+        assert_eq!(f.field_clauses.len(), 1);
+        assert!(!f.matches(&event("DC01", "10.0.0.1")));
+        assert!(f.matches(&event("DC01", "10.0.0.2")));
+    }
+
+    #[test]
+    fn nearby_sets_time_window() {
+        let mut f = FilterState::default();
+        let req = request(PivotField::Computer, "DC01", PivotAction::Nearby);
+        apply_pivot(&mut f, &req, 10);
+        // Bounds round-trip through the second-precision input fields.
+        let from = f.time_from.expect("lower bound");
+        let to = f.time_to.expect("upper bound");
+        assert!((599..=601).contains(&(req.at - from).num_seconds()));
+        assert!((599..=601).contains(&(to - req.at).num_seconds()));
+        assert_eq!(f.field_clauses.len(), 1);
+    }
+
+    #[test]
+    fn level_pivot_uses_checkboxes() {
+        let mut f = FilterState::default();
+        apply_pivot(
+            &mut f,
+            &request(PivotField::Level, "2", PivotAction::FilterTo),
+            5,
+        );
+        assert_eq!(f.levels, [false, false, true, false, false, false]);
+        assert!(f.field_clauses.is_empty());
+    }
+
+    #[test]
+    fn related_activity_pivot_matches_the_related_field() {
+        let mut f = FilterState::default();
+        apply_pivot(
+            &mut f,
+            &request(
+                PivotField::RelatedActivityId,
+                "{AAAA}",
+                PivotAction::FilterTo,
+            ),
+            5,
+        );
+        let child = EventRecord {
+            related_activity_id: Some("{AAAA}".into()),
+            ..event("WS01", "")
+        };
+        let parent = EventRecord {
+            activity_id: Some("{AAAA}".into()),
+            ..event("WS01", "")
+        };
+        assert!(f.matches(&child));
+        assert!(!f.matches(&parent));
+    }
+}
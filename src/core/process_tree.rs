@@ -0,0 +1,412 @@
+//! Process tree reconstruction from process-creation events.
+//!
+//! Two sources are understood:
+//! - Security 4688 / 4689, linked by `NewProcessId` → `ProcessId` (hex
+//!   PIDs, so a reused PID maps to whichever process was alive at the
+//!   time);
+//! - Sysmon 1 / 5, linked by `ProcessGuid` → `ParentProcessGuid`.
+//!
+//! Sysmon network (3), file (11) and registry (12-14) events are attached
+//! to the process that owns them. Parents that were not themselves
+//! captured become placeholder nodes, so siblings still group together.
+//! A process audited by both sources appears once per source.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_PROCESS_NODES;
+
+const SYSMON_CHANNEL: &str = "Microsoft-Windows-Sysmon/Operational";
+
+/// One process.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessNode {
+    pub pid: u32,
+    /// Full image path.
+    pub image: String,
+    pub command_line: String,
+    pub user: String,
+    /// `Low`, `Medium`, `High`, `System`, ...
+    pub integrity: String,
+    pub computer: String,
+    /// Creation time; `None` for placeholder parents.
+    pub start: Option<DateTime<Utc>>,
+    /// Termination time, if seen.
+    pub end: Option<DateTime<Utc>>,
+    /// Index of the parent node.
+    pub parent: Option<usize>,
+    /// Child node indices, in creation order.
+    pub children: Vec<usize>,
+    /// Creation, termination and attached activity events (indices into
+    /// the event list, time order).
+    pub events: Vec<usize>,
+    /// Attached Sysmon network connections.
+    pub network: usize,
+    /// Attached Sysmon file creations.
+    pub files: usize,
+    /// Attached Sysmon registry events.
+    pub registry: usize,
+}
+
+impl ProcessNode {
+    /// File name of the image (`cmd.exe`).
+    pub fn image_name(&self) -> &str {
+        self.image
+            .rsplit(['\\', '/'])
+            .next()
+            .filter(|n| !n.is_empty())
+            .unwrap_or("(unknown)")
+    }
+
+    /// Whether this node was inferred from a child rather than captured.
+    pub fn is_placeholder(&self) -> bool {
+        self.start.is_none()
+    }
+
+    /// Creation-to-termination time, when both were seen.
+    pub fn lifetime(&self) -> Option<Duration> {
+        Some(self.end? - self.start?)
+    }
+}
+
+/// All reconstructed trees.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessForest {
+    pub nodes: Vec<ProcessNode>,
+    /// Nodes without a parent, oldest first.
+    pub roots: Vec<usize>,
+}
+
+impl ProcessForest {
+    /// `node` and all its descendants, depth first.
+    pub fn subtree(&self, node: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![node];
+        while let Some(n) = stack.pop() {
+            out.push(n);
+            stack.extend(self.nodes[n].children.iter().rev());
+        }
+        out
+    }
+
+    fn add(&mut self, node: ProcessNode) -> Option<usize> {
+        if self.nodes.len() >= MAX_PROCESS_NODES {
+            return None;
+        }
+        self.nodes.push(node);
+        Some(self.nodes.len() - 1)
+    }
+
+    fn link(&mut self, child: usize, parent: usize) {
+        self.nodes[child].parent = Some(parent);
+        self.nodes[parent].children.push(child);
+    }
+}
+
+/// Parse a Security-log PID (`0x1a2c`) or a Sysmon one (`6700`).
+fn parse_pid(v: &str) -> Option<u32> {
+    match v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => v.parse().ok(),
+    }
+}
+
+/// Integrity level from a Security-log mandatory label SID.
+fn integrity_from_label(sid: &str) -> &'static str {
+    match sid {
+        "S-1-16-0" => "Untrusted",
+        "S-1-16-4096" => "Low",
+        "S-1-16-8192" => "Medium",
+        "S-1-16-8448" => "MediumPlus",
+        "S-1-16-12288" => "High",
+        "S-1-16-16384" => "System",
+        _ => "",
+    }
+}
+
+fn field(e: &EventRecord, name: &str) -> String {
+    e.data(name).unwrap_or_default().to_owned()
+}
+
+/// Build process trees from `events` (any order).
+///
+/// Reconstruction stops at the first event that needs a new node once
+/// [`MAX_PROCESS_NODES`] is reached.
+pub fn build(events: &[EventRecord]) -> ProcessForest {
+    let mut order: Vec<usize> = (0..events.len())
+        .filter(|&i| {
+            let e = &events[i];
+            match e.channel.as_str() {
+                "Security" => matches!(e.event_id, 4688 | 4689),
+                SYSMON_CHANNEL => matches!(e.event_id, 1 | 3 | 5 | 11 | 12 | 13 | 14),
+                _ => false,
+            }
+        })
+        .collect();
+    order.sort_by_key(|&i| events[i].timestamp);
+
+    let mut forest = ProcessForest::default();
+    // (computer, PID) → live node, for Security events.
+    let mut by_pid: HashMap<(String, u32), usize> = HashMap::new();
+    // (computer, ProcessGuid) → node, for Sysmon events.
+    let mut by_guid: HashMap<(String, String), usize> = HashMap::new();
+
+    for i in order {
+        let e = &events[i];
+        let computer = e.computer.to_ascii_lowercase();
+        if e.channel == "Security" {
+            let Some(pid) = e
+                .data("NewProcessId")
+                .or(e.data("ProcessId"))
+                .and_then(parse_pid)
+            else {
+                continue;
+            };
+            if e.event_id == 4689 {
+                let node = match by_pid.remove(&(computer.clone(), pid)) {
+                    Some(n) => n,
+                    None => {
+                        let Some(n) = forest.add(ProcessNode {
+                            pid,
+                            image: field(e, "ProcessName"),
+                            computer: e.computer.clone(),
+                            ..Default::default()
+                        }) else {
+                            break;
+                        };
+                        n
+                    }
+                };
+                forest.nodes[node].end = Some(e.timestamp);
+                forest.nodes[node].events.push(i);
+                continue;
+            }
+
+            let user = match e.data("TargetUserName").filter(|u| *u != "-") {
+                Some(u) => u.to_owned(),
+                None => field(e, "SubjectUserName"),
+            };
+            let Some(node) = forest.add(ProcessNode {
+                pid,
+                image: field(e, "NewProcessName"),
+                command_line: field(e, "CommandLine"),
+                user,
+                integrity: integrity_from_label(e.data("MandatoryLabel").unwrap_or_default())
+                    .to_owned(),
+                computer: e.computer.clone(),
+                start: Some(e.timestamp),
+                events: vec![i],
+                ..Default::default()
+            }) else {
+                break;
+            };
+            if let Some(ppid) = e.data("ProcessId").and_then(parse_pid) {
+                let parent = match by_pid.get(&(computer.clone(), ppid)) {
+                    Some(&p) => Some(p),
+                    None => forest
+                        .add(ProcessNode {
+                            pid: ppid,
+                            image: field(e, "ParentProcessName"),
+                            computer: e.computer.clone(),
+                            ..Default::default()
+                        })
+                        .inspect(|&p| {
+                            by_pid.insert((computer.clone(), ppid), p);
+                        }),
+                };
+                if let Some(p) = parent {
+                    forest.link(node, p);
+                }
+            }
+            by_pid.insert((computer, pid), node);
+            continue;
+        }
+
+        // Sysmon
+        let Some(guid) = e.data("ProcessGuid").map(str::to_ascii_lowercase) else {
+            continue;
+        };
+        let key = (computer.clone(), guid);
+        let node = match (e.event_id, by_guid.get(&key)) {
+            // A creation event for a node first seen as a placeholder
+            // parent fills it in.
+            (1, Some(&n)) if forest.nodes[n].is_placeholder() => n,
+            (1, _) | (_, None) => {
+                let Some(n) = forest.add(ProcessNode {
+                    pid: e.data("ProcessId").and_then(parse_pid).unwrap_or_default(),
+                    image: field(e, "Image"),
+                    computer: e.computer.clone(),
+                    ..Default::default()
+                }) else {
+                    break;
+                };
+                by_guid.insert(key, n);
+                n
+            }
+            (_, Some(&n)) => n,
+        };
+        forest.nodes[node].events.push(i);
+
+        match e.event_id {
+            1 => {
+                let n = &mut forest.nodes[node];
+                n.command_line = field(e, "CommandLine");
+                n.user = field(e, "User");
+                n.integrity = field(e, "IntegrityLevel");
+                n.start = Some(e.timestamp);
+                if n.parent.is_some() {
+                    continue;
+                }
+                let Some(parent_guid) = e.data("ParentProcessGuid").map(str::to_ascii_lowercase)
+                else {
+                    continue;
+                };
+                let parent_key = (computer, parent_guid);
+                let parent = match by_guid.get(&parent_key) {
+                    Some(&p) => Some(p),
+                    None => forest
+                        .add(ProcessNode {
+                            pid: e
+                                .data("ParentProcessId")
+                                .and_then(parse_pid)
+                                .unwrap_or_default(),
+                            image: field(e, "ParentImage"),
+                            command_line: field(e, "ParentCommandLine"),
+                            computer: e.computer.clone(),
+                            ..Default::default()
+                        })
+                        .inspect(|&p| {
+                            by_guid.insert(parent_key, p);
+                        }),
+                };
+                if let Some(p) = parent.filter(|&p| p != node) {
+                    forest.link(node, p);
+                }
+            }
+            5 => forest.nodes[node].end = Some(e.timestamp),
+            3 => forest.nodes[node].network += 1,
+            11 => forest.nodes[node].files += 1,
+            _ => forest.nodes[node].registry += 1,
+        }
+    }
+
+    forest.roots = (0..forest.nodes.len())
+        .filter(|&n| forest.nodes[n].parent.is_none())
+        .collect();
+    let nodes = &forest.nodes;
+    forest.roots.sort_by_key(|&n| {
+        // Placeholder roots sort by their first child.
+        nodes[n]
+            .start
+            .or_else(|| nodes[n].children.first().and_then(|&c| nodes[c].start))
+    });
+    forest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(channel: &str, id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: channel.into(),
+            event_id: id,
+            timestamp: EventRecord::test_time(secs),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    fn security_create(secs: i64, pid: &str, ppid: &str, image: &str) -> EventRecord {
+        event(
+            "Security",
+            4688,
+            secs,
+            &[
+                ("NewProcessId", pid),
+                ("ProcessId", ppid),
+                ("NewProcessName", image),
+                ("SubjectUserName", "alice"),
+                ("TargetUserName", "-"),
+                ("MandatoryLabel", "S-1-16-12288"),
+            ],
+        )
+    }
+
+    #[test]
+    fn security_tree_handles_pid_reuse_and_exit() {
+        let events = vec![
+            security_create(0, "0x100", "0x4", r"C:\Windows\explorer.exe"),
+            security_create(10, "0x200", "0x100", r"C:\Windows\System32\cmd.exe"),
+            event("Security", 4689, 20, &[("ProcessId", "0x200")]),
+            // PID 0x200 reused by a new process after the exit.
+            security_create(30, "0x300", "0x200", r"C:\Tools\evil.exe"),
+        ];
+        let forest = build(&events);
+        // explorer's placeholder parent (PID 4) and evil.exe's placeholder
+        // parent (the new, uncaptured 0x200) are the two roots.
+        assert_eq!(forest.roots.len(), 2);
+        let explorer = forest
+            .nodes
+            .iter()
+            .position(|n| n.image_name() == "explorer.exe")
+            .unwrap();
+        let cmd = forest.nodes[explorer].children[0];
+        assert_eq!(forest.nodes[cmd].image_name(), "cmd.exe");
+        assert_eq!(forest.nodes[cmd].user, "alice");
+        assert_eq!(forest.nodes[cmd].integrity, "High");
+        assert_eq!(forest.nodes[cmd].lifetime(), Some(Duration::seconds(10)));
+        assert!(forest.nodes[cmd].children.is_empty());
+        assert_eq!(forest.subtree(explorer), vec![explorer, cmd]);
+    }
+
+    #[test]
+    fn sysmon_tree_attaches_activity() {
+        let sysmon = SYSMON_CHANNEL;
+        let events = vec![
+            event(
+                sysmon,
+                3,
+                5,
+                &[("ProcessGuid", "{B}"), ("Image", "powershell.exe")],
+            ),
+            event(
+                sysmon,
+                1,
+                0,
+                &[
+                    ("ProcessGuid", "{b}"),
+                    ("ProcessId", "6700"),
+                    (
+                        "Image",
+                        r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe",
+                    ),
+                    ("CommandLine", "powershell -enc AAAA"),
+                    ("User", "CORP\\bob"),
+                    ("IntegrityLevel", "Medium"),
+                    ("ParentProcessGuid", "{A}"),
+                    ("ParentProcessId", "512"),
+                    (
+                        "ParentImage",
+                        r"C:\Program Files\Microsoft Office\WINWORD.EXE",
+                    ),
+                ],
+            ),
+            event(sysmon, 11, 6, &[("ProcessGuid", "{B}")]),
+            event(sysmon, 13, 7, &[("ProcessGuid", "{B}")]),
+            event(sysmon, 5, 9, &[("ProcessGuid", "{B}")]),
+        ];
+        let forest = build(&events);
+        assert_eq!(forest.roots.len(), 1);
+        let word = &forest.nodes[forest.roots[0]];
+        assert!(word.is_placeholder());
+        assert_eq!(word.image_name(), "WINWORD.EXE");
+        let ps = &forest.nodes[word.children[0]];
+        assert_eq!(ps.pid, 6700);
+        assert_eq!((ps.network, ps.files, ps.registry), (1, 1, 1));
+        assert_eq!(ps.events, vec![1, 0, 2, 3, 4]);
+        assert_eq!(ps.lifetime(), Some(Duration::seconds(9)));
+    }
+}
@@ -0,0 +1,189 @@
+//! Query-by-example: propose a filter from a handful of example events.
+//!
+//! [`suggest_filter`] looks at what the examples have in common (event ID
+//! set, provider, levels, identical EventData key/value pairs, time span)
+//! and returns a [`FilterSuggestion`] whose parts can be toggled and
+//! edited before being turned into a [`FilterState`].
+
+use crate::core::event_record::EventRecord;
+use crate::core::filter::FilterState;
+use crate::core::pivot::{FieldClause, PivotField};
+use std::collections::BTreeSet;
+
+/// An editable filter proposal built from example events.
+///
+/// Each criterion has a `use_*` flag so the user can drop parts that are
+/// too specific before applying the suggestion.
+#[derive(Debug, Clone, Default)]
+pub struct FilterSuggestion {
+    /// Number of example events the suggestion was built from.
+    pub example_count: usize,
+    /// Event ID input text, e.g. `"4624, 4625"`.
+    pub event_id_input: String,
+    pub use_event_ids: bool,
+    /// Shared provider name (empty when the examples differ).
+    pub provider: String,
+    pub use_provider: bool,
+    /// Levels seen across the examples.
+    pub levels: [bool; 6],
+    pub use_levels: bool,
+    /// EventData key/value pairs identical in every example, with an
+    /// enabled flag each.
+    pub common_data: Vec<(String, String, bool)>,
+    /// Local-time span of the examples, as time-range input strings.
+    pub time_from_input: String,
+    pub time_to_input: String,
+    pub use_time_span: bool,
+}
+
+/// Build a suggestion from `examples`. Returns `None` for an empty slice.
+///
+/// EventData pairs start enabled only when there are at least two
+/// examples; with a single event every pair is "common", which would
+/// produce a filter matching that one event. The time span starts
+/// disabled since the aim is to find *similar* events elsewhere in the log.
+pub fn suggest_filter(examples: &[&EventRecord]) -> Option<FilterSuggestion> {
+    let first = examples.first()?;
+
+    let ids: BTreeSet<u32> = examples.iter().map(|e| e.event_id).collect();
+    let event_id_input = ids
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let provider = if examples
+        .iter()
+        .all(|e| e.provider_name == first.provider_name)
+    {
+        first.provider_name.clone()
+    } else {
+        String::new()
+    };
+
+    let mut levels = [false; 6];
+    for e in examples {
+        levels[(e.level as usize).min(5)] = true;
+    }
+
+    let enable_data = examples.len() > 1;
+    let common_data = first
+        .event_data
+        .iter()
+        .filter(|(_, v)| !v.trim().is_empty())
+        .filter(|(k, v)| {
+            examples[1..]
+                .iter()
+                .all(|e| e.event_data.iter().any(|(k2, v2)| k2 == k && v2 == v))
+        })
+        .map(|(k, v)| (k.clone(), v.clone(), enable_data))
+        .collect();
+
+    let min_ts = examples.iter().map(|e| e.timestamp).min()?;
+    let max_ts = examples.iter().map(|e| e.timestamp).max()?;
+    let fmt = |t: chrono::DateTime<chrono::Utc>| {
+        t.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    };
+
+    Some(FilterSuggestion {
+        example_count: examples.len(),
+        event_id_input,
+        use_event_ids: true,
+        use_provider: !provider.is_empty(),
+        provider,
+        levels,
+        use_levels: !levels.iter().all(|&l| l),
+        common_data,
+        time_from_input: fmt(min_ts),
+        // Round the upper bound up so the last example (which may carry
+        // sub-second precision) stays inside the range.
+        time_to_input: fmt(max_ts + chrono::Duration::seconds(1)),
+        use_time_span: false,
+    })
+}
+
+impl FilterSuggestion {
+    /// Build a fully-parsed [`FilterState`] from the enabled parts.
+    pub fn to_filter_state(&self) -> FilterState {
+        let mut state = FilterState::default();
+        if self.use_event_ids {
+            state.event_id_input = self.event_id_input.clone();
+        }
+        if self.use_provider {
+            state.provider_filter = self.provider.clone();
+        }
+        if self.use_levels {
+            state.levels = self.levels;
+        }
+        state.field_clauses = self
+            .common_data
+            .iter()
+            .filter(|(_, _, on)| *on)
+            .map(|(k, v, _)| FieldClause {
+                field: PivotField::EventData(k.clone()),
+                value: v.clone(),
+                exclude: false,
+            })
+            .collect();
+        if self.use_time_span {
+            state.time_from_input = self.time_from_input.clone();
+            state.time_to_input = self.time_to_input.clone();
+        }
+        state.parse_event_ids();
+        state.parse_time_range();
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, level: u8, user: &str, logon_type: &str) -> EventRecord {
+        EventRecord {
+            channel: "Security".into(),
+            event_id: id,
+            level,
+            level_name: EventRecord::level_to_name(level).into(),
+            provider_name: "Microsoft-Windows-Security-Auditing".into(),
+            computer: "DC01".into(),
+            event_data: vec![
+                ("TargetUserName".into(), user.into()),
+                ("LogonType".into(), logon_type.into()),
+            ],
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn suggestion_keeps_shared_fields() {
+        let a = event(4625, 0, "alice", "3");
+        let b = event(4624, 0, "bob", "3");
+        let s = suggest_filter(&[&a, &b]).expect("suggestion");
+
+        assert_eq!(s.event_id_input, "4624, 4625");
+        assert_eq!(s.provider, "Microsoft-Windows-Security-Auditing");
+        assert_eq!(s.common_data, vec![("LogonType".into(), "3".into(), true)]);
+
+        let f = s.to_filter_state();
+        assert!(f.matches(&event(4625, 0, "carol", "3")));
+        assert!(!f.matches(&event(4625, 0, "carol", "10")));
+        assert!(!f.matches(&event(4634, 0, "carol", "3")));
+    }
+
+    #[test]
+    fn single_example_disables_data_pairs() {
+        let a = event(4625, 0, "alice", "3");
+        let s = suggest_filter(&[&a]).expect("suggestion");
+        assert_eq!(s.common_data.len(), 2);
+        assert!(s.common_data.iter().all(|(_, _, on)| !on));
+        assert!(s.to_filter_state().matches(&event(4625, 0, "bob", "10")));
+    }
+
+    #[test]
+    fn empty_examples_yield_none() {
+        assert!(suggest_filter(&[]).is_none());
+    }
+}
@@ -0,0 +1,643 @@
+//! Service lifecycle timeline from Service Control Manager events.
+//!
+//! SCM 7000-7045 and Security 4697 are folded into one timeline per
+//! service (per computer), keyed by service name: installations log it
+//! directly, while the other SCM events log the display name, which 7040
+//! records map back to the service name. The timeline holds
+//! installations, state transitions (7036), controls sent (7035 / 7042),
+//! start-type changes (7040), start failures and timeouts, and
+//! unexpected terminations (7031 / 7034). Repeated
+//! terminations within a short window are reported as crash / restart
+//! loops. Installations are listed on their own and flagged when the
+//! image path looks like a launcher rather than a service binary.
+
+use std::collections::HashMap;
+
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::{SERVICE_CRASH_LOOP_COUNT, SERVICE_CRASH_LOOP_WINDOW_SECS};
+
+const SCM: &str = "Service Control Manager";
+const SECURITY_AUDITING: &str = "Microsoft-Windows-Security-Auditing";
+
+/// What a timeline entry records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// 7045 / 4697.
+    Installed,
+    /// 7036: entered the running / stopped / ... state.
+    State,
+    /// 7035 / 7042: start or stop control sent.
+    Control,
+    /// 7040: start type changed.
+    StartType,
+    /// 7000 / 7001 / 7009 / 7011 / 7022-7024 / 7032.
+    Failure,
+    /// 7031 / 7034: terminated unexpectedly.
+    Crash,
+}
+
+impl EntryKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Installed => "Installed",
+            Self::State => "State",
+            Self::Control => "Control",
+            Self::StartType => "Start type",
+            Self::Failure => "Failure",
+            Self::Crash => "Crash",
+        }
+    }
+}
+
+/// One event in a service's timeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEntry {
+    /// Index into the event list.
+    pub event: usize,
+    pub time: DateTime<Utc>,
+    pub event_id: u32,
+    pub kind: EntryKind,
+    /// Human-readable summary (`Entered the running state`, ...).
+    pub detail: String,
+}
+
+/// A 7045 or 4697 service installation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Installation {
+    pub event: usize,
+    pub time: DateTime<Utc>,
+    pub event_id: u32,
+    pub computer: String,
+    pub service: String,
+    pub image_path: String,
+    /// Account the service runs as (`LocalSystem`, ...).
+    pub account: String,
+    pub start_type: String,
+    /// Installing user (4697 only).
+    pub installed_by: Option<String>,
+    /// Reasons the image path looks suspicious; empty when it does not.
+    pub flags: Vec<&'static str>,
+}
+
+/// Unexpected terminations of one service close together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashLoop {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// 7031 / 7034 event indices, in time order.
+    pub events: Vec<usize>,
+}
+
+/// Timeline of one service on one computer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    /// Service name, or the display name when no 7040 / installation
+    /// links the two.
+    pub name: String,
+    /// Display name logged by SCM, when it differs from `name`.
+    pub display_name: Option<String>,
+    pub computer: String,
+    /// Entries in time order.
+    pub entries: Vec<ServiceEntry>,
+    /// Last state entered (7036), if any.
+    pub state: Option<String>,
+    /// Last start type (7040 / installation), if any.
+    pub start_type: Option<String>,
+    pub crashes: usize,
+    pub loops: Vec<CrashLoop>,
+    /// Indices into [`ServiceTimeline::installs`].
+    pub installs: Vec<usize>,
+    /// An installation of this service is flagged.
+    pub flagged: bool,
+}
+
+impl Service {
+    /// Crash loops or a flagged installation.
+    pub fn needs_attention(&self) -> bool {
+        self.flagged || !self.loops.is_empty()
+    }
+}
+
+/// Everything the service window shows.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceTimeline {
+    /// Services needing attention first, then by name.
+    pub services: Vec<Service>,
+    /// All installations, in time order.
+    pub installs: Vec<Installation>,
+}
+
+/// Launchers and script hosts that have no business being a service
+/// binary.
+const LAUNCHERS: &[&str] = &[
+    "cmd.exe /c",
+    "cmd /c",
+    "%comspec%",
+    "powershell",
+    "pwsh",
+    "mshta",
+    "rundll32",
+    "regsvr32",
+    "wscript",
+    "cscript",
+];
+
+/// Folders any user can write to.
+const WRITABLE_DIRS: &[&str] = &[
+    "\\appdata\\",
+    "\\temp\\",
+    "\\tmp\\",
+    "\\users\\public\\",
+    "\\perflogs\\",
+    "\\$recycle.bin\\",
+];
+
+/// A PowerShell `-EncodedCommand` switch (any prefix down to `-e`, or
+/// `-ec`) on a `powershell` / `pwsh` command line, or a long base64 blob
+/// anywhere in it. Hex strings such as hashes are not base64 blobs.
+fn has_encoded_command(image: &str) -> bool {
+    let lower = image.to_ascii_lowercase();
+    let tokens: Vec<&str> = image.split_whitespace().collect();
+    let powershell = lower.contains("powershell") || lower.contains("pwsh");
+    let switch = powershell
+        && tokens.windows(2).any(|w| {
+            let t = w[0].to_ascii_lowercase();
+            let t = t.trim_start_matches(['-', '/']);
+            w[0].len() > t.len()
+                && t.starts_with('e')
+                && (t == "ec" || "encodedcommand".starts_with(t))
+        });
+    switch
+        || tokens.iter().any(|t| {
+            let t = t.trim_matches(['"', '\'']);
+            t.len() >= 64
+                && !t.bytes().all(|b| b.is_ascii_hexdigit())
+                && base64::engine::general_purpose::STANDARD.decode(t).is_ok()
+        })
+}
+
+/// Reasons an installed service's image path looks suspicious.
+pub fn install_flags(image_path: &str) -> Vec<&'static str> {
+    let lower = image_path.to_ascii_lowercase();
+    let mut flags = Vec::new();
+    if has_encoded_command(image_path) {
+        flags.push("Encoded command line");
+    }
+    if LAUNCHERS.iter().any(|l| lower.contains(l)) {
+        flags.push("Shell / script host");
+    }
+    // ProgramData is writable too, but Microsoft's own services (Defender's
+    // platform binaries) live under ProgramData\Microsoft.
+    let program_data =
+        lower.contains("\\programdata\\") && !lower.contains("\\programdata\\microsoft\\");
+    if program_data || WRITABLE_DIRS.iter().any(|d| lower.contains(d)) {
+        flags.push("User-writable path");
+    }
+    if lower.starts_with("\\\\") || lower.contains("admin$") {
+        flags.push("Network / admin share");
+    }
+    // `C:\Program Files\My App\svc.exe` without quotes.
+    let path = lower.trim_start();
+    if path.as_bytes().get(1..3) == Some(b":\\") {
+        if let Some(end) = path.find(".exe") {
+            if path[..end].contains(' ') {
+                flags.push("Unquoted path with spaces");
+            }
+        }
+    }
+    flags
+}
+
+/// 4697 numeric start types.
+fn start_type_name(value: &str) -> String {
+    match value.trim() {
+        "0" => "boot start".to_owned(),
+        "1" => "system start".to_owned(),
+        "2" => "auto start".to_owned(),
+        "3" => "demand start".to_owned(),
+        "4" => "disabled".to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+fn field(e: &EventRecord, name: &str) -> String {
+    e.data(name).unwrap_or_default().trim().to_owned()
+}
+
+/// Service name and timeline entry of an SCM event.
+fn scm_entry(idx: usize, e: &EventRecord) -> Option<(String, ServiceEntry)> {
+    let p = |n: u8| field(e, &format!("param{n}"));
+    let (service, kind, detail) = match e.event_id {
+        7000 => (
+            p(1),
+            EntryKind::Failure,
+            format!("Failed to start: {}", p(2)),
+        ),
+        7001 => (
+            p(1),
+            EntryKind::Failure,
+            format!("Dependency {} failed: {}", p(2), p(3)),
+        ),
+        7009 | 7011 => (
+            p(2),
+            EntryKind::Failure,
+            format!("Timed out after {} ms", p(1)),
+        ),
+        7022 => (p(1), EntryKind::Failure, "Hung on starting".to_owned()),
+        7023 | 7024 => (
+            p(1),
+            EntryKind::Failure,
+            format!("Terminated with error: {}", p(2)),
+        ),
+        7031 => (
+            p(1),
+            EntryKind::Crash,
+            format!(
+                "Terminated unexpectedly ({} times); recovery: {}",
+                p(2),
+                p(5)
+            ),
+        ),
+        7032 => (
+            p(2),
+            EntryKind::Failure,
+            format!("Recovery action {} failed: {}", p(1), p(3)),
+        ),
+        7034 => (
+            p(1),
+            EntryKind::Crash,
+            format!("Terminated unexpectedly ({} times)", p(2)),
+        ),
+        7035 => (p(1), EntryKind::Control, format!("{} control sent", p(2))),
+        7036 => (
+            p(1),
+            EntryKind::State,
+            format!("Entered the {} state", p(2)),
+        ),
+        7040 => (
+            p(1),
+            EntryKind::StartType,
+            format!("Start type {} \u{2192} {}", p(2), p(3)),
+        ),
+        7042 => (
+            p(1),
+            EntryKind::Control,
+            format!("{} control sent: {}", p(2), p(4)),
+        ),
+        _ => return None,
+    };
+    (!service.is_empty()).then_some((
+        service,
+        ServiceEntry {
+            event: idx,
+            time: e.timestamp,
+            event_id: e.event_id,
+            kind,
+            detail,
+        },
+    ))
+}
+
+/// A 7045 or 4697 installation.
+fn installation(idx: usize, e: &EventRecord) -> Option<Installation> {
+    let (service, image_path, account, start_type, installed_by) = match e.event_id {
+        7045 => (
+            field(e, "ServiceName"),
+            field(e, "ImagePath"),
+            field(e, "AccountName"),
+            field(e, "StartType"),
+            None,
+        ),
+        4697 => (
+            field(e, "ServiceName"),
+            field(e, "ServiceFileName"),
+            field(e, "ServiceAccount"),
+            start_type_name(&field(e, "ServiceStartType")),
+            Some(field(e, "SubjectUserName")),
+        ),
+        _ => return None,
+    };
+    if service.is_empty() {
+        return None;
+    }
+    Some(Installation {
+        event: idx,
+        time: e.timestamp,
+        event_id: e.event_id,
+        computer: e.computer.clone(),
+        flags: install_flags(&image_path),
+        service,
+        image_path,
+        account,
+        start_type,
+        installed_by,
+    })
+}
+
+/// Merge 7031 / 7034 entries into crash loops.
+fn crash_loops(entries: &[ServiceEntry]) -> Vec<CrashLoop> {
+    let window = Duration::seconds(SERVICE_CRASH_LOOP_WINDOW_SECS);
+    let crashes: Vec<&ServiceEntry> = entries
+        .iter()
+        .filter(|e| e.kind == EntryKind::Crash)
+        .collect();
+    let mut loops: Vec<CrashLoop> = Vec::new();
+    for (i, first) in crashes.iter().enumerate() {
+        let Some(last) = crashes.get(i + SERVICE_CRASH_LOOP_COUNT - 1) else {
+            break;
+        };
+        if last.time - first.time > window {
+            continue;
+        }
+        let members = &crashes[i..i + SERVICE_CRASH_LOOP_COUNT];
+        match loops.last_mut() {
+            // Overlapping windows extend the previous loop.
+            Some(l) if first.time <= l.end => {
+                for m in members {
+                    if !l.events.contains(&m.event) {
+                        l.events.push(m.event);
+                    }
+                }
+                l.end = last.time;
+            }
+            _ => loops.push(CrashLoop {
+                start: first.time,
+                end: last.time,
+                events: members.iter().map(|m| m.event).collect(),
+            }),
+        }
+    }
+    loops
+}
+
+/// (computer, display name) → service name, both lowercased on the key
+/// side, from 7040 events (param1 is the display name, param4 the
+/// service name).
+fn service_names(events: &[EventRecord]) -> HashMap<(String, String), String> {
+    events
+        .iter()
+        .filter(|e| e.provider_name == SCM && e.event_id == 7040)
+        .filter_map(|e| {
+            let display = field(e, "param1");
+            let service = field(e, "param4");
+            (!display.is_empty() && !service.is_empty())
+                .then(|| ((e.computer.to_lowercase(), display.to_lowercase()), service))
+        })
+        .collect()
+}
+
+/// Build per-service timelines from `events`.
+pub fn build(events: &[EventRecord]) -> ServiceTimeline {
+    let mut order: Vec<usize> = (0..events.len()).collect();
+    order.sort_by_key(|&i| events[i].timestamp);
+    let names = service_names(events);
+
+    let mut services: Vec<Service> = Vec::new();
+    let mut by_key: HashMap<(String, String), usize> = HashMap::new();
+    let mut installs: Vec<Installation> = Vec::new();
+
+    for i in order {
+        let e = &events[i];
+        let (name, entry) = match (e.provider_name.as_str(), e.event_id) {
+            (SCM, 7045) | (SECURITY_AUDITING, 4697) => {
+                let Some(install) = installation(i, e) else {
+                    continue;
+                };
+                let mut detail = format!("{} as {}", install.image_path, install.account);
+                if let Some(user) = &install.installed_by {
+                    detail.push_str(&format!(" (by {user})"));
+                }
+                let entry = ServiceEntry {
+                    event: i,
+                    time: e.timestamp,
+                    event_id: e.event_id,
+                    kind: EntryKind::Installed,
+                    detail,
+                };
+                let name = install.service.clone();
+                installs.push(install);
+                (name, entry)
+            }
+            (SCM, _) => match scm_entry(i, e) {
+                Some(found) => found,
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let computer = e.computer.to_lowercase();
+        let (name, display_name) = match names.get(&(computer.clone(), name.to_lowercase())) {
+            Some(service) if !service.eq_ignore_ascii_case(&name) => (service.clone(), Some(name)),
+            _ => (name, None),
+        };
+        let key = (computer, name.to_lowercase());
+        let idx = *by_key.entry(key).or_insert_with(|| {
+            services.push(Service {
+                name,
+                display_name: None,
+                computer: e.computer.clone(),
+                entries: Vec::new(),
+                state: None,
+                start_type: None,
+                crashes: 0,
+                loops: Vec::new(),
+                installs: Vec::new(),
+                flagged: false,
+            });
+            services.len() - 1
+        });
+        let service = &mut services[idx];
+        if service.display_name.is_none() {
+            service.display_name = display_name;
+        }
+        match entry.kind {
+            EntryKind::State => service.state = Some(field(e, "param2")),
+            EntryKind::StartType => service.start_type = Some(field(e, "param3")),
+            EntryKind::Crash => service.crashes += 1,
+            EntryKind::Installed => {
+                let install = &installs[installs.len() - 1];
+                service.start_type = Some(install.start_type.clone());
+                service.flagged |= !install.flags.is_empty();
+                service.installs.push(installs.len() - 1);
+            }
+            EntryKind::Control | EntryKind::Failure => {}
+        }
+        service.entries.push(entry);
+    }
+
+    for service in &mut services {
+        service.loops = crash_loops(&service.entries);
+    }
+    services.sort_by(|a, b| {
+        b.needs_attention()
+            .cmp(&a.needs_attention())
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.computer.cmp(&b.computer))
+    });
+    ServiceTimeline { services, installs }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(provider: &str, id: u32, secs: i64, data: &[(&str, &str)]) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: id,
+            provider_name: provider.into(),
+            timestamp: EventRecord::test_time(secs),
+            event_data: EventRecord::test_data(data),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn flags_suspicious_image_paths() {
+        assert!(install_flags(r#""C:\Program Files\Vendor\svc.exe""#).is_empty());
+        assert!(install_flags(r"C:\Windows\system32\svchost.exe -k netsvcs").is_empty());
+        assert_eq!(
+            install_flags(r"C:\Program Files\Vendor App\svc.exe"),
+            ["Unquoted path with spaces"]
+        );
+        assert_eq!(
+            install_flags(r"C:\Users\bob\AppData\Local\Temp\x.exe"),
+            ["User-writable path"]
+        );
+        let encoded = format!(
+            "powershell.exe -nop -w hidden -enc {}",
+            "SQBFAFgA".repeat(10)
+        );
+        assert_eq!(
+            install_flags(&encoded),
+            ["Encoded command line", "Shell / script host"]
+        );
+        assert_eq!(
+            install_flags(r"%COMSPEC% /b /c start /b /min \\127.0.0.1\ADMIN$\a.exe"),
+            ["Shell / script host", "Network / admin share"]
+        );
+    }
+
+    #[test]
+    fn encoded_command_needs_powershell_and_real_base64() {
+        // `-e` belongs to PowerShell only; other binaries use it freely.
+        assert!(install_flags(r"C:\Vendor\svc.exe -e prod").is_empty());
+        // A SHA-256 argument is hex, not base64.
+        let sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert!(install_flags(&format!(r"C:\Vendor\svc.exe --verify {sha256}")).is_empty());
+    }
+
+    #[test]
+    fn timeline_with_install_state_and_crash_loop() {
+        let state =
+            |secs, name: &str, s: &str| event(SCM, 7036, secs, &[("param1", name), ("param2", s)]);
+        let crash = |secs| event(SCM, 7034, secs, &[("param1", "Updater"), ("param2", "1")]);
+        let events = vec![
+            state(0, "Windows Update", "running"),
+            event(
+                SCM,
+                7045,
+                10,
+                &[
+                    ("ServiceName", "Updater"),
+                    ("ImagePath", r"C:\ProgramData\upd\upd.exe"),
+                    ("StartType", "auto start"),
+                    ("AccountName", "LocalSystem"),
+                ],
+            ),
+            state(20, "Updater", "running"),
+            crash(100),
+            crash(700),
+            crash(1300),
+            crash(1900),
+            crash(90_000),
+            event(
+                SCM,
+                7040,
+                90_100,
+                &[
+                    ("param1", "Updater"),
+                    ("param2", "auto start"),
+                    ("param3", "disabled"),
+                ],
+            ),
+            state(90_200, "windows update", "stopped"),
+        ];
+        let timeline = build(&events);
+        assert_eq!(timeline.services.len(), 2);
+
+        let updater = &timeline.services[0];
+        assert_eq!(updater.name, "Updater");
+        assert!(updater.flagged);
+        assert_eq!(updater.entries.len(), 8);
+        assert_eq!(updater.entries[0].kind, EntryKind::Installed);
+        assert_eq!(updater.crashes, 5);
+        assert_eq!(updater.loops.len(), 1);
+        assert_eq!(updater.loops[0].events, [3, 4, 5, 6]);
+        assert_eq!(updater.start_type.as_deref(), Some("disabled"));
+        assert_eq!(updater.state.as_deref(), Some("running"));
+
+        let wu = &timeline.services[1];
+        assert!(!wu.needs_attention());
+        assert_eq!(wu.entries.len(), 2);
+        assert_eq!(wu.state.as_deref(), Some("stopped"));
+
+        assert_eq!(timeline.installs.len(), 1);
+        assert_eq!(timeline.installs[0].flags, ["User-writable path"]);
+    }
+
+    #[test]
+    fn display_names_join_the_installed_service() {
+        let events = vec![
+            event(
+                SCM,
+                7045,
+                0,
+                &[
+                    ("ServiceName", "UpdSvc"),
+                    ("ImagePath", r"C:\Program Files\Upd\upd.exe"),
+                    ("StartType", "auto start"),
+                    ("AccountName", "LocalSystem"),
+                ],
+            ),
+            event(
+                SCM,
+                7036,
+                10,
+                &[("param1", "Updater Service"), ("param2", "running")],
+            ),
+            event(
+                SCM,
+                7040,
+                20,
+                &[
+                    ("param1", "Updater Service"),
+                    ("param2", "auto start"),
+                    ("param3", "demand start"),
+                    ("param4", "UpdSvc"),
+                ],
+            ),
+        ];
+        let timeline = build(&events);
+        assert_eq!(timeline.services.len(), 1);
+        let s = &timeline.services[0];
+        assert_eq!(s.name, "UpdSvc");
+        assert_eq!(s.display_name.as_deref(), Some("Updater Service"));
+        assert_eq!(s.entries.len(), 3);
+        assert_eq!(s.state.as_deref(), Some("running"));
+    }
+
+    #[test]
+    fn microsoft_programdata_is_not_user_writable() {
+        let defender =
+            r#""C:\ProgramData\Microsoft\Windows Defender\Platform\4.18.24090.11-0\MsMpEng.exe""#;
+        assert!(install_flags(defender).is_empty());
+        assert_eq!(
+            install_flags(r"C:\ProgramData\upd\upd.exe"),
+            ["User-writable path"]
+        );
+    }
+}
@@ -0,0 +1,754 @@
+//! Sigma rule engine: load Sigma YAML rules and match them against
+//! [`EventRecord`]s.
+//!
+//! Each rule's `logsource` is mapped to the Windows channels (and, for
+//! categories such as `process_creation`, event IDs) it applies to.
+//! Detection fields are looked up in EventData first and then in the
+//! System header (`EventID`, `Provider_Name`, `Channel`, `Computer`,
+//! `Level`). Supported value modifiers: `contains`, `startswith`,
+//! `endswith`, `re`, `all`, `base64`, and `base64offset`. Plain values use
+//! Sigma's case-insensitive `*` / `?` wildcard semantics.
+//!
+//! The `condition` grammar lives in [`super::sigma_condition`]. Rule
+//! folders may also hold correlation rules ([`super::correlation`]), which
+//! reuse the selection syntax for each step.
+
+use crate::core::correlation::CorrelationRule;
+use crate::core::event_record::EventRecord;
+use crate::core::sigma_condition::{parse_condition, Condition};
+use crate::util::error::{EventSleuthError, Result};
+use base64::Engine as _;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Sigma rule severity, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum SigmaLevel {
+    Informational,
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl SigmaLevel {
+    /// Parse a Sigma `level:` value; unknown values map to `Medium`.
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "informational" => SigmaLevel::Informational,
+            "low" => SigmaLevel::Low,
+            "high" => SigmaLevel::High,
+            "critical" => SigmaLevel::Critical,
+            _ => SigmaLevel::Medium,
+        }
+    }
+
+    /// Display label.
+    pub fn label(self) -> &'static str {
+        match self {
+            SigmaLevel::Informational => "Informational",
+            SigmaLevel::Low => "Low",
+            SigmaLevel::Medium => "Medium",
+            SigmaLevel::High => "High",
+            SigmaLevel::Critical => "Critical",
+        }
+    }
+
+    /// Closest Windows event level (`1` = Critical … `4` = Information),
+    /// used to reuse the table's severity colours.
+    pub fn event_level(self) -> u8 {
+        match self {
+            SigmaLevel::Critical => 1,
+            SigmaLevel::High => 2,
+            SigmaLevel::Medium => 3,
+            SigmaLevel::Low | SigmaLevel::Informational => 4,
+        }
+    }
+}
+
+/// Channels / event IDs a rule applies to, derived from its `logsource`.
+/// Empty lists mean "no constraint".
+#[derive(Debug, Clone, Default)]
+pub struct LogSource {
+    pub channels: Vec<String>,
+    pub event_ids: Vec<u32>,
+}
+
+impl LogSource {
+    pub(crate) fn accepts(&self, event: &EventRecord) -> bool {
+        (self.channels.is_empty()
+            || self
+                .channels
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&event.channel)))
+            && (self.event_ids.is_empty() || self.event_ids.contains(&event.event_id))
+    }
+}
+
+/// Map a Sigma `service` name to Windows channel names; `None` when the
+/// service is unknown.
+fn service_channels(service: &str) -> Option<&'static [&'static str]> {
+    Some(match service {
+        "security" => &["Security"],
+        "system" => &["System"],
+        "application" => &["Application"],
+        "sysmon" => &["Microsoft-Windows-Sysmon/Operational"],
+        "powershell" => &["Microsoft-Windows-PowerShell/Operational"],
+        "powershell-classic" => &["Windows PowerShell"],
+        "windefend" => &["Microsoft-Windows-Windows Defender/Operational"],
+        "taskscheduler" => &["Microsoft-Windows-TaskScheduler/Operational"],
+        "wmi" => &["Microsoft-Windows-WMI-Activity/Operational"],
+        "bits-client" => &["Microsoft-Windows-Bits-Client/Operational"],
+        "codeintegrity-operational" => &["Microsoft-Windows-CodeIntegrity/Operational"],
+        "firewall-as" => &["Microsoft-Windows-Windows Firewall With Advanced Security/Firewall"],
+        "terminalservices-localsessionmanager" => {
+            &["Microsoft-Windows-TerminalServices-LocalSessionManager/Operational"]
+        }
+        _ => return None,
+    })
+}
+
+/// Map a Sigma `category` to channels and event IDs (Sysmon plus the
+/// native Security / PowerShell equivalents where one exists); `None`
+/// when the category is unknown.
+fn category_source(category: &str) -> Option<(&'static [&'static str], &'static [u32])> {
+    const SYSMON: &str = "Microsoft-Windows-Sysmon/Operational";
+    const POWERSHELL: &str = "Microsoft-Windows-PowerShell/Operational";
+    Some(match category {
+        "process_creation" => (&[SYSMON, "Security"], &[1, 4688]),
+        "process_termination" => (&[SYSMON, "Security"], &[5, 4689]),
+        "network_connection" => (&[SYSMON], &[3]),
+        "driver_load" => (&[SYSMON], &[6]),
+        "image_load" => (&[SYSMON], &[7]),
+        "create_remote_thread" => (&[SYSMON], &[8]),
+        "raw_access_thread" => (&[SYSMON], &[9]),
+        "process_access" => (&[SYSMON], &[10]),
+        "file_event" => (&[SYSMON], &[11]),
+        "registry_event" => (&[SYSMON], &[12, 13, 14]),
+        "registry_add" | "registry_delete" => (&[SYSMON], &[12]),
+        "registry_set" => (&[SYSMON], &[13]),
+        "registry_rename" => (&[SYSMON], &[14]),
+        "create_stream_hash" => (&[SYSMON], &[15]),
+        "pipe_created" => (&[SYSMON], &[17, 18]),
+        "wmi_event" => (&[SYSMON], &[19, 20, 21]),
+        "dns_query" => (&[SYSMON], &[22]),
+        "file_delete" => (&[SYSMON], &[23, 26]),
+        "ps_module" => (&[POWERSHELL], &[4103]),
+        "ps_script" => (&[POWERSHELL], &[4104]),
+        _ => return None,
+    })
+}
+
+/// Sigma field names that map to differently named Security 4688 fields,
+/// tried when the primary name is absent.
+const FIELD_ALIASES: &[(&str, &str)] = &[
+    ("Image", "NewProcessName"),
+    ("ParentImage", "ParentProcessName"),
+];
+
+/// Look up a Sigma field on an event: EventData first, then the System
+/// header, then [`FIELD_ALIASES`].
+pub(crate) fn field_value<'a>(event: &'a EventRecord, name: &str) -> Option<Cow<'a, str>> {
+    let data = |key: &str| {
+        event
+            .event_data
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| Cow::Borrowed(v.as_str()))
+    };
+    if let Some(v) = data(name) {
+        return Some(v);
+    }
+    let system = match name.to_ascii_lowercase().as_str() {
+        "eventid" => Some(Cow::Owned(event.event_id.to_string())),
+        "provider_name" | "provider" => Some(Cow::Borrowed(event.provider_name.as_str())),
+        "channel" => Some(Cow::Borrowed(event.channel.as_str())),
+        "computer" | "computername" => Some(Cow::Borrowed(event.computer.as_str())),
+        "level" => Some(Cow::Owned(event.level.to_string())),
+        _ => None,
+    };
+    system.or_else(|| {
+        FIELD_ALIASES
+            .iter()
+            .find(|(sigma, _)| sigma.eq_ignore_ascii_case(name))
+            .and_then(|(_, alias)| data(alias))
+    })
+}
+
+/// One compiled value test. Strings are stored lowercased for the
+/// case-insensitive fast paths.
+#[derive(Debug, Clone)]
+enum ValueMatcher {
+    /// YAML `null`: the field must be absent or empty.
+    Null,
+    Exact(String),
+    Contains(String),
+    StartsWith(String),
+    EndsWith(String),
+    Regex(regex::Regex),
+}
+
+impl ValueMatcher {
+    fn matches(&self, value: Option<&str>) -> bool {
+        let Some(v) = value else {
+            return matches!(self, ValueMatcher::Null);
+        };
+        match self {
+            ValueMatcher::Null => v.is_empty(),
+            ValueMatcher::Exact(s) => {
+                v.eq_ignore_ascii_case(s) || (!v.is_ascii() && v.to_lowercase() == *s)
+            }
+            ValueMatcher::Contains(s) => crate::core::text_search::contains_case_insensitive(v, s),
+            // Lowercasing can change the byte length of non-ASCII text, so
+            // only ASCII haystacks are sliced by the needle's length.
+            ValueMatcher::StartsWith(s) if v.is_ascii() => {
+                v.get(..s.len()).is_some_and(|p| p.eq_ignore_ascii_case(s))
+            }
+            ValueMatcher::StartsWith(s) => v.to_lowercase().starts_with(s.as_str()),
+            ValueMatcher::EndsWith(s) if v.is_ascii() => v
+                .len()
+                .checked_sub(s.len())
+                .and_then(|start| v.get(start..))
+                .is_some_and(|p| p.eq_ignore_ascii_case(s)),
+            ValueMatcher::EndsWith(s) => v.to_lowercase().ends_with(s.as_str()),
+            ValueMatcher::Regex(re) => re.is_match(v),
+        }
+    }
+}
+
+/// How a value is positioned within the field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchKind {
+    Exact,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Regex,
+}
+
+/// Value transformation applied before matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transform {
+    None,
+    Base64,
+    Base64Offset,
+}
+
+/// Base64-encode `value` at each of the three byte offsets and trim the
+/// characters that depend on the unknown surrounding bytes, as defined by
+/// the Sigma `base64offset` modifier.
+fn base64_offsets(value: &str) -> Vec<String> {
+    const START: [usize; 3] = [0, 2, 3];
+    const END_TRIM: [usize; 3] = [0, 3, 2];
+    let engine = base64::engine::general_purpose::STANDARD;
+    (0..3)
+        .map(|i| {
+            let mut bytes = vec![b' '; i];
+            bytes.extend_from_slice(value.as_bytes());
+            let encoded = engine.encode(&bytes);
+            let end_trim = END_TRIM[(value.len() + i) % 3];
+            let end = encoded.len().saturating_sub(end_trim);
+            encoded
+                .get(START[i]..end)
+                .unwrap_or_default()
+                .trim_end_matches('=')
+                .to_owned()
+        })
+        .collect()
+}
+
+/// Case-sensitive matcher for the base64 encodings of one value (any of
+/// `variants`), positioned within the field by `kind`. Encoded text is
+/// case-sensitive, unlike plain Sigma values.
+fn encoded_matcher(variants: &[String], kind: MatchKind) -> Result<ValueMatcher> {
+    let alternation = variants
+        .iter()
+        .map(|s| regex::escape(s))
+        .collect::<Vec<_>>()
+        .join("|");
+    let pattern = match kind {
+        MatchKind::Exact => format!("^(?:{alternation})$"),
+        MatchKind::Contains => format!("(?:{alternation})"),
+        MatchKind::StartsWith => format!("^(?:{alternation})"),
+        MatchKind::EndsWith => format!("(?:{alternation})$"),
+        MatchKind::Regex => {
+            return Err(EventSleuthError::SigmaRule(
+                "'re' cannot be combined with base64 modifiers".into(),
+            ))
+        }
+    };
+    regex::Regex::new(&pattern)
+        .map(ValueMatcher::Regex)
+        .map_err(|e| EventSleuthError::SigmaRule(e.to_string()))
+}
+
+/// Translate a Sigma wildcard value (`*`, `?`, `\` escapes) to a regex.
+fn wildcard_regex(value: &str, kind: MatchKind) -> Result<regex::Regex> {
+    let mut pattern = String::from("(?is)");
+    if !matches!(kind, MatchKind::Contains | MatchKind::EndsWith) {
+        pattern.push('^');
+    }
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('*' | '?' | '\\')) => {
+                let escaped = chars.next().unwrap_or('\\');
+                pattern.push_str(&regex::escape(&escaped.to_string()));
+            }
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    if !matches!(kind, MatchKind::Contains | MatchKind::StartsWith) {
+        pattern.push('$');
+    }
+    regex::Regex::new(&pattern).map_err(|e| EventSleuthError::SigmaRule(e.to_string()))
+}
+
+/// Returns `true` if `value` contains an unescaped `*` or `?`.
+fn has_wildcard(value: &str) -> bool {
+    let mut escaped = false;
+    for c in value.chars() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '*' | '?' if !escaped => return true,
+            _ => escaped = false,
+        }
+    }
+    false
+}
+
+fn compile_value(value: &str, kind: MatchKind) -> Result<ValueMatcher> {
+    if kind == MatchKind::Regex {
+        return regex::Regex::new(value)
+            .map(ValueMatcher::Regex)
+            .map_err(|e| EventSleuthError::SigmaRule(format!("invalid regex: {e}")));
+    }
+    if has_wildcard(value) {
+        return wildcard_regex(value, kind).map(ValueMatcher::Regex);
+    }
+    let unescaped = value.replace("\\*", "*").replace("\\?", "?");
+    let lower = unescaped.to_lowercase();
+    Ok(match kind {
+        MatchKind::Exact => ValueMatcher::Exact(lower),
+        MatchKind::Contains => ValueMatcher::Contains(lower),
+        MatchKind::StartsWith => ValueMatcher::StartsWith(lower),
+        MatchKind::EndsWith => ValueMatcher::EndsWith(lower),
+        MatchKind::Regex => unreachable!("handled above"),
+    })
+}
+
+/// A field test inside a selection: `Field|mods: value(s)`.
+#[derive(Debug, Clone)]
+struct FieldMatch {
+    field: String,
+    matchers: Vec<ValueMatcher>,
+    /// `|all`: every value must match instead of any.
+    all: bool,
+}
+
+impl FieldMatch {
+    fn matches(&self, event: &EventRecord) -> bool {
+        let value = field_value(event, &self.field);
+        let test = |m: &ValueMatcher| m.matches(value.as_deref());
+        if self.all {
+            self.matchers.iter().all(test)
+        } else {
+            self.matchers.iter().any(test)
+        }
+    }
+}
+
+/// One named entry under `detection:`.
+#[derive(Debug, Clone)]
+enum Detection {
+    /// Maps of field tests; the detection matches if any map matches and
+    /// a map matches if all of its field tests do.
+    Selections(Vec<Vec<FieldMatch>>),
+    /// Bare keyword list, matched as substrings against the message and
+    /// EventData values.
+    Keywords(Vec<ValueMatcher>),
+}
+
+impl Detection {
+    fn matches(&self, event: &EventRecord) -> bool {
+        match self {
+            Detection::Selections(maps) => maps
+                .iter()
+                .any(|fields| fields.iter().all(|f| f.matches(event))),
+            Detection::Keywords(words) => words.iter().any(|w| {
+                w.matches(Some(&event.message))
+                    || event.event_data.iter().any(|(_, v)| w.matches(Some(v)))
+            }),
+        }
+    }
+}
+
+/// A loaded, compiled Sigma rule.
+#[derive(Debug, Clone)]
+pub struct SigmaRule {
+    pub title: String,
+    pub id: String,
+    pub description: String,
+    pub level: SigmaLevel,
+    /// Raw tags, e.g. `attack.t1059.001`, `attack.execution`.
+    pub tags: Vec<String>,
+    /// File the rule was loaded from (empty for built-in rules).
+    pub path: PathBuf,
+    logsource: LogSource,
+    names: Vec<String>,
+    detections: Vec<Detection>,
+    condition: Condition,
+}
+
+impl SigmaRule {
+    /// Parse a single-document Sigma rule from YAML text.
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Self::from_value(&parse_yaml(text)?)
+    }
+
+    /// Build a rule from an already-parsed YAML document.
+    pub fn from_value(doc: &serde_yaml::Value) -> Result<Self> {
+        let str_field = |key: &str| {
+            doc.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_owned()
+        };
+
+        let title = str_field("title");
+        if title.is_empty() {
+            return Err(EventSleuthError::SigmaRule("rule has no title".into()));
+        }
+        let tags = string_list(doc.get("tags"));
+
+        let logsource = parse_logsource(doc.get("logsource"))?;
+
+        let detection = doc
+            .get("detection")
+            .and_then(|d| d.as_mapping())
+            .ok_or_else(|| EventSleuthError::SigmaRule("rule has no detection".into()))?;
+        let mut names = Vec::new();
+        let mut detections = Vec::new();
+        let mut condition_value = None;
+        for (key, value) in detection {
+            let Some(key) = key.as_str() else { continue };
+            if key == "condition" {
+                condition_value = Some(value);
+            } else if key != "timeframe" {
+                names.push(key.to_owned());
+                detections.push(parse_detection(value)?);
+            }
+        }
+
+        let condition = match condition_value {
+            Some(serde_yaml::Value::String(s)) => parse_condition(s)?,
+            // A list of conditions is an implicit OR.
+            Some(serde_yaml::Value::Sequence(seq)) => Condition::Or(
+                seq.iter()
+                    .map(|c| parse_condition(c.as_str().unwrap_or_default()))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => return Err(EventSleuthError::SigmaRule("rule has no condition".into())),
+        };
+        condition.validate(&names)?;
+
+        Ok(Self {
+            title,
+            id: str_field("id"),
+            description: str_field("description"),
+            level: SigmaLevel::parse(&str_field("level")),
+            tags,
+            path: PathBuf::new(),
+            logsource,
+            names,
+            detections,
+            condition,
+        })
+    }
+
+    /// Test the rule against one event.
+    pub fn matches(&self, event: &EventRecord) -> bool {
+        if !self.logsource.accepts(event) {
+            return false;
+        }
+        let eval = |name: &str| {
+            self.names
+                .iter()
+                .position(|n| n == name)
+                .is_some_and(|i| self.detections[i].matches(event))
+        };
+        self.condition.evaluate(&self.names, &eval)
+    }
+
+    /// MITRE ATT&CK tags (those starting with `attack.`), without the prefix.
+    pub fn attack_tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().filter_map(|t| t.strip_prefix("attack."))
+    }
+}
+
+pub(crate) fn parse_logsource(value: Option<&serde_yaml::Value>) -> Result<LogSource> {
+    let get = |key: &str| {
+        value
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_ascii_lowercase)
+    };
+    if let Some(product) = get("product") {
+        if product != "windows" {
+            return Err(EventSleuthError::SigmaRule(format!(
+                "logsource product '{product}' is not Windows"
+            )));
+        }
+    }
+    // An unmapped service or category would leave the source
+    // unconstrained and run the rule against every channel.
+    let mut source = LogSource::default();
+    if let Some(service) = get("service") {
+        let channels = service_channels(&service).ok_or_else(|| {
+            EventSleuthError::SigmaRule(format!("unsupported logsource service '{service}'"))
+        })?;
+        source.channels = channels.iter().map(|s| s.to_string()).collect();
+    }
+    if let Some(category) = get("category") {
+        let (channels, ids) = category_source(&category).ok_or_else(|| {
+            EventSleuthError::SigmaRule(format!("unsupported logsource category '{category}'"))
+        })?;
+        if source.channels.is_empty() {
+            source.channels = channels.iter().map(|s| s.to_string()).collect();
+        }
+        source.event_ids = ids.to_vec();
+    }
+    Ok(source)
+}
+
+/// Parse YAML text into a generic document.
+pub(crate) fn parse_yaml(text: &str) -> Result<serde_yaml::Value> {
+    serde_yaml::from_str(text)
+        .map_err(|e| EventSleuthError::SigmaRule(format!("invalid YAML: {e}")))
+}
+
+/// Read a YAML list of strings (or a single string) such as `tags:`.
+pub(crate) fn string_list(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    match value {
+        Some(serde_yaml::Value::Sequence(seq)) => seq
+            .iter()
+            .filter_map(|t| t.as_str().map(str::to_owned))
+            .collect(),
+        Some(serde_yaml::Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+/// A standalone detection block (selection map, list of maps, or keyword
+/// list) matched on its own, e.g. one step of a correlation rule.
+#[derive(Debug, Clone)]
+pub struct Selection(Detection);
+
+impl Selection {
+    pub fn parse(value: &serde_yaml::Value) -> Result<Self> {
+        parse_detection(value).map(Selection)
+    }
+
+    pub fn matches(&self, event: &EventRecord) -> bool {
+        self.0.matches(event)
+    }
+}
+
+/// Render a YAML scalar as the string Sigma compares against, or `None`
+/// for `null`.
+fn scalar_text(value: &serde_yaml::Value) -> Result<Option<String>> {
+    match value {
+        serde_yaml::Value::Null => Ok(None),
+        serde_yaml::Value::String(s) => Ok(Some(s.clone())),
+        serde_yaml::Value::Number(n) => Ok(Some(n.to_string())),
+        serde_yaml::Value::Bool(b) => Ok(Some(b.to_string())),
+        _ => Err(EventSleuthError::SigmaRule(
+            "detection values must be scalars".into(),
+        )),
+    }
+}
+
+fn parse_detection(value: &serde_yaml::Value) -> Result<Detection> {
+    match value {
+        serde_yaml::Value::Mapping(map) => Ok(Detection::Selections(vec![parse_map(map)?])),
+        serde_yaml::Value::Sequence(seq) if seq.iter().all(|v| v.is_mapping()) => {
+            let maps = seq
+                .iter()
+                .filter_map(|v| v.as_mapping())
+                .map(parse_map)
+                .collect::<Result<_>>()?;
+            Ok(Detection::Selections(maps))
+        }
+        serde_yaml::Value::Sequence(seq) => {
+            let words = seq
+                .iter()
+                .filter_map(|v| scalar_text(v).transpose())
+                .map(|w| w.and_then(|w| compile_value(&w, MatchKind::Contains)))
+                .collect::<Result<_>>()?;
+            Ok(Detection::Keywords(words))
+        }
+        _ => Err(EventSleuthError::SigmaRule(
+            "detection must be a map or a list".into(),
+        )),
+    }
+}
+
+fn parse_map(map: &serde_yaml::Mapping) -> Result<Vec<FieldMatch>> {
+    map.iter()
+        .map(|(key, value)| {
+            let key = key
+                .as_str()
+                .ok_or_else(|| EventSleuthError::SigmaRule("field names must be strings".into()))?;
+            parse_field(key, value)
+        })
+        .collect()
+}
+
+fn parse_field(spec: &str, value: &serde_yaml::Value) -> Result<FieldMatch> {
+    let mut parts = spec.split('|');
+    let field = parts.next().unwrap_or_default().to_owned();
+    let mut kind = MatchKind::Exact;
+    let mut transform = Transform::None;
+    let mut all = false;
+    for modifier in parts {
+        match modifier {
+            "contains" => kind = MatchKind::Contains,
+            "startswith" => kind = MatchKind::StartsWith,
+            "endswith" => kind = MatchKind::EndsWith,
+            "re" => kind = MatchKind::Regex,
+            "all" => all = true,
+            "base64" => transform = Transform::Base64,
+            "base64offset" => transform = Transform::Base64Offset,
+            other => {
+                return Err(EventSleuthError::SigmaRule(format!(
+                    "unsupported modifier '{other}'"
+                )))
+            }
+        }
+    }
+
+    let raw: Vec<Option<String>> = match value {
+        serde_yaml::Value::Sequence(seq) => seq.iter().map(scalar_text).collect::<Result<_>>()?,
+        other => vec![scalar_text(other)?],
+    };
+
+    let mut matchers = Vec::new();
+    for v in raw {
+        let Some(v) = v else {
+            matchers.push(ValueMatcher::Null);
+            continue;
+        };
+        match transform {
+            Transform::None => matchers.push(compile_value(&v, kind)?),
+            Transform::Base64 => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(v.as_bytes());
+                matchers.push(encoded_matcher(&[encoded], kind)?);
+            }
+            // The three offset encodings are alternatives for one value, so
+            // they share one matcher and `|all` still means every value.
+            Transform::Base64Offset => {
+                matchers.push(encoded_matcher(&base64_offsets(&v), kind)?);
+            }
+        }
+    }
+    Ok(FieldMatch {
+        field,
+        matchers,
+        all,
+    })
+}
+
+/// Outcome of loading a rules directory.
+#[derive(Debug, Default)]
+pub struct SigmaLoadReport {
+    pub rules: Vec<SigmaRule>,
+    /// Files with a top-level `sequence:` key, loaded as correlation rules.
+    pub correlations: Vec<CorrelationRule>,
+    /// Files that failed to parse or use unsupported features.
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// Recursively load every `.yml` / `.yaml` file under `dir`.
+///
+/// Individual rule failures are collected in
+/// [`SigmaLoadReport::errors`] rather than aborting the load; only an
+/// unreadable top-level directory is an error.
+pub fn load_rules_dir(dir: &Path) -> Result<SigmaLoadReport> {
+    let mut report = SigmaLoadReport::default();
+    let mut stack = vec![dir.to_path_buf()];
+    let mut first = true;
+    while let Some(current) = stack.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(e) => e,
+            Err(e) if first => return Err(e.into()),
+            Err(e) => {
+                report.errors.push((current, e.to_string()));
+                continue;
+            }
+        };
+        first = false;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let is_yaml = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case("yml") || e.eq_ignore_ascii_case("yaml"));
+            if !is_yaml {
+                continue;
+            }
+            let doc = std::fs::read_to_string(&path)
+                .map_err(EventSleuthError::from)
+                .and_then(|text| parse_yaml(&text));
+            let loaded = doc.and_then(|doc| {
+                if doc.get("sequence").is_some() {
+                    let mut rule = CorrelationRule::from_value(&doc)?;
+                    rule.path = path.clone();
+                    report.correlations.push(rule);
+                } else {
+                    let mut rule = SigmaRule::from_value(&doc)?;
+                    rule.path = path.clone();
+                    report.rules.push(rule);
+                }
+                Ok(())
+            });
+            if let Err(e) = loaded {
+                report.errors.push((path, e.to_string()));
+            }
+        }
+    }
+    report.rules.sort_by(|a, b| a.title.cmp(&b.title));
+    report.correlations.sort_by(|a, b| a.title.cmp(&b.title));
+    Ok(report)
+}
+
+/// Evaluate every rule against `events[start..]`, appending matching
+/// indices (into `events`) to `hits[rule_index]`.
+///
+/// `hits` is resized to `rules.len()` if needed, so callers can keep one
+/// vector and feed it successive live-tail batches.
+pub fn evaluate_rules(
+    rules: &[SigmaRule],
+    events: &[EventRecord],
+    start: usize,
+    hits: &mut Vec<Vec<usize>>,
+) {
+    hits.resize_with(rules.len(), Vec::new);
+    for (idx, event) in events.iter().enumerate().skip(start) {
+        for (rule, rule_hits) in rules.iter().zip(hits.iter_mut()) {
+            if rule.matches(event) {
+                rule_hits.push(idx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "sigma_tests.rs"]
+mod tests;
@@ -0,0 +1,256 @@
+//! Parser and evaluator for Sigma `condition` expressions.
+//!
+//! Supports the boolean subset used by the vast majority of public rules:
+//! `and`, `or`, `not`, parentheses, named selections, and the
+//! `1 of <pattern>` / `all of <pattern>` / `1 of them` / `all of them`
+//! quantifiers (patterns may end in `*`). Aggregations (`| count() > N`)
+//! and `near` are rejected as unsupported.
+
+use crate::util::error::{EventSleuthError, Result};
+
+/// A parsed condition expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// A single named detection.
+    Ref(String),
+    /// At least one detection whose name matches the pattern.
+    OneOf(String),
+    /// Every detection whose name matches the pattern.
+    AllOf(String),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Evaluate the expression. `names` lists every detection in the rule
+    /// (for quantifier patterns); `eval` tests one named detection.
+    pub fn evaluate(&self, names: &[String], eval: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Condition::Ref(name) => eval(name),
+            Condition::OneOf(pat) => names
+                .iter()
+                .filter(|n| pattern_matches(pat, n))
+                .any(|n| eval(n)),
+            Condition::AllOf(pat) => {
+                let mut matched = names.iter().filter(|n| pattern_matches(pat, n)).peekable();
+                matched.peek().is_some() && matched.all(|n| eval(n))
+            }
+            Condition::And(parts) => parts.iter().all(|c| c.evaluate(names, eval)),
+            Condition::Or(parts) => parts.iter().any(|c| c.evaluate(names, eval)),
+            Condition::Not(inner) => !inner.evaluate(names, eval),
+        }
+    }
+
+    /// Check that every plain reference names an existing detection.
+    pub fn validate(&self, names: &[String]) -> Result<()> {
+        match self {
+            Condition::Ref(name) if !names.iter().any(|n| n == name) => Err(
+                EventSleuthError::SigmaRule(format!("condition references unknown '{name}'")),
+            ),
+            Condition::And(parts) | Condition::Or(parts) => {
+                parts.iter().try_for_each(|c| c.validate(names))
+            }
+            Condition::Not(inner) => inner.validate(names),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `them` matches every detection except `_`-prefixed ones; a trailing
+/// `*` is a prefix match; anything else must match exactly.
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    if pattern == "them" {
+        return !name.starts_with('_');
+    }
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// Parse a condition string such as `selection and not 1 of filter_*`.
+pub fn parse_condition(input: &str) -> Result<Condition> {
+    if input.contains('|') {
+        return Err(EventSleuthError::SigmaRule(
+            "aggregation conditions are not supported".into(),
+        ));
+    }
+    let tokens = tokenize(input);
+    let mut parser = Parser { tokens, pos: 0 };
+    let cond = parser.parse_or()?;
+    if let Some(tok) = parser.peek() {
+        return Err(EventSleuthError::SigmaRule(format!(
+            "unexpected '{tok}' in condition"
+        )));
+    }
+    Ok(cond)
+}
+
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in input.chars() {
+        match c {
+            '(' | ')' => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                tokens.push(c.to_string());
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn peek_keyword(&self, kw: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(kw))
+    }
+
+    fn parse_or(&mut self) -> Result<Condition> {
+        let mut parts = vec![self.parse_and()?];
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            parts.push(self.parse_and()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Condition::Or(parts)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Condition> {
+        let mut parts = vec![self.parse_not()?];
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            parts.push(self.parse_not()?);
+        }
+        Ok(if parts.len() == 1 {
+            parts.remove(0)
+        } else {
+            Condition::And(parts)
+        })
+    }
+
+    fn parse_not(&mut self) -> Result<Condition> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition> {
+        let tok = self
+            .next()
+            .ok_or_else(|| EventSleuthError::SigmaRule("condition ends unexpectedly".into()))?;
+        match tok.to_ascii_lowercase().as_str() {
+            "(" => {
+                let inner = self.parse_or()?;
+                match self.next().as_deref() {
+                    Some(")") => Ok(inner),
+                    _ => Err(EventSleuthError::SigmaRule(
+                        "missing ')' in condition".into(),
+                    )),
+                }
+            }
+            ")" | "and" | "or" => Err(EventSleuthError::SigmaRule(format!(
+                "unexpected '{tok}' in condition"
+            ))),
+            "near" => Err(EventSleuthError::SigmaRule(
+                "'near' conditions are not supported".into(),
+            )),
+            quant @ ("1" | "any" | "all") if self.peek_keyword("of") => {
+                self.pos += 1;
+                let pattern = self.next().ok_or_else(|| {
+                    EventSleuthError::SigmaRule("missing pattern after 'of'".into())
+                })?;
+                Ok(if quant == "all" {
+                    Condition::AllOf(pattern)
+                } else {
+                    Condition::OneOf(pattern)
+                })
+            }
+            _ => Ok(Condition::Ref(tok)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_precedence() {
+        let c = parse_condition("a or b and not c").unwrap();
+        assert_eq!(
+            c,
+            Condition::Or(vec![
+                Condition::Ref("a".into()),
+                Condition::And(vec![
+                    Condition::Ref("b".into()),
+                    Condition::Not(Box::new(Condition::Ref("c".into()))),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn quantifiers_use_patterns() {
+        let all = names(&["selection_a", "selection_b", "filter"]);
+        let c = parse_condition("all of selection_* and not filter").unwrap();
+        let eval = |n: &str| n.starts_with("selection");
+        assert!(c.evaluate(&all, &eval));
+        let c = parse_condition("1 of them").unwrap();
+        assert!(c.evaluate(&all, &|n: &str| n == "filter"));
+        let hidden = names(&["selection", "_helper"]);
+        assert!(!c.evaluate(&hidden, &|n: &str| n == "_helper"));
+    }
+
+    #[test]
+    fn parentheses_group() {
+        let c = parse_condition("(a or b) and c").unwrap();
+        let all = names(&["a", "b", "c"]);
+        assert!(c.evaluate(&all, &|n: &str| n == "b" || n == "c"));
+        assert!(!c.evaluate(&all, &|n: &str| n == "b"));
+    }
+
+    #[test]
+    fn rejects_unsupported_and_malformed() {
+        assert!(parse_condition("selection | count() > 5").is_err());
+        assert!(parse_condition("(a and b").is_err());
+        assert!(parse_condition("a and").is_err());
+        let c = parse_condition("missing").unwrap();
+        assert!(c.validate(&names(&["selection"])).is_err());
+    }
+}
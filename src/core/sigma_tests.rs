@@ -0,0 +1,275 @@
+use super::*;
+
+fn event(channel: &str, id: u32, data: &[(&str, &str)]) -> EventRecord {
+    EventRecord {
+        channel: channel.into(),
+        event_id: id,
+        provider_name: "Microsoft-Windows-Sysmon".into(),
+        event_data: EventRecord::test_data(data),
+        ..EventRecord::test_base()
+    }
+}
+
+const SYSMON: &str = "Microsoft-Windows-Sysmon/Operational";
+
+const ENCODED_PS: &str = r#"
+title: Encoded PowerShell
+id: 00000000-0000-0000-0000-000000000001
+level: high
+tags:
+  - attack.execution
+  - attack.t1059.001
+logsource:
+  product: windows
+  category: process_creation
+detection:
+  selection:
+    Image|endswith: '\powershell.exe'
+    CommandLine|contains:
+      - ' -enc '
+      - ' -EncodedCommand '
+  filter:
+    ParentImage: 'C:\Windows\System32\svchost.exe'
+  condition: selection and not filter
+"#;
+
+#[test]
+fn parses_metadata_and_tags() {
+    let rule = SigmaRule::from_yaml(ENCODED_PS).unwrap();
+    assert_eq!(rule.title, "Encoded PowerShell");
+    assert_eq!(rule.level, SigmaLevel::High);
+    assert_eq!(
+        rule.attack_tags().collect::<Vec<_>>(),
+        vec!["execution", "t1059.001"]
+    );
+}
+
+#[test]
+fn selection_and_filter() {
+    let rule = SigmaRule::from_yaml(ENCODED_PS).unwrap();
+    let hit = event(
+        SYSMON,
+        1,
+        &[
+            (
+                "Image",
+                r"C:\Windows\System32\WindowsPowerShell\v1.0\PowerShell.exe",
+            ),
+            ("CommandLine", "powershell -ENC SQBFAFgA"),
+            ("ParentImage", r"C:\Windows\explorer.exe"),
+        ],
+    );
+    assert!(rule.matches(&hit));
+
+    let filtered = event(
+        SYSMON,
+        1,
+        &[
+            ("Image", r"C:\x\powershell.exe"),
+            ("CommandLine", "powershell -enc AAAA"),
+            ("ParentImage", r"C:\Windows\System32\svchost.exe"),
+        ],
+    );
+    assert!(!rule.matches(&filtered));
+}
+
+#[test]
+fn logsource_restricts_channel_and_id() {
+    let rule = SigmaRule::from_yaml(ENCODED_PS).unwrap();
+    let data = [
+        ("Image", r"C:\x\powershell.exe"),
+        ("CommandLine", "powershell -enc AAAA"),
+    ];
+    assert!(!rule.matches(&event("Application", 1, &data)));
+    assert!(!rule.matches(&event(SYSMON, 3, &data)));
+}
+
+#[test]
+fn security_4688_uses_field_aliases() {
+    let rule = SigmaRule::from_yaml(ENCODED_PS).unwrap();
+    let e = event(
+        "Security",
+        4688,
+        &[
+            ("NewProcessName", r"C:\x\powershell.exe"),
+            ("CommandLine", "powershell -enc AAAA"),
+        ],
+    );
+    assert!(rule.matches(&e));
+}
+
+#[test]
+fn wildcards_all_and_regex() {
+    let yaml = r#"
+title: Mixed modifiers
+logsource:
+  product: windows
+  service: security
+detection:
+  sel_user:
+    TargetUserName: 'adm*'
+  sel_all:
+    CommandLine|contains|all: ['net', 'user', '/add']
+  sel_re:
+    IpAddress|re: '^10\.\d+\.\d+\.\d+$'
+  condition: sel_user or sel_all or sel_re
+"#;
+    let rule = SigmaRule::from_yaml(yaml).unwrap();
+    assert!(rule.matches(&event(
+        "Security",
+        4625,
+        &[("TargetUserName", "Administrator")]
+    )));
+    assert!(!rule.matches(&event("Security", 4625, &[("TargetUserName", "sadmin")])));
+    assert!(rule.matches(&event(
+        "Security",
+        4688,
+        &[("CommandLine", "net user bob P@ss /add")]
+    )));
+    assert!(!rule.matches(&event("Security", 4688, &[("CommandLine", "net user bob")])));
+    assert!(rule.matches(&event("Security", 4625, &[("IpAddress", "10.1.2.3")])));
+}
+
+#[test]
+fn base64offset_matches_every_alignment() {
+    let yaml = r#"
+title: Base64 IEX
+logsource:
+  product: windows
+  service: powershell
+detection:
+  selection:
+    ScriptBlockText|base64offset|contains: 'IEX'
+  condition: selection
+"#;
+    let rule = SigmaRule::from_yaml(yaml).unwrap();
+    let engine = base64::engine::general_purpose::STANDARD;
+    for prefix in ["", "a", "ab"] {
+        let payload = engine.encode(format!("{prefix}IEX (New-Object Net.WebClient)"));
+        let e = event(
+            "Microsoft-Windows-PowerShell/Operational",
+            4104,
+            &[("ScriptBlockText", payload.as_str())],
+        );
+        assert!(rule.matches(&e), "prefix {prefix:?} should match");
+        // Base64 is case-sensitive: the lowercased payload decodes to
+        // something else entirely.
+        let lowered = event(
+            "Microsoft-Windows-PowerShell/Operational",
+            4104,
+            &[("ScriptBlockText", payload.to_lowercase().as_str())],
+        );
+        assert!(!rule.matches(&lowered), "prefix {prefix:?} lowercased");
+    }
+}
+
+#[test]
+fn base64offset_all_respects_position() {
+    let yaml = r#"
+title: Base64 prefix
+logsource:
+  product: windows
+  service: powershell
+detection:
+  selection:
+    ScriptBlockText|base64offset|startswith|all:
+      - 'IEX'
+  condition: selection
+"#;
+    let rule = SigmaRule::from_yaml(yaml).unwrap();
+    let engine = base64::engine::general_purpose::STANDARD;
+    let text = |s: &str| {
+        event(
+            "Microsoft-Windows-PowerShell/Operational",
+            4104,
+            &[("ScriptBlockText", engine.encode(s).as_str())],
+        )
+    };
+    assert!(rule.matches(&text("IEX (New-Object Net.WebClient)")));
+    assert!(!rule.matches(&text("Write-Host 'x'; IEX $a")));
+}
+
+#[test]
+fn startswith_and_endswith_handle_non_ascii() {
+    let yaml = r#"
+title: Non-ASCII prefix
+logsource:
+  product: windows
+  service: sysmon
+detection:
+  selection:
+    Image|startswith: 'İx'
+    CommandLine|endswith: 'STRASSEİ'
+  condition: selection
+"#;
+    let rule = SigmaRule::from_yaml(yaml).unwrap();
+    let e = event(
+        "Microsoft-Windows-Sysmon/Operational",
+        1,
+        &[("Image", "\u{130}xyz"), ("CommandLine", "a strasse\u{130}")],
+    );
+    assert!(rule.matches(&e));
+}
+
+#[test]
+fn keywords_and_system_fields() {
+    let yaml = r#"
+title: Keyword
+logsource:
+  product: windows
+  service: system
+detection:
+  keywords:
+    - 'mimikatz'
+  sel:
+    EventID: 7045
+  condition: keywords and sel
+"#;
+    let rule = SigmaRule::from_yaml(yaml).unwrap();
+    assert!(rule.matches(&event("System", 7045, &[("ImagePath", "C:\\mimikatz.exe")])));
+    assert!(!rule.matches(&event("System", 7036, &[("ImagePath", "C:\\mimikatz.exe")])));
+}
+
+#[test]
+fn rejects_unsupported_rules() {
+    let linux = "title: x\nlogsource: {product: linux}\ndetection: {sel: {a: b}, condition: sel}";
+    assert!(SigmaRule::from_yaml(linux).is_err());
+    let cidr = "title: x\ndetection: {sel: {'IpAddress|cidr': '10.0.0.0/8'}, condition: sel}";
+    assert!(SigmaRule::from_yaml(cidr).is_err());
+    let no_cond = "title: x\ndetection: {sel: {a: b}}";
+    assert!(SigmaRule::from_yaml(no_cond).is_err());
+}
+
+#[test]
+fn rejects_unknown_logsource_service_and_category() {
+    let ntlm = "title: x\nlogsource: {product: windows, service: ntlm}\ndetection: {sel: {EventID: 8001}, condition: sel}";
+    let err = SigmaRule::from_yaml(ntlm).unwrap_err().to_string();
+    assert!(
+        err.contains("unsupported logsource service 'ntlm'"),
+        "{err}"
+    );
+    let category = "title: x\nlogsource: {product: windows, category: antivirus}\ndetection: {sel: {EventID: 1116}, condition: sel}";
+    let err = SigmaRule::from_yaml(category).unwrap_err().to_string();
+    assert!(
+        err.contains("unsupported logsource category 'antivirus'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn evaluate_rules_appends_from_offset() {
+    let rule = SigmaRule::from_yaml(
+        "title: x\nlogsource: {product: windows, service: system}\ndetection: {sel: {EventID: 7045}, condition: sel}",
+    )
+    .unwrap();
+    let events = vec![
+        event("System", 7045, &[]),
+        event("System", 7036, &[]),
+        event("System", 7045, &[]),
+    ];
+    let mut hits = Vec::new();
+    evaluate_rules(std::slice::from_ref(&rule), &events, 0, &mut hits);
+    assert_eq!(hits, vec![vec![0, 2]]);
+    evaluate_rules(std::slice::from_ref(&rule), &events, 2, &mut hits);
+    assert_eq!(hits, vec![vec![0, 2, 2]]);
+}
@@ -0,0 +1,311 @@
+//! Summary statistics over the filtered event set.
+//!
+//! Counts by severity, top-N breakdowns by provider, channel, computer,
+//! event ID and user, a time histogram with per-level counts in every
+//! bucket, and a day-of-week × hour-of-day heatmap that folds the whole
+//! time span onto one week to expose periodic activity. Buckets and slots
+//! are aligned in the caller's time zone (local or UTC).
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, TimeZone, Timelike, Utc};
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::MAX_STATS_TOP_N;
+
+/// Histogram bucket width.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    Minute,
+    #[default]
+    Hour,
+    Day,
+}
+
+impl Granularity {
+    pub const ALL: [Granularity; 3] = [Granularity::Minute, Granularity::Hour, Granularity::Day];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Granularity::Minute => "Minute",
+            Granularity::Hour => "Hour",
+            Granularity::Day => "Day",
+        }
+    }
+
+    /// Width of one bucket.
+    pub fn step(self) -> Duration {
+        match self {
+            Granularity::Minute => Duration::minutes(1),
+            Granularity::Hour => Duration::hours(1),
+            Granularity::Day => Duration::days(1),
+        }
+    }
+
+    /// Number of most recent buckets kept in the histogram.
+    pub fn max_buckets(self) -> usize {
+        match self {
+            Granularity::Minute => 120,
+            Granularity::Hour => 72,
+            Granularity::Day => 62,
+        }
+    }
+
+    /// Start of the bucket containing local time `t`.
+    pub fn floor(self, t: NaiveDateTime) -> NaiveDateTime {
+        let t = t
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(t);
+        match self {
+            Granularity::Minute => t,
+            Granularity::Hour => t.with_minute(0).unwrap_or(t),
+            Granularity::Day => t.date().and_hms_opt(0, 0, 0).unwrap_or(t),
+        }
+    }
+
+    /// Label format for bucket starts.
+    pub fn label_format(self) -> &'static str {
+        match self {
+            Granularity::Minute => "%m-%d %H:%M",
+            Granularity::Hour => "%m-%d %H:00",
+            Granularity::Day => "%Y-%m-%d",
+        }
+    }
+}
+
+/// One histogram bucket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeBucket {
+    /// Bucket start in the histogram's local time.
+    pub start: NaiveDateTime,
+    /// Counts per severity level (index 0..=5).
+    pub levels: [usize; 6],
+}
+
+impl TimeBucket {
+    pub fn total(&self) -> usize {
+        self.levels.iter().sum()
+    }
+}
+
+/// Event ID breakdown entry; the same ID from two providers counts twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventIdCount {
+    pub event_id: u32,
+    pub provider: String,
+    pub count: usize,
+}
+
+/// Pre-computed statistics snapshot for the current event set.
+///
+/// Computed lazily and cached in app state; invalidated whenever
+/// `needs_refilter` triggers or the event list changes.
+#[derive(Debug, Clone, Default)]
+pub struct EventStats {
+    /// Total events in the filtered set.
+    pub total: usize,
+    /// Counts per severity level (index 0..=5).
+    pub level_counts: [usize; 6],
+    /// Top N `(value, count)` breakdowns, most frequent first.
+    pub top_providers: Vec<(String, usize)>,
+    pub top_channels: Vec<(String, usize)>,
+    pub top_computers: Vec<(String, usize)>,
+    /// Keyed by user SID; events without one are not counted.
+    pub top_users: Vec<(String, usize)>,
+    pub top_event_ids: Vec<EventIdCount>,
+    /// Contiguous buckets, oldest first, at most
+    /// [`Granularity::max_buckets`] (the most recent ones).
+    pub histogram: Vec<TimeBucket>,
+    pub granularity: Granularity,
+    /// Event counts by `[weekday][hour]`, Monday first.
+    pub week_hours: [[usize; 24]; 7],
+}
+
+/// Short weekday names, Monday first (matching [`week_slot`]).
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// `(weekday, hour)` of `ts` in time zone `tz`; weekday 0 is Monday.
+pub fn week_slot<Tz: TimeZone>(ts: DateTime<Utc>, tz: &Tz) -> (usize, usize) {
+    let t = ts.with_timezone(tz);
+    (
+        t.weekday().num_days_from_monday() as usize,
+        t.hour() as usize,
+    )
+}
+
+/// Most frequent `N` entries of `counts`, ties broken by key.
+fn top_n<K: Ord>(counts: HashMap<K, usize>) -> Vec<(K, usize)> {
+    let mut v: Vec<(K, usize)> = counts.into_iter().collect();
+    v.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    v.truncate(MAX_STATS_TOP_N);
+    v
+}
+
+fn owned(v: Vec<(&str, usize)>) -> Vec<(String, usize)> {
+    v.into_iter().map(|(k, c)| (k.to_owned(), c)).collect()
+}
+
+/// Compute statistics over `indices` (positions into `events`), with the
+/// histogram bucketed at `granularity` in time zone `tz`.
+pub fn compute<Tz: TimeZone>(
+    events: &[EventRecord],
+    indices: &[usize],
+    granularity: Granularity,
+    tz: &Tz,
+) -> EventStats {
+    let mut level_counts = [0usize; 6];
+    let mut providers: HashMap<&str, usize> = HashMap::new();
+    let mut channels: HashMap<&str, usize> = HashMap::new();
+    let mut computers: HashMap<&str, usize> = HashMap::new();
+    let mut users: HashMap<&str, usize> = HashMap::new();
+    let mut event_ids: HashMap<(u32, &str), usize> = HashMap::new();
+    let mut buckets: BTreeMap<NaiveDateTime, [usize; 6]> = BTreeMap::new();
+    let mut week_hours = [[0usize; 24]; 7];
+
+    for &idx in indices {
+        let e = &events[idx];
+        let level = (e.level as usize).min(5);
+        level_counts[level] += 1;
+        *providers.entry(&e.provider_name).or_default() += 1;
+        *channels.entry(&e.channel).or_default() += 1;
+        *computers.entry(&e.computer).or_default() += 1;
+        if let Some(sid) = e.user_sid.as_deref() {
+            *users.entry(sid).or_default() += 1;
+        }
+        *event_ids.entry((e.event_id, &e.provider_name)).or_default() += 1;
+        let local = e.timestamp.with_timezone(tz).naive_local();
+        buckets.entry(granularity.floor(local)).or_default()[level] += 1;
+        let (day, hour) = week_slot(e.timestamp, tz);
+        week_hours[day][hour] += 1;
+    }
+
+    EventStats {
+        total: indices.len(),
+        level_counts,
+        top_providers: owned(top_n(providers)),
+        top_channels: owned(top_n(channels)),
+        top_computers: owned(top_n(computers)),
+        top_users: owned(top_n(users)),
+        top_event_ids: top_n(event_ids)
+            .into_iter()
+            .map(|((event_id, provider), count)| EventIdCount {
+                event_id,
+                provider: provider.to_owned(),
+                count,
+            })
+            .collect(),
+        histogram: fill_buckets(&buckets, granularity),
+        granularity,
+        week_hours,
+    }
+}
+
+/// Expand sparse bucket counts into the contiguous run of the most recent
+/// [`Granularity::max_buckets`] buckets, empty ones included.
+fn fill_buckets(
+    buckets: &BTreeMap<NaiveDateTime, [usize; 6]>,
+    granularity: Granularity,
+) -> Vec<TimeBucket> {
+    let (Some((&first, _)), Some((&last, _))) =
+        (buckets.first_key_value(), buckets.last_key_value())
+    else {
+        return Vec::new();
+    };
+    let step = granularity.step();
+    let back = step * (granularity.max_buckets() as i32 - 1);
+    let start = if last - first > back {
+        last - back
+    } else {
+        first
+    };
+
+    let mut out = Vec::new();
+    let mut t = start;
+    while t <= last {
+        out.push(TimeBucket {
+            start: t,
+            levels: buckets.get(&t).copied().unwrap_or_default(),
+        });
+        t += step;
+    }
+    out
+}
+
+/// UTC range `[from, to)` covered by the bucket starting at local `start`
+/// in time zone `tz`, or `None` when that local time does not exist.
+pub fn bucket_range<Tz: TimeZone>(
+    start: NaiveDateTime,
+    granularity: Granularity,
+    tz: &Tz,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let to_utc = |t: NaiveDateTime| {
+        tz.from_local_datetime(&t)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+    };
+    Some((to_utc(start)?, to_utc(start + granularity.step())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(level: u8, secs: i64, computer: &str, sid: Option<&str>) -> EventRecord {
+        EventRecord {
+            channel: "System".into(),
+            event_id: 7036,
+            level,
+            level_name: String::new(),
+            provider_name: "Service Control Manager".into(),
+            timestamp: EventRecord::test_time(secs),
+            computer: computer.into(),
+            user_sid: sid.map(Into::into),
+            ..EventRecord::test_base()
+        }
+    }
+
+    #[test]
+    fn breakdowns_and_stacked_buckets() {
+        // 1_700_000_000 is 22:13:20 UTC.
+        let events = vec![
+            event(2, 0, "DC01", Some("S-1-5-18")),
+            event(4, 60, "DC01", Some("S-1-5-18")),
+            event(4, 3 * 3600, "WS07", None),
+        ];
+        let stats = compute(&events, &[0, 1, 2], Granularity::Hour, &Utc);
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.top_computers[0], ("DC01".to_owned(), 2));
+        assert_eq!(stats.top_users, vec![("S-1-5-18".to_owned(), 2)]);
+        assert_eq!(stats.top_event_ids[0].count, 3);
+        // 22:00, 23:00, 00:00 (empty) and 01:00.
+        assert_eq!(stats.histogram.len(), 4);
+        assert_eq!(stats.histogram[0].levels[2], 1);
+        assert_eq!(stats.histogram[0].levels[4], 1);
+        assert_eq!(stats.histogram[1].total(), 0);
+        assert_eq!(stats.histogram[3].total(), 1);
+        // 2023-11-14 is a Tuesday; the last event falls on Wednesday 01:00.
+        assert_eq!(stats.week_hours[1][22], 2);
+        assert_eq!(stats.week_hours[2][1], 1);
+        assert_eq!(week_slot(events[2].timestamp, &Utc), (2, 1));
+
+        let daily = compute(&events, &[0, 1, 2], Granularity::Day, &Utc);
+        assert_eq!(daily.histogram.len(), 2);
+        let (from, to) = bucket_range(daily.histogram[0].start, Granularity::Day, &Utc).unwrap();
+        assert_eq!((to - from).num_hours(), 24);
+        assert!(from <= events[0].timestamp && events[0].timestamp < to);
+    }
+
+    #[test]
+    fn histogram_keeps_most_recent_buckets() {
+        let events: Vec<EventRecord> = (0..500).map(|m| event(4, m * 60, "WS07", None)).collect();
+        let indices: Vec<usize> = (0..events.len()).collect();
+        let stats = compute(&events, &indices, Granularity::Minute, &Utc);
+        assert_eq!(stats.histogram.len(), Granularity::Minute.max_buckets());
+        let last = events[499].timestamp.naive_utc();
+        assert_eq!(
+            stats.histogram.last().unwrap().start,
+            Granularity::Minute.floor(last)
+        );
+    }
+}
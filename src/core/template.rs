@@ -0,0 +1,178 @@
+//! Drain-style message template mining.
+//!
+//! Each message is split into whitespace tokens; tokens containing a digit
+//! are masked as variables up front. Messages are routed by token count
+//! and first token to a small list of candidate templates, and join the
+//! most similar one when at least [`SIMILARITY_THRESHOLD`] of the
+//! positions agree; positions that differ become `<*>` slots. Otherwise a
+//! new template is started. The miner is incremental, so live-tail
+//! batches only cost their own size.
+
+use std::collections::HashMap;
+
+use crate::core::event_record::EventRecord;
+use crate::util::constants::{MAX_TEMPLATES, MAX_TEMPLATE_TOKENS};
+
+/// A variable slot in a template.
+pub const WILDCARD: &str = "<*>";
+
+/// Fraction of positions that must match for a message to join a template.
+const SIMILARITY_THRESHOLD: f64 = 0.5;
+
+/// One mined template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    pub tokens: Vec<String>,
+    /// Events assigned to this template.
+    pub count: usize,
+}
+
+impl Template {
+    /// The template as text, slots shown as `<*>`.
+    pub fn text(&self) -> String {
+        self.tokens.join(" ")
+    }
+}
+
+/// Incremental template miner over an event list.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateMiner {
+    pub templates: Vec<Template>,
+    /// Template ID of each event fed so far (index = event index).
+    pub assignments: Vec<usize>,
+    /// (token count, first token) → candidate template IDs.
+    tree: HashMap<(usize, String), Vec<usize>>,
+    /// Catch-all template used once [`MAX_TEMPLATES`] is reached.
+    overflow: Option<usize>,
+}
+
+fn tokenize(message: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = message
+        .split_whitespace()
+        .take(MAX_TEMPLATE_TOKENS + 1)
+        .map(|t| {
+            if t.bytes().any(|b| b.is_ascii_digit()) {
+                WILDCARD.to_owned()
+            } else {
+                t.to_owned()
+            }
+        })
+        .collect();
+    if tokens.len() > MAX_TEMPLATE_TOKENS {
+        // The tail is folded into a single trailing slot.
+        tokens.truncate(MAX_TEMPLATE_TOKENS);
+        tokens.push(WILDCARD.to_owned());
+    }
+    tokens
+}
+
+/// Fraction of positions where `template` has exactly `tokens`' token.
+fn similarity(template: &[String], tokens: &[String]) -> f64 {
+    if tokens.is_empty() {
+        return 1.0;
+    }
+    let same = template.iter().zip(tokens).filter(|(a, b)| a == b).count();
+    same as f64 / tokens.len() as f64
+}
+
+impl TemplateMiner {
+    /// Assign `message` to a template and return the template ID.
+    pub fn add(&mut self, message: &str) -> usize {
+        let tokens = tokenize(message);
+        let key = (tokens.len(), tokens.first().cloned().unwrap_or_default());
+        let candidates = self.tree.get(&key).map(Vec::as_slice).unwrap_or_default();
+        let best = candidates
+            .iter()
+            .map(|&id| (id, similarity(&self.templates[id].tokens, &tokens)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        let at_cap = self.templates.len() >= MAX_TEMPLATES;
+
+        let id = match best {
+            Some((id, sim)) if sim >= SIMILARITY_THRESHOLD || at_cap => {
+                for (slot, token) in self.templates[id].tokens.iter_mut().zip(&tokens) {
+                    if slot != token {
+                        *slot = WILDCARD.to_owned();
+                    }
+                }
+                id
+            }
+            _ if at_cap => *self.overflow.get_or_insert_with(|| {
+                self.templates.push(Template {
+                    tokens: vec![WILDCARD.to_owned()],
+                    count: 0,
+                });
+                self.templates.len() - 1
+            }),
+            _ => {
+                self.templates.push(Template { tokens, count: 0 });
+                let id = self.templates.len() - 1;
+                self.tree.entry(key).or_default().push(id);
+                id
+            }
+        };
+        self.templates[id].count += 1;
+        self.assignments.push(id);
+        id
+    }
+
+    /// Feed every event of `events` not seen yet.
+    pub fn extend(&mut self, events: &[EventRecord]) {
+        for e in events.iter().skip(self.assignments.len()) {
+            self.add(e.display_message());
+        }
+    }
+
+    /// Template ID of event `idx`, if it has been fed.
+    pub fn template_of(&self, idx: usize) -> Option<usize> {
+        self.assignments.get(idx).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_positions_become_slots() {
+        let mut miner = TemplateMiner::default();
+        let a = miner.add("Service Foo entered the running state.");
+        let b = miner.add("Service Bar entered the stopped state.");
+        let c = miner.add("Connection from 10.0.0.5 port 4431 closed");
+        let d = miner.add("Connection from 10.0.0.9 port 51000 closed");
+        assert_eq!(a, b);
+        assert_eq!(c, d);
+        assert_ne!(a, c);
+        assert_eq!(
+            miner.templates[a].text(),
+            "Service <*> entered the <*> state."
+        );
+        assert_eq!(
+            miner.templates[c].text(),
+            "Connection from <*> port <*> closed"
+        );
+        assert_eq!(miner.templates[a].count, 2);
+        assert_eq!(miner.assignments, vec![a, a, c, c]);
+    }
+
+    #[test]
+    fn dissimilar_messages_stay_apart() {
+        let mut miner = TemplateMiner::default();
+        let a = miner.add("The user profile could not be loaded");
+        let b = miner.add("The system clock was changed by policy");
+        let c = miner.add("Short");
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(miner.templates.len(), 3);
+    }
+
+    #[test]
+    fn long_messages_fold_their_tail() {
+        let long: String = (0..MAX_TEMPLATE_TOKENS + 10)
+            .map(|i| format!("w{}", (b'a' + (i % 26) as u8) as char))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let tokens = tokenize(&long);
+        assert_eq!(tokens.len(), MAX_TEMPLATE_TOKENS + 1);
+        assert_eq!(tokens.last().map(String::as_str), Some(WILDCARD));
+    }
+}
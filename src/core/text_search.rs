@@ -0,0 +1,169 @@
+//! Text-search primitives used by [`super::filter::FilterState`].
+//!
+//! Holds the [`SearchScope`] selector plus the zero-allocation substring
+//! and whole-word matchers shared by the case-sensitive and
+//! case-insensitive search paths. Split out from `filter.rs` to keep
+//! individual module sizes manageable.
+
+/// Which parts of an event the free-form text search looks at.
+///
+/// The default [`SearchScope::AllFields`] reproduces the original
+/// behaviour (message, provider, channel, EventData keys and values, then
+/// raw XML). The narrower scopes exist because XML markup such as
+/// `System` or `Data` matches almost every event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum SearchScope {
+    /// Message, provider, channel, EventData keys/values, and raw XML.
+    #[default]
+    AllFields,
+    /// The formatted message only.
+    MessageOnly,
+    /// EventData / UserData values (keys are ignored).
+    EventDataValues,
+    /// Values of the EventData keys listed in `FilterState::search_keys`.
+    SpecificKeys,
+    /// The raw event XML only.
+    Xml,
+}
+
+impl SearchScope {
+    /// Every scope, in the order shown in the filter panel selector.
+    pub const ALL: [SearchScope; 5] = [
+        SearchScope::AllFields,
+        SearchScope::MessageOnly,
+        SearchScope::EventDataValues,
+        SearchScope::SpecificKeys,
+        SearchScope::Xml,
+    ];
+
+    /// Short display label for the scope selector.
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchScope::AllFields => "All fields",
+            SearchScope::MessageOnly => "Message only",
+            SearchScope::EventDataValues => "EventData values",
+            SearchScope::SpecificKeys => "Specific keys",
+            SearchScope::Xml => "Raw XML",
+        }
+    }
+}
+
+/// Returns `true` for characters that form part of a "word" when
+/// whole-word matching is enabled (letters, digits, underscore).
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns `true` if the byte range `start..end` of `haystack` is not
+/// directly preceded or followed by a word character.
+///
+/// Both offsets must lie on `char` boundaries.
+fn is_word_bounded(haystack: &str, start: usize, end: usize) -> bool {
+    let before_ok = haystack[..start]
+        .chars()
+        .next_back()
+        .is_none_or(|c| !is_word_char(c));
+    let after_ok = haystack[end..]
+        .chars()
+        .next()
+        .is_none_or(|c| !is_word_char(c));
+    before_ok && after_ok
+}
+
+/// Case-insensitive substring search without heap allocation for ASCII content.
+///
+/// Assumes `needle_lower` is already fully lowercased. Uses a fast byte-level
+/// comparison for ASCII-only haystacks (typical of Windows Event Log data),
+/// falling back to `to_lowercase().contains()` only when non-ASCII is detected.
+pub(crate) fn contains_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
+    if needle_lower.is_empty() {
+        return true;
+    }
+    let n = needle_lower.as_bytes();
+    let h = haystack.as_bytes();
+    if n.len() > h.len() {
+        return false;
+    }
+    // Fast path: byte-level ASCII comparison (zero-alloc, covers ~99% of event log data)
+    let found = h
+        .windows(n.len())
+        .any(|w| w.iter().zip(n).all(|(a, b)| a.to_ascii_lowercase() == *b));
+    if found {
+        return true;
+    }
+    // Slow path: full Unicode lowering only if haystack contains non-ASCII
+    if !haystack.is_ascii() {
+        return haystack.to_lowercase().contains(needle_lower);
+    }
+    false
+}
+
+/// Case-sensitive whole-word search: `needle` must appear in `haystack`
+/// with no word character immediately before or after it.
+pub(crate) fn contains_word(haystack: &str, needle: &str) -> bool {
+    if needle.is_empty() {
+        return true;
+    }
+    haystack
+        .match_indices(needle)
+        .any(|(start, m)| is_word_bounded(haystack, start, start + m.len()))
+}
+
+/// Case-insensitive whole-word search. Same contract as
+/// [`contains_case_insensitive`] (`needle_lower` must be lowercased) with
+/// the added word-boundary check from [`contains_word`].
+pub(crate) fn contains_word_case_insensitive(haystack: &str, needle_lower: &str) -> bool {
+    if needle_lower.is_empty() {
+        return true;
+    }
+    let n = needle_lower.as_bytes();
+    let h = haystack.as_bytes();
+    if n.len() > h.len() {
+        return false;
+    }
+    // Fast path: byte-level comparison. A matching window always starts and
+    // ends on a char boundary because the needle is valid UTF-8.
+    let found = (0..=h.len() - n.len()).any(|start| {
+        h[start..start + n.len()]
+            .iter()
+            .zip(n)
+            .all(|(a, b)| a.to_ascii_lowercase() == *b)
+            && is_word_bounded(haystack, start, start + n.len())
+    });
+    if found {
+        return true;
+    }
+    if !haystack.is_ascii() {
+        return contains_word(&haystack.to_lowercase(), needle_lower);
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_word_respects_boundaries() {
+        assert!(contains_word("the Data value", "Data"));
+        assert!(contains_word("<Data Name=\"x\">", "Data"));
+        assert!(!contains_word("EventData", "Data"));
+        assert!(!contains_word("Database", "Data"));
+    }
+
+    #[test]
+    fn test_contains_word_case_insensitive() {
+        assert!(contains_word_case_insensitive(
+            "Logon FAILED for user",
+            "failed"
+        ));
+        assert!(!contains_word_case_insensitive("FailedLogon", "failed"));
+        assert!(contains_word_case_insensitive("ÄRGER failed", "failed"));
+    }
+
+    #[test]
+    fn test_contains_word_later_occurrence() {
+        // First occurrence is inside a word; the second one is standalone.
+        assert!(contains_word("SystemTime System", "System"));
+    }
+}
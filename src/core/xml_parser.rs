@@ -126,10 +126,14 @@ pub fn parse_event_xml(
         })
         .unwrap_or(0);
 
-    // Correlation Activity ID
-    let activity_id = find_child(&system, "Correlation")
+    // Correlation Activity ID and the activity that spawned it
+    let correlation = find_child(&system, "Correlation");
+    let activity_id = correlation
         .and_then(|e| e.attribute("ActivityID"))
         .map(String::from);
+    let related_activity_id = correlation
+        .and_then(|e| e.attribute("RelatedActivityID"))
+        .map(String::from);
 
     // User SID
     let user_sid = find_child(&system, "Security")
@@ -177,6 +181,7 @@ pub fn parse_event_xml(
         opcode,
         keywords,
         activity_id,
+        related_activity_id,
         user_sid,
         event_data,
     })
@@ -324,6 +329,18 @@ mod tests {
             ("ProgramName".into(), "explorer.exe".into())
         );
         assert_eq!(record.user_sid, Some("S-1-5-21-123".into()));
+        assert_eq!(record.activity_id, None);
+    }
+
+    #[test]
+    fn test_parse_correlation_ids() {
+        let xml = SAMPLE_XML.replace(
+            "<Computer>",
+            r#"<Correlation ActivityID="{AAAA}" RelatedActivityID="{BBBB}" /><Computer>"#,
+        );
+        let record = parse_event_xml(&xml, "Application", None).unwrap();
+        assert_eq!(record.activity_id.as_deref(), Some("{AAAA}"));
+        assert_eq!(record.related_activity_id.as_deref(), Some("{BBBB}"));
     }
 
     #[test]
@@ -41,7 +41,9 @@ pub fn validate_export_path(path: &Path) -> Result<(), EventSleuthError> {
 
 /// Export the given events to a CSV file at `path`.
 ///
-/// Columns: Timestamp, Level, EventID, Provider, Computer, Channel, Message.
+/// Columns: Timestamp, Level, EventID, Provider, Computer, Channel, Message,
+/// ATT&CK. `attack[i]` holds the technique IDs tagged on `events[i]`
+/// (joined with `;`); a shorter slice leaves the remaining cells empty.
 ///
 /// # Pre-flight (Rule 17)
 /// Validates that the target directory exists and is writable before writing.
@@ -49,7 +51,11 @@ pub fn validate_export_path(path: &Path) -> Result<(), EventSleuthError> {
 /// # Errors
 /// Returns [`EventSleuthError::Export`] if validation fails or the file
 /// cannot be created or written.
-pub fn export_csv(events: &[EventRecord], path: &Path) -> Result<(), EventSleuthError> {
+pub fn export_csv(
+    events: &[EventRecord],
+    attack: &[Vec<String>],
+    path: &Path,
+) -> Result<(), EventSleuthError> {
     validate_export_path(path)?;
     let mut writer = csv::Writer::from_path(path)
         .map_err(|e| EventSleuthError::Export(format!("Failed to create CSV file: {e}")))?;
@@ -64,11 +70,13 @@ pub fn export_csv(events: &[EventRecord], path: &Path) -> Result<(), EventSleuth
             "Computer",
             "Channel",
             "Message",
+            "ATT&CK",
         ])
         .map_err(|e| EventSleuthError::Export(format!("Failed to write CSV header: {e}")))?;
 
     // Write each event as a row
-    for event in events {
+    for (i, event) in events.iter().enumerate() {
+        let techniques = attack.get(i).map(|t| t.join(";")).unwrap_or_default();
         writer
             .write_record([
                 &format_table_timestamp(&event.timestamp),
@@ -78,6 +86,7 @@ pub fn export_csv(events: &[EventRecord], path: &Path) -> Result<(), EventSleuth
                 &event.computer,
                 &event.channel,
                 event.display_message(),
+                &techniques,
             ])
             .map_err(|e| EventSleuthError::Export(format!("Failed to write CSV row: {e}")))?;
     }
@@ -93,3 +102,65 @@ pub fn export_csv(events: &[EventRecord], path: &Path) -> Result<(), EventSleuth
     );
     Ok(())
 }
+
+/// Export a pivot table (as produced by
+/// [`CrossTab::to_records`](crate::core::crosstab::CrossTab::to_records))
+/// to a CSV file at `path`.
+///
+/// # Errors
+/// Returns [`EventSleuthError::Export`] if validation fails or the file
+/// cannot be created or written.
+pub fn export_crosstab(records: &[Vec<String>], path: &Path) -> Result<(), EventSleuthError> {
+    validate_export_path(path)?;
+    let mut writer = csv::Writer::from_path(path)
+        .map_err(|e| EventSleuthError::Export(format!("Failed to create CSV file: {e}")))?;
+    for record in records {
+        writer
+            .write_record(record)
+            .map_err(|e| EventSleuthError::Export(format!("Failed to write CSV row: {e}")))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| EventSleuthError::Export(format!("Failed to flush CSV: {e}")))?;
+
+    tracing::info!("Exported pivot table to CSV: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_csv_writes_attack_column() {
+        let events = vec![
+            EventRecord {
+                event_id: 4688,
+                message: "Process created".into(),
+                ..EventRecord::test_base()
+            },
+            EventRecord {
+                event_id: 7036,
+                ..EventRecord::test_base()
+            },
+        ];
+        let attack = vec![vec!["T1059.001".to_string(), "T1027".to_string()]];
+        let path =
+            std::env::temp_dir().join(format!("eventsleuth_csv_export_{}.csv", std::process::id()));
+        export_csv(&events, &attack, &path).unwrap();
+
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let header = reader.headers().unwrap().clone();
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(header.len(), 8);
+        assert_eq!(&header[7], "ATT&CK");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][2], "4688");
+        assert_eq!(&rows[0][6], "Process created");
+        assert_eq!(&rows[0][7], "T1059.001;T1027");
+        // No entry in `attack` for the second event: empty cell.
+        assert_eq!(&rows[1][7], "");
+    }
+}
@@ -7,9 +7,20 @@ use crate::core::event_record::EventRecord;
 use crate::util::error::EventSleuthError;
 use std::path::Path;
 
+/// An exported event with its ATT&CK techniques flattened alongside.
+#[derive(serde::Serialize)]
+struct TaggedEvent<'a> {
+    #[serde(flatten)]
+    event: &'a EventRecord,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    attack: &'a [String],
+}
+
 /// Export the given events to a JSON file at `path`.
 ///
-/// Output is a pretty-printed JSON array of [`EventRecord`] objects.
+/// Output is a pretty-printed JSON array of [`EventRecord`] objects. Events
+/// with ATT&CK techniques (`attack[i]` for `events[i]`) carry them in an
+/// extra `attack` array.
 ///
 /// # Pre-flight (Rule 17)
 /// Validates that the target directory exists and is writable before writing.
@@ -17,14 +28,26 @@ use std::path::Path;
 /// # Errors
 /// Returns [`EventSleuthError::Export`] if validation fails or the file
 /// cannot be created or written.
-pub fn export_json(events: &[EventRecord], path: &Path) -> Result<(), EventSleuthError> {
+pub fn export_json(
+    events: &[EventRecord],
+    attack: &[Vec<String>],
+    path: &Path,
+) -> Result<(), EventSleuthError> {
     // Re-use the same validation logic as CSV export
     crate::export::csv_export::validate_export_path(path)?;
     let file = std::fs::File::create(path)
         .map_err(|e| EventSleuthError::Export(format!("Failed to create JSON file: {e}")))?;
 
     let mut writer = std::io::BufWriter::new(file);
-    serde_json::to_writer_pretty(&mut writer, events)
+    let tagged: Vec<TaggedEvent> = events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| TaggedEvent {
+            event,
+            attack: attack.get(i).map_or(&[], Vec::as_slice),
+        })
+        .collect();
+    serde_json::to_writer_pretty(&mut writer, &tagged)
         .map_err(|e| EventSleuthError::Export(format!("Failed to write JSON: {e}")))?;
 
     // Explicit flush so I/O errors are not silently swallowed by BufWriter::drop.
@@ -40,3 +63,39 @@ pub fn export_json(events: &[EventRecord], path: &Path) -> Result<(), EventSleut
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_json_adds_attack_field_only_when_tagged() {
+        let events = vec![
+            EventRecord {
+                event_id: 4688,
+                ..EventRecord::test_base()
+            },
+            EventRecord {
+                event_id: 7036,
+                ..EventRecord::test_base()
+            },
+        ];
+        let attack = vec![vec!["T1059.001".to_string()], Vec::new()];
+        let path = std::env::temp_dir().join(format!(
+            "eventsleuth_json_export_{}.json",
+            std::process::id()
+        ));
+        export_json(&events, &attack, &path).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let rows = value.as_array().unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["event_id"], 4688);
+        assert_eq!(rows[0]["attack"], serde_json::json!(["T1059.001"]));
+        assert!(rows[1].get("attack").is_none());
+        assert_eq!(rows[1]["event_id"], 7036);
+    }
+}
@@ -0,0 +1,198 @@
+//! Activity chains window: events grouped by ActivityID correlation.
+//!
+//! One collapsible group per chain, with its time span, duration and the
+//! providers involved; expanding it lists the member events. Each group
+//! links back to its events in the main table.
+
+use crate::app::EventSleuthApp;
+use crate::core::activity::{self, ActivityChain};
+use crate::ui::theme;
+use crate::util::time;
+
+/// Chains rendered at most; the search narrows the rest down.
+const MAX_CHAINS_SHOWN: usize = 500;
+
+/// Member events listed per expanded chain.
+const MAX_CHAIN_EVENTS_SHOWN: usize = 200;
+
+/// Cached chains and the window's view options.
+#[derive(Debug, Default)]
+pub struct ActivityView {
+    /// Chains of two or more events, newest first.
+    pub chains: Vec<ActivityChain>,
+    /// `events_generation` the chains were built from.
+    pub built_for: Option<u64>,
+    /// Case-insensitive substring over activity IDs, providers and
+    /// channels.
+    pub search: String,
+    /// Hide chains with fewer events than this.
+    pub min_events: usize,
+}
+
+impl ActivityView {
+    fn shows(&self, c: &ActivityChain, needle: &str) -> bool {
+        c.events.len() >= self.min_events
+            && (needle.is_empty()
+                || c.activity_ids
+                    .iter()
+                    .chain(&c.providers)
+                    .chain(&c.channels)
+                    .any(|v| v.to_lowercase().contains(needle)))
+    }
+}
+
+impl EventSleuthApp {
+    /// Render the floating activity chains window.
+    ///
+    /// Visibility is controlled by `self.show_activity`.
+    pub fn render_activity_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_activity {
+            return;
+        }
+        if self.activity_view.built_for != Some(self.events_generation) {
+            self.activity_view.chains = activity::group(&self.all_events, 2);
+            self.activity_view.built_for = Some(self.events_generation);
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F517} Activity Chains")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(720.0)
+            .default_height(max_h.min(460.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.activity_view;
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut view.search)
+                            .hint_text("Activity ID, provider or channel")
+                            .desired_width(240.0),
+                    );
+                    ui.label("Min events");
+                    ui.add(egui::DragValue::new(&mut view.min_events).range(2..=1000));
+                });
+
+                let needle = view.search.trim().to_lowercase();
+                let rows: Vec<usize> = (0..view.chains.len())
+                    .filter(|&i| view.shows(&view.chains[i], &needle))
+                    .collect();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} of {} chains shown (events sharing an ActivityID, linked through \
+                         RelatedActivityID)",
+                        rows.len(),
+                        view.chains.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if view.chains.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No correlated activities in the loaded events")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let events = &self.all_events;
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for &i in rows.iter().take(MAX_CHAINS_SHOWN) {
+                            let c = &view.chains[i];
+                            let id = ui.make_persistent_id(("activity_chain", &c.activity_ids[0]));
+                            egui::collapsing_header::CollapsingState::load_with_default_open(
+                                ui.ctx(),
+                                id,
+                                false,
+                            )
+                            .show_header(ui, |ui| {
+                                ui.label(
+                                    egui::RichText::new(time::format_table_timestamp(&c.start))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                                ui.label(
+                                    egui::RichText::new(time::format_span(c.duration()))
+                                        .color(theme::accent(dark)),
+                                );
+                                ui.label(
+                                    egui::RichText::new(c.providers.join(", "))
+                                        .color(theme::text_primary(dark)),
+                                )
+                                .on_hover_text(format!(
+                                    "Activity IDs:\n{}\n\nChannels:\n{}",
+                                    c.activity_ids.join("\n"),
+                                    c.channels.join("\n")
+                                ));
+                                if ui
+                                    .small_button(format!("{} \u{2192}", c.events.len()))
+                                    .on_hover_text("Show this chain's events in the table")
+                                    .clicked()
+                                {
+                                    filter_to = Some((
+                                        format!("Activity {}", c.activity_ids[0]),
+                                        c.events.clone(),
+                                    ));
+                                }
+                            })
+                            .body(|ui| {
+                                for &e in c.events.iter().take(MAX_CHAIN_EVENTS_SHOWN) {
+                                    let ev = &events[e];
+                                    ui.horizontal(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(time::format_table_timestamp(
+                                                &ev.timestamp,
+                                            ))
+                                            .color(theme::text_secondary(dark))
+                                            .small(),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(ev.event_id.to_string())
+                                                .color(theme::level_color(ev.level, dark)),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(&ev.provider_name)
+                                                .color(theme::text_primary(dark)),
+                                        );
+                                        ui.add(
+                                            egui::Label::new(
+                                                egui::RichText::new(ev.display_message())
+                                                    .color(theme::text_dim(dark)),
+                                            )
+                                            .truncate(),
+                                        );
+                                    });
+                                }
+                                if c.events.len() > MAX_CHAIN_EVENTS_SHOWN {
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "\u{2026} {} more",
+                                            c.events.len() - MAX_CHAIN_EVENTS_SHOWN
+                                        ))
+                                        .color(theme::text_dim(dark))
+                                        .italics(),
+                                    );
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_activity = false;
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Live-tail alerts: rule editor window and the in-app alert banner.
+//!
+//! Each rule watches a saved filter preset and fires on any new match or
+//! when a count is exceeded within a time window (see
+//! [`crate::core::alert`]).
+
+use crate::app::EventSleuthApp;
+use crate::core::alert::{AlertRule, AlertTrigger};
+use crate::ui::theme;
+use crate::util::time;
+
+impl EventSleuthApp {
+    /// Banner above the event table announcing alerts fired since it was
+    /// last dismissed.
+    pub fn render_alert_banner(&mut self, ui: &mut egui::Ui) {
+        if self.unseen_alerts == 0 {
+            return;
+        }
+        let Some(latest) = self.fired_alerts.last() else {
+            return;
+        };
+        let dark = self.dark_mode;
+        let mut dismiss = false;
+        let mut open = false;
+        egui::Frame::new()
+            .fill(theme::security_banner_bg(dark))
+            .inner_margin(egui::Margin::same(6))
+            .corner_radius(4.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("\u{1F514} {}", latest.rule))
+                            .color(theme::level_color(2, dark))
+                            .strong(),
+                    );
+                    ui.label(
+                        egui::RichText::new(&latest.message).color(theme::text_secondary(dark)),
+                    );
+                    if self.unseen_alerts > 1 {
+                        ui.label(
+                            egui::RichText::new(format!("(+{} more)", self.unseen_alerts - 1))
+                                .color(theme::text_dim(dark))
+                                .small(),
+                        );
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("\u{2716}")
+                            .on_hover_text("Dismiss")
+                            .clicked()
+                        {
+                            dismiss = true;
+                        }
+                        if ui.small_button("View").clicked() {
+                            open = true;
+                            dismiss = true;
+                        }
+                    });
+                });
+            });
+        ui.add_space(4.0);
+        if open {
+            self.show_alerts = true;
+        }
+        if dismiss {
+            self.unseen_alerts = 0;
+        }
+    }
+
+    /// Render the floating alerts window (rules + recent alerts).
+    ///
+    /// Visibility is controlled by `self.show_alerts`.
+    pub fn render_alerts_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_alerts {
+            return;
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut rules_changed = false;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F514} Alerts")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(460.0)
+            .default_height(max_h.min(480.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                if !self.live_tail {
+                    ui.label(
+                        egui::RichText::new(
+                            "Alert rules are checked against new events while Live Tail is on.",
+                        )
+                        .color(theme::text_dim(dark))
+                        .italics()
+                        .small(),
+                    );
+                    ui.add_space(theme::ITEM_SPACING);
+                }
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        // ── Rules ───────────────────────────────────
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(format!("Rules ({})", self.alert_rules.len()))
+                                .strong(),
+                        )
+                        .id_salt("alert_rules")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            rules_changed |= self.render_alert_rule_list(ui);
+                        });
+
+                        ui.add_space(theme::SECTION_SPACING);
+
+                        // ── Recent alerts ───────────────────────────
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(format!(
+                                "Recent alerts ({})",
+                                self.fired_alerts.len()
+                            ))
+                            .strong(),
+                        )
+                        .id_salt("alert_recent")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if self.fired_alerts.is_empty() {
+                                ui.label(
+                                    egui::RichText::new("No alerts yet")
+                                        .color(theme::text_dim(dark))
+                                        .italics(),
+                                );
+                            }
+                            for alert in self.fired_alerts.iter().rev() {
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(time::format_table_timestamp(
+                                            &alert.fired_at,
+                                        ))
+                                        .color(theme::text_dim(dark))
+                                        .monospace()
+                                        .small(),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(&alert.rule)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(&alert.message)
+                                            .color(theme::text_secondary(dark)),
+                                    );
+                                })
+                                .response
+                                .on_hover_text(format!(
+                                    "Event {} from {} on {} at {}",
+                                    alert.event.event_id,
+                                    alert.event.provider_name,
+                                    alert.event.computer,
+                                    time::format_detail_timestamp(&alert.event.timestamp),
+                                ));
+                            }
+                        });
+
+                        if let Some(path) = &self.alert_log_path {
+                            ui.add_space(theme::ITEM_SPACING);
+                            ui.label(
+                                egui::RichText::new(format!("Alert log: {}", path.display()))
+                                    .color(theme::text_dim(dark))
+                                    .small(),
+                            );
+                        }
+                    });
+            });
+
+        if rules_changed {
+            // Thresholds may have changed meaning; start counting afresh.
+            self.alert_monitor.reset();
+        }
+        if !open {
+            self.show_alerts = false;
+        }
+    }
+
+    /// Editable list of alert rules. Returns `true` if anything changed.
+    fn render_alert_rule_list(&mut self, ui: &mut egui::Ui) -> bool {
+        let dark = self.dark_mode;
+        let mut changed = false;
+        let mut remove: Option<usize> = None;
+        let preset_names: Vec<String> =
+            self.filter_presets.iter().map(|p| p.name.clone()).collect();
+
+        for (i, rule) in self.alert_rules.iter_mut().enumerate() {
+            egui::Frame::group(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .checkbox(&mut rule.enabled, "")
+                        .on_hover_text("Enable or disable this rule")
+                        .changed();
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut rule.name)
+                                .hint_text("Rule name")
+                                .desired_width(160.0),
+                        )
+                        .changed();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("\u{1F5D1}")
+                            .on_hover_text("Delete rule")
+                            .clicked()
+                        {
+                            remove = Some(i);
+                        }
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Preset");
+                    let missing = !preset_names.contains(&rule.preset);
+                    let shown = if missing {
+                        egui::RichText::new(format!("{} (missing)", rule.preset))
+                            .color(theme::level_color(2, dark))
+                    } else {
+                        egui::RichText::new(&rule.preset)
+                    };
+                    egui::ComboBox::from_id_salt(("alert_preset", i))
+                        .selected_text(shown)
+                        .width(180.0)
+                        .show_ui(ui, |ui| {
+                            for name in &preset_names {
+                                changed |= ui
+                                    .selectable_value(&mut rule.preset, name.clone(), name)
+                                    .changed();
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    let mut threshold = matches!(rule.trigger, AlertTrigger::Threshold { .. });
+                    if ui
+                        .radio_value(&mut threshold, false, "Any match")
+                        .on_hover_text("Fire whenever a new event matches the preset")
+                        .changed()
+                        | ui.radio_value(&mut threshold, true, "More than")
+                            .on_hover_text("Fire when the count is exceeded within the window")
+                            .changed()
+                    {
+                        rule.trigger = if threshold {
+                            AlertTrigger::Threshold {
+                                count: 10,
+                                window_minutes: 5,
+                            }
+                        } else {
+                            AlertTrigger::AnyMatch
+                        };
+                        changed = true;
+                    }
+                    if let AlertTrigger::Threshold {
+                        count,
+                        window_minutes,
+                    } = &mut rule.trigger
+                    {
+                        changed |= ui
+                            .add(egui::DragValue::new(count).range(1..=1_000_000))
+                            .changed();
+                        ui.label("events in");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(window_minutes)
+                                    .range(1..=24 * 60)
+                                    .suffix(" min"),
+                            )
+                            .changed();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Run");
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut rule.command)
+                                .hint_text("optional command (event JSON on stdin)")
+                                .desired_width(f32::INFINITY),
+                        )
+                        .changed();
+                });
+            });
+            ui.add_space(2.0);
+        }
+
+        if let Some(i) = remove {
+            self.alert_rules.remove(i);
+            changed = true;
+        }
+
+        let can_add = !preset_names.is_empty();
+        if ui
+            .add_enabled(can_add, egui::Button::new("\u{2795} Add rule"))
+            .on_disabled_hover_text(
+                "Save a filter preset first; rules match events against a preset",
+            )
+            .clicked()
+        {
+            let name = format!("Alert {}", self.alert_rules.len() + 1);
+            self.alert_rules
+                .push(AlertRule::new(&name, &preset_names[0]));
+            changed = true;
+        }
+        changed
+    }
+}
@@ -0,0 +1,367 @@
+//! Anomalies window: volume spikes / drops and rare events.
+//!
+//! Two tables over the loaded window: time buckets whose volume deviates
+//! from the per-series baseline, and (provider, event ID) pairs that are
+//! rare or appear for the first time late in the window. Every row
+//! narrows the event table to the events behind it. The baselines are
+//! built on a background thread once per event-list change; the threshold
+//! only filters them.
+
+use std::sync::Arc;
+
+use crossbeam_channel::Receiver;
+
+use crate::app::EventSleuthApp;
+use crate::core::anomaly::{self, AnomalyReport, RateAnomaly, Series};
+use crate::ui::theme;
+use crate::util::time;
+use egui_extras::{Column, TableBuilder};
+
+/// Default deviation threshold, in standard deviations.
+pub const DEFAULT_THRESHOLD: f64 = 3.0;
+
+/// Cached report and the window's view options.
+#[derive(Debug)]
+pub struct AnomalyView {
+    pub report: AnomalyReport,
+    /// `events_generation` of the report, or of the running build.
+    pub built_for: Option<u64>,
+    /// Receives the result of the running build, if any.
+    rx: Option<Receiver<AnomalyReport>>,
+    /// Standard deviations a bucket must be away from its baseline.
+    pub threshold: f64,
+    /// Also list per-provider and per-event-ID series (not only the total).
+    pub per_series: bool,
+}
+
+impl Default for AnomalyView {
+    fn default() -> Self {
+        Self {
+            report: AnomalyReport::default(),
+            built_for: None,
+            rx: None,
+            threshold: DEFAULT_THRESHOLD,
+            per_series: false,
+        }
+    }
+}
+
+/// What a row click asked for.
+enum AnomalyAction {
+    /// These events.
+    Events(String, Vec<usize>),
+    /// Everything in a time bucket (used for drops, which have few or no
+    /// events of their own).
+    Bucket(String, chrono::DateTime<chrono::Utc>),
+}
+
+impl EventSleuthApp {
+    /// Collect a finished report and start a new one if the event list
+    /// changed.
+    fn refresh_anomalies(&mut self, ctx: &egui::Context) {
+        let view = &mut self.anomaly_view;
+        if let Some(rx) = &view.rx {
+            match rx.try_recv() {
+                Ok(report) => {
+                    view.report = report;
+                    view.rx = None;
+                }
+                Err(crossbeam_channel::TryRecvError::Disconnected) => view.rx = None,
+                Err(crossbeam_channel::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    return;
+                }
+            }
+        }
+        if view.built_for == Some(self.events_generation) {
+            return;
+        }
+        view.built_for = Some(self.events_generation);
+        let events = Arc::clone(&self.all_events);
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        view.rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(anomaly::analyse(&events));
+        });
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    /// Render the floating anomalies window.
+    ///
+    /// Visibility is controlled by `self.show_anomalies`.
+    pub fn render_anomaly_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_anomalies {
+            return;
+        }
+        self.refresh_anomalies(ctx);
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut action: Option<AnomalyAction> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{26A1} Anomalies")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(760.0)
+            .default_height(max_h.min(520.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.anomaly_view;
+                ui.horizontal(|ui| {
+                    ui.label("Threshold");
+                    ui.add(
+                        egui::DragValue::new(&mut view.threshold)
+                            .range(anomaly::MIN_THRESHOLD..=10.0)
+                            .speed(0.1)
+                            .suffix(" \u{3C3}"),
+                    )
+                    .on_hover_text("Standard deviations from the bucket baseline");
+                    ui.checkbox(&mut view.per_series, "Per provider / event ID");
+                    if view.rx.is_some() {
+                        ui.spinner();
+                    }
+                });
+
+                let report = &view.report;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} buckets of {} over all loaded events",
+                        report.bucket_count,
+                        time::format_span(report.bucket)
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                let above = report.rate_above(view.threshold);
+                let rate: Vec<&RateAnomaly> = above
+                    .iter()
+                    .filter(|a| view.per_series || a.series == Series::All)
+                    .collect();
+                let rate_height = (ui.available_height() * 0.5).max(120.0);
+
+                ui.label(
+                    egui::RichText::new(format!("\u{1F4C8} Volume anomalies ({})", rate.len()))
+                        .color(theme::text_primary(dark))
+                        .strong(),
+                );
+                if rate.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            if report.bucket_count < anomaly::MIN_BASELINE_BUCKETS {
+                                "Time span too short for a baseline"
+                            } else {
+                                "No bucket deviates beyond the threshold"
+                            },
+                        )
+                        .color(theme::text_dim(dark))
+                        .italics(),
+                    );
+                } else {
+                    ui.push_id("anomaly_rate", |ui| {
+                        TableBuilder::new(ui)
+                            .striped(true)
+                            .resizable(true)
+                            .max_scroll_height(rate_height)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(Column::auto().at_least(135.0)) // Bucket
+                            .column(Column::auto().at_least(220.0).clip(true)) // Series
+                            .column(Column::auto().at_least(60.0)) // Count
+                            .column(Column::auto().at_least(90.0)) // Baseline
+                            .column(Column::remainder().at_least(60.0)) // Deviation
+                            .header(20.0, |mut header| {
+                                for title in ["Bucket", "Series", "Count", "Baseline", "\u{3C3}"] {
+                                    header.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(title)
+                                                .color(theme::text_primary(dark))
+                                                .strong(),
+                                        );
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(theme::TABLE_ROW_HEIGHT, rate.len(), |mut row| {
+                                    let a = rate[row.index()];
+                                    let when = time::format_table_timestamp(&a.bucket_start);
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&when)
+                                                .color(theme::text_secondary(dark))
+                                                .small(),
+                                        );
+                                    });
+                                    row.col(|ui| {
+                                        let label = a.series.label();
+                                        let clicked = ui
+                                            .add(
+                                                egui::Label::new(
+                                                    egui::RichText::new(&label)
+                                                        .color(theme::text_primary(dark)),
+                                                )
+                                                .sense(egui::Sense::click()),
+                                            )
+                                            .on_hover_text("Show the events behind this bucket")
+                                            .clicked();
+                                        if clicked {
+                                            action = Some(if a.z < 0.0 {
+                                                AnomalyAction::Bucket(
+                                                    format!("Drop: {label} @ {when}"),
+                                                    a.bucket_start,
+                                                )
+                                            } else {
+                                                AnomalyAction::Events(
+                                                    format!("Spike: {label} @ {when}"),
+                                                    a.events.clone(),
+                                                )
+                                            });
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(a.count.to_string());
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "{:.1} \u{B1} {:.1}",
+                                                a.mean, a.std_dev
+                                            ))
+                                            .color(theme::text_secondary(dark)),
+                                        );
+                                    });
+                                    row.col(|ui| {
+                                        let color = if a.z < 0.0 {
+                                            theme::accent(dark)
+                                        } else {
+                                            theme::level_color(3, dark)
+                                        };
+                                        ui.label(
+                                            egui::RichText::new(format!("{:+.1}", a.z))
+                                                .color(color),
+                                        );
+                                    });
+                                });
+                            });
+                    });
+                }
+
+                ui.add_space(theme::SECTION_SPACING);
+                ui.label(
+                    egui::RichText::new(format!(
+                        "\u{1F50E} Rare and first-seen events ({})",
+                        report.rare.len()
+                    ))
+                    .color(theme::text_primary(dark))
+                    .strong(),
+                );
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Provider / event ID pairs seen at most {} times, or first seen in the \
+                         second half of the window",
+                        anomaly::RARE_MAX_COUNT
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                if report.rare.is_empty() {
+                    ui.label(
+                        egui::RichText::new("None")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+                ui.push_id("anomaly_rare", |ui| {
+                    TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                        .column(Column::auto().at_least(220.0).clip(true)) // Provider
+                        .column(Column::auto().at_least(60.0)) // Event ID
+                        .column(Column::auto().at_least(60.0)) // Count
+                        .column(Column::auto().at_least(135.0)) // First seen
+                        .column(Column::remainder().at_least(60.0)) // Flag
+                        .header(20.0, |mut header| {
+                            for title in ["Provider", "Event ID", "Count", "First seen", ""] {
+                                header.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(title)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                });
+                            }
+                        })
+                        .body(|body| {
+                            body.rows(theme::TABLE_ROW_HEIGHT, report.rare.len(), |mut row| {
+                                let r = &report.rare[row.index()];
+                                row.col(|ui| {
+                                    let clicked = ui
+                                        .add(
+                                            egui::Label::new(
+                                                egui::RichText::new(&r.provider)
+                                                    .color(theme::text_primary(dark)),
+                                            )
+                                            .sense(egui::Sense::click()),
+                                        )
+                                        .on_hover_text("Show these events")
+                                        .clicked();
+                                    if clicked {
+                                        action = Some(AnomalyAction::Events(
+                                            format!("Rare: {} / {}", r.provider, r.event_id),
+                                            r.events.clone(),
+                                        ));
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(r.event_id.to_string());
+                                });
+                                row.col(|ui| {
+                                    ui.label(r.events.len().to_string());
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(time::format_table_timestamp(
+                                            &r.first_seen,
+                                        ))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    if r.first_seen_late {
+                                        ui.label(
+                                            egui::RichText::new("new")
+                                                .color(theme::level_color(3, dark)),
+                                        )
+                                        .on_hover_text(
+                                            "Not seen in the first half of the loaded window",
+                                        );
+                                    }
+                                });
+                            });
+                        });
+                });
+            });
+
+        match action {
+            Some(AnomalyAction::Events(label, indices)) => {
+                self.set_event_index_filter(label, &indices);
+            }
+            Some(AnomalyAction::Bucket(label, start)) => {
+                let end = self.anomaly_view.report.bucket_end(start);
+                let indices: Vec<usize> = (0..self.all_events.len())
+                    .filter(|&i| (start..end).contains(&self.all_events[i].timestamp))
+                    .collect();
+                self.set_event_index_filter(label, &indices);
+            }
+            None => {}
+        }
+        if !open {
+            self.show_anomalies = false;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! ATT&CK matrix window: tactics as columns, observed techniques as cells.
+//!
+//! Only techniques present in the loaded events are shown, each with the
+//! number of supporting events. Clicking a cell narrows the event table to
+//! those events.
+
+use crate::app::EventSleuthApp;
+use crate::core::attack::{self, UNMAPPED_TACTIC};
+use crate::ui::theme;
+
+/// Width of one tactic column.
+const COLUMN_WIDTH: f32 = 150.0;
+
+impl EventSleuthApp {
+    /// Render the floating ATT&CK matrix window.
+    ///
+    /// Visibility is controlled by `self.show_attack`.
+    pub fn render_attack_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_attack {
+            return;
+        }
+        self.refresh_attack_map();
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F5FA} ATT&CK Matrix")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(720.0)
+            .default_height(max_h.min(420.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let map = &self.attack_map;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} techniques across {} events (detection rule tags and known event IDs)",
+                        map.techniques.len(),
+                        map.tagged_event_count()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if map.techniques.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No ATT&CK techniques observed in the loaded events")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                // Kill-chain order, then anything a rule tagged outside it.
+                let columns: Vec<(&str, &str)> = attack::TACTICS
+                    .iter()
+                    .map(|t| (t.short, t.name))
+                    .chain(std::iter::once((UNMAPPED_TACTIC, "Other")))
+                    .filter(|(short, _)| map.tactics.contains_key(*short))
+                    .collect();
+
+                egui::ScrollArea::both()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        ui.horizontal_top(|ui| {
+                            for (short, name) in columns {
+                                let techniques = &map.tactics[short];
+                                ui.vertical(|ui| {
+                                    ui.set_width(COLUMN_WIDTH);
+                                    let events: usize = techniques
+                                        .iter()
+                                        .map(|t| map.techniques.get(t).map_or(0, Vec::len))
+                                        .sum();
+                                    ui.label(
+                                        egui::RichText::new(name)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{} techniques, {events} hits",
+                                            techniques.len()
+                                        ))
+                                        .color(theme::text_dim(dark))
+                                        .small(),
+                                    );
+                                    ui.add_space(theme::ITEM_SPACING);
+                                    for id in techniques {
+                                        let hits = map.techniques.get(id).map_or(&[][..], |v| v);
+                                        let title = attack::technique_name(id).unwrap_or("");
+                                        let text = egui::RichText::new(format!(
+                                            "{id} ({})\n{title}",
+                                            hits.len()
+                                        ))
+                                        .color(theme::text_primary(dark))
+                                        .small();
+                                        let cell = ui
+                                            .add_sized(
+                                                [COLUMN_WIDTH, 0.0],
+                                                egui::Button::new(text)
+                                                    .wrap()
+                                                    .fill(theme::filter_active_bg(dark)),
+                                            )
+                                            .on_hover_text(
+                                                "Show only the events tagged with this technique",
+                                            );
+                                        if cell.clicked() {
+                                            filter_to = Some((
+                                                format!("ATT&CK: {id} {title}")
+                                                    .trim_end()
+                                                    .to_owned(),
+                                                hits.to_vec(),
+                                            ));
+                                        }
+                                    }
+                                });
+                                ui.separator();
+                            }
+                        });
+                    });
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_attack = false;
+        }
+    }
+}
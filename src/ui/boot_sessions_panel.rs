@@ -0,0 +1,234 @@
+//! Boot sessions window: the System log split into boot-to-shutdown spans.
+//!
+//! One row per session (boot, shutdown, uptime, clean / dirty, planned
+//! shutdown initiator and reason, bugcheck codes) with a "dirty only"
+//! toggle. Each row can narrow the event table to everything its computer
+//! logged during that session.
+
+use crate::app::EventSleuthApp;
+use crate::core::boot_session::{self, BootSession, ShutdownKind};
+use crate::ui::theme;
+use crate::util::time;
+use egui_extras::{Column, TableBuilder};
+
+/// Cached sessions and the window's view options.
+#[derive(Debug, Default)]
+pub struct BootSessionView {
+    /// Segmented sessions, newest first.
+    pub sessions: Vec<BootSession>,
+    /// `events_generation` the sessions were built from.
+    pub built_for: Option<u64>,
+    /// Show only sessions that ended dirty.
+    pub dirty_only: bool,
+}
+
+impl EventSleuthApp {
+    /// Render the floating boot sessions window.
+    ///
+    /// Visibility is controlled by `self.show_boot_sessions`.
+    pub fn render_boot_sessions_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_boot_sessions {
+            return;
+        }
+        if self.boot_view.built_for != Some(self.events_generation) {
+            self.boot_view.sessions = boot_session::segment(&self.all_events);
+            self.boot_view.built_for = Some(self.events_generation);
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_session: Option<usize> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F50C} Boot Sessions")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(860.0)
+            .default_height(max_h.min(420.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.boot_view;
+                ui.checkbox(&mut view.dirty_only, "Unexpected shutdowns only");
+
+                let rows: Vec<usize> = (0..view.sessions.len())
+                    .filter(|&i| {
+                        !view.dirty_only || view.sessions[i].shutdown == ShutdownKind::Dirty
+                    })
+                    .collect();
+                let dirty = view
+                    .sessions
+                    .iter()
+                    .filter(|s| s.shutdown == ShutdownKind::Dirty)
+                    .count();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} boot sessions, {dirty} unexpected shutdowns \
+                         (System 6005/6006/6008/6009/6013, Kernel-General 12/13, \
+                         Kernel-Power 41, User32 1074, 1001)",
+                        view.sessions.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if view.sessions.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No System log events loaded")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let sessions = &view.sessions;
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::auto().at_least(90.0).clip(true)) // Computer
+                    .column(Column::auto().at_least(135.0)) // Boot
+                    .column(Column::auto().at_least(135.0)) // Shutdown
+                    .column(Column::auto().at_least(70.0)) // Uptime
+                    .column(Column::auto().at_least(65.0)) // Status
+                    .column(Column::auto().at_least(200.0).clip(true)) // Initiated by
+                    .column(Column::auto().at_least(90.0).clip(true)) // Bugcheck
+                    .column(Column::remainder().at_least(60.0)) // Filter
+                    .header(20.0, |mut header| {
+                        for title in [
+                            "Computer",
+                            "Boot",
+                            "Shutdown",
+                            "Uptime",
+                            "Status",
+                            "Initiated by",
+                            "Bugcheck",
+                            "",
+                        ] {
+                            header.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(title)
+                                        .color(theme::text_primary(dark))
+                                        .strong(),
+                                );
+                            });
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(theme::TABLE_ROW_HEIGHT, rows.len(), |mut row| {
+                            let idx = rows[row.index()];
+                            let s = &sessions[idx];
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(&s.computer)
+                                        .color(theme::text_secondary(dark)),
+                                );
+                            });
+                            row.col(|ui| {
+                                let boot = s
+                                    .start
+                                    .map(|t| time::format_table_timestamp(&t))
+                                    .unwrap_or_else(|| "(before log)".to_owned());
+                                ui.label(
+                                    egui::RichText::new(boot)
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                            });
+                            row.col(|ui| {
+                                let (text, hover) = match (s.shutdown, s.end) {
+                                    (_, Some(t)) => (time::format_table_timestamp(&t), None),
+                                    (ShutdownKind::Dirty, None) => (
+                                        format!("~{}", time::format_table_timestamp(&s.last_seen)),
+                                        Some("No shutdown logged; last System event shown"),
+                                    ),
+                                    _ => (String::new(), None),
+                                };
+                                let label = ui.label(
+                                    egui::RichText::new(text)
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                                if let Some(h) = hover {
+                                    label.on_hover_text(h);
+                                }
+                            });
+                            row.col(|ui| {
+                                if let Some(d) = s.uptime() {
+                                    let label = ui.label(time::format_span(d));
+                                    if let Some(r) = s.reported_uptime {
+                                        label.on_hover_text(format!(
+                                            "Windows reported {} (6013)",
+                                            time::format_span(r)
+                                        ));
+                                    }
+                                }
+                            });
+                            row.col(|ui| {
+                                let color = match s.shutdown {
+                                    ShutdownKind::Clean => theme::text_secondary(dark),
+                                    ShutdownKind::Dirty => theme::level_color(2, dark),
+                                    ShutdownKind::Running => theme::accent(dark),
+                                };
+                                ui.label(egui::RichText::new(s.shutdown.label()).color(color));
+                            });
+                            row.col(|ui| {
+                                if let Some(p) = &s.planned {
+                                    let mut hover = format!(
+                                        "Process: {}\nUser: {}\nType: {}\nReason: {}",
+                                        p.process, p.user, p.kind, p.reason
+                                    );
+                                    if !p.comment.is_empty() {
+                                        hover.push_str(&format!("\nComment: {}", p.comment));
+                                    }
+                                    ui.label(
+                                        egui::RichText::new(format!("{} ({})", p.user, p.process))
+                                            .color(theme::text_primary(dark)),
+                                    )
+                                    .on_hover_text(hover);
+                                }
+                            });
+                            row.col(|ui| {
+                                if !s.bugchecks.is_empty() {
+                                    ui.label(
+                                        egui::RichText::new(s.bugchecks.join(", "))
+                                            .color(theme::level_color(1, dark)),
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if ui
+                                    .small_button("Filter \u{2192}")
+                                    .on_hover_text(
+                                        "Show everything this computer logged during the session",
+                                    )
+                                    .clicked()
+                                {
+                                    filter_session = Some(idx);
+                                }
+                            });
+                        });
+                    });
+            });
+
+        if let Some(idx) = filter_session {
+            let s = &self.boot_view.sessions[idx];
+            let indices: Vec<usize> = (0..self.all_events.len())
+                .filter(|&i| s.contains(&self.all_events[i]))
+                .collect();
+            let label = match s.start {
+                Some(t) => format!(
+                    "Boot session {} {}",
+                    s.computer,
+                    time::format_table_timestamp(&t)
+                ),
+                None => format!("Boot session {} (before log)", s.computer),
+            };
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_boot_sessions = false;
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! Failed logons window: brute-force and password-spray report.
+//!
+//! Findings (bursts against one account, sprays from one source) on top,
+//! with any success that followed them highlighted; below, failures
+//! grouped by source or by account and a breakdown of decoded status
+//! codes. Every row links back to its events in the main table.
+
+use crate::app::EventSleuthApp;
+use crate::core::brute_force::{self, BruteForceReport, FailureGroup, FindingKind, Thresholds};
+use crate::ui::theme;
+use crate::util::time;
+use egui_extras::{Column, TableBuilder};
+
+/// Which breakdown is shown below the findings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BruteForceTab {
+    #[default]
+    BySource,
+    ByAccount,
+    Reasons,
+}
+
+/// Cached report and the window's view options.
+#[derive(Debug, Default)]
+pub struct BruteForceView {
+    pub report: BruteForceReport,
+    /// `events_generation` and thresholds the report was built for.
+    pub built_for: Option<(u64, Thresholds)>,
+    pub thresholds: Thresholds,
+    pub tab: BruteForceTab,
+}
+
+/// A groups table (by source or by account); returns the group whose
+/// events were asked for.
+fn groups_table<'a>(
+    ui: &mut egui::Ui,
+    groups: &'a [FailureGroup],
+    key_title: &str,
+    distinct_title: &str,
+    dark: bool,
+) -> Option<&'a FailureGroup> {
+    let mut picked = None;
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(170.0).clip(true)) // Key
+        .column(Column::auto().at_least(60.0)) // Failures
+        .column(Column::auto().at_least(70.0)) // Distinct
+        .column(Column::auto().at_least(135.0)) // First
+        .column(Column::auto().at_least(135.0)) // Last
+        .column(Column::auto().at_least(180.0).clip(true)) // Top reason
+        .column(Column::remainder().at_least(60.0)) // Events
+        .header(20.0, |mut header| {
+            for title in [
+                key_title,
+                "Failures",
+                distinct_title,
+                "First",
+                "Last",
+                "Top reason",
+                "Events",
+            ] {
+                header.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(title)
+                            .color(theme::text_primary(dark))
+                            .strong(),
+                    );
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(theme::TABLE_ROW_HEIGHT, groups.len(), |mut row| {
+                let g = &groups[row.index()];
+                row.col(|ui| {
+                    ui.label(egui::RichText::new(&g.key).color(theme::text_primary(dark)));
+                });
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(g.failures.to_string())
+                            .color(theme::level_color(2, dark))
+                            .strong(),
+                    );
+                });
+                row.col(|ui| {
+                    ui.label(g.distinct.to_string());
+                });
+                for t in [g.first, g.last] {
+                    row.col(|ui| {
+                        ui.label(
+                            egui::RichText::new(time::format_table_timestamp(&t))
+                                .color(theme::text_secondary(dark))
+                                .small(),
+                        );
+                    });
+                }
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(g.top_reason.1).color(theme::text_secondary(dark)),
+                    )
+                    .on_hover_text(&g.top_reason.0);
+                });
+                row.col(|ui| {
+                    if ui
+                        .small_button(format!("{} \u{2192}", g.events.len()))
+                        .on_hover_text("Show these failures in the table")
+                        .clicked()
+                    {
+                        picked = Some(g);
+                    }
+                });
+            });
+        });
+    picked
+}
+
+impl EventSleuthApp {
+    /// Render the floating failed logons window.
+    ///
+    /// Visibility is controlled by `self.show_brute_force`.
+    pub fn render_brute_force_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_brute_force {
+            return;
+        }
+        let key = (self.events_generation, self.brute_force_view.thresholds);
+        if self.brute_force_view.built_for != Some(key) {
+            self.brute_force_view.report = brute_force::analyse(&self.all_events, key.1);
+            self.brute_force_view.built_for = Some(key);
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.8;
+
+        egui::Window::new("\u{1F528} Failed Logons")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(900.0)
+            .default_height(max_h.min(560.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.brute_force_view;
+                ui.horizontal(|ui| {
+                    let mut minutes = view.thresholds.window_secs / 60;
+                    ui.label("Window");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut minutes)
+                                .range(1..=240)
+                                .suffix(" min"),
+                        )
+                        .changed()
+                    {
+                        view.thresholds.window_secs = minutes * 60;
+                    }
+                    ui.label("Burst \u{2265}");
+                    ui.add(
+                        egui::DragValue::new(&mut view.thresholds.burst)
+                            .range(2..=10_000)
+                            .suffix(" failures"),
+                    )
+                    .on_hover_text("Failures against one account within the window");
+                    ui.label("Spray \u{2265}");
+                    ui.add(
+                        egui::DragValue::new(&mut view.thresholds.spray)
+                            .range(2..=10_000)
+                            .suffix(" accounts"),
+                    )
+                    .on_hover_text("Accounts failed by one source within the window");
+                });
+
+                let report = &view.report;
+                let compromised = report
+                    .findings
+                    .iter()
+                    .filter(|f| f.success.is_some())
+                    .count();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} failures (4625 / 4771 / 4776) from {} sources against {} accounts; \
+                         {} findings, {compromised} followed by a success",
+                        report.failures.len(),
+                        report.by_source.len(),
+                        report.by_account.len(),
+                        report.findings.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if report.failures.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No failed logons in the loaded Security log")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                // ── Findings ────────────────────────────────────────
+                ui.label(
+                    egui::RichText::new(format!("\u{1F6A8} Findings ({})", report.findings.len()))
+                        .color(theme::text_primary(dark))
+                        .strong(),
+                );
+                if report.findings.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No window reaches the burst or spray threshold")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                } else {
+                    let findings_height = (ui.available_height() * 0.45).max(120.0);
+                    ui.push_id("brute_force_findings", |ui| {
+                        TableBuilder::new(ui)
+                            .striped(true)
+                            .resizable(true)
+                            .max_scroll_height(findings_height)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(Column::auto().at_least(110.0)) // Kind
+                            .column(Column::auto().at_least(170.0).clip(true)) // Key
+                            .column(Column::auto().at_least(135.0)) // Start
+                            .column(Column::auto().at_least(70.0)) // Span
+                            .column(Column::auto().at_least(60.0)) // Failures
+                            .column(Column::auto().at_least(90.0)) // Distinct
+                            .column(Column::auto().at_least(170.0)) // Success
+                            .column(Column::remainder().at_least(60.0)) // Events
+                            .header(20.0, |mut header| {
+                                for title in [
+                                    "Kind", "Target / source", "Start", "Span", "Failures",
+                                    "Spread", "Followed by", "Events",
+                                ] {
+                                    header.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(title)
+                                                .color(theme::text_primary(dark))
+                                                .strong(),
+                                        );
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(
+                                    theme::TABLE_ROW_HEIGHT,
+                                    report.findings.len(),
+                                    |mut row| {
+                                        let f = &report.findings[row.index()];
+                                        let events = &self.all_events;
+                                        row.col(|ui| {
+                                            let color = match f.kind {
+                                                FindingKind::Burst => theme::level_color(2, dark),
+                                                FindingKind::Spray => theme::level_color(3, dark),
+                                            };
+                                            ui.label(
+                                                egui::RichText::new(f.kind.label()).color(color),
+                                            );
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(
+                                                egui::RichText::new(&f.key)
+                                                    .color(theme::text_primary(dark))
+                                                    .strong(),
+                                            );
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(
+                                                egui::RichText::new(
+                                                    time::format_table_timestamp(&f.start),
+                                                )
+                                                .color(theme::text_secondary(dark))
+                                                .small(),
+                                            );
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(time::format_span(f.end - f.start));
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(f.failures.to_string());
+                                        });
+                                        row.col(|ui| {
+                                            let what = match f.kind {
+                                                FindingKind::Burst => "sources",
+                                                FindingKind::Spray => "accounts",
+                                            };
+                                            ui.label(format!("{} {what}", f.distinct));
+                                        });
+                                        row.col(|ui| match f.success.map(|i| &events[i]) {
+                                            Some(e) => {
+                                                let who = e.data("TargetUserName").unwrap_or("?");
+                                                ui.label(
+                                                    egui::RichText::new(format!(
+                                                        "\u{2714} 4624 {who}"
+                                                    ))
+                                                    .color(theme::level_color(1, dark))
+                                                    .strong(),
+                                                )
+                                                .on_hover_text(format!(
+                                                    "Successful logon at {}",
+                                                    time::format_table_timestamp(&e.timestamp)
+                                                ));
+                                            }
+                                            None => {
+                                                ui.label(
+                                                    egui::RichText::new("\u{2014}")
+                                                        .color(theme::text_dim(dark)),
+                                                );
+                                            }
+                                        });
+                                        row.col(|ui| {
+                                            if ui
+                                                .small_button(format!(
+                                                    "{} \u{2192}",
+                                                    f.events.len() + usize::from(f.success.is_some())
+                                                ))
+                                                .on_hover_text(
+                                                    "Show these failures (and the success) in the table",
+                                                )
+                                                .clicked()
+                                            {
+                                                let mut indices = f.events.clone();
+                                                indices.extend(f.success);
+                                                filter_to = Some((
+                                                    format!("{}: {}", f.kind.label(), f.key),
+                                                    indices,
+                                                ));
+                                            }
+                                        });
+                                    },
+                                );
+                            });
+                    });
+                }
+
+                ui.add_space(theme::SECTION_SPACING);
+
+                // ── Breakdowns ──────────────────────────────────────
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut view.tab, BruteForceTab::BySource, "By source");
+                    ui.selectable_value(&mut view.tab, BruteForceTab::ByAccount, "By account");
+                    ui.selectable_value(&mut view.tab, BruteForceTab::Reasons, "Status codes");
+                });
+                ui.separator();
+                ui.push_id("brute_force_groups", |ui| match view.tab {
+                    BruteForceTab::BySource => {
+                        if let Some(g) = groups_table(
+                            ui,
+                            &report.by_source,
+                            "Source",
+                            "Accounts",
+                            dark,
+                        ) {
+                            filter_to = Some((format!("Failed logons from {}", g.key), g.events.clone()));
+                        }
+                    }
+                    BruteForceTab::ByAccount => {
+                        if let Some(g) = groups_table(
+                            ui,
+                            &report.by_account,
+                            "Account",
+                            "Sources",
+                            dark,
+                        ) {
+                            filter_to = Some((format!("Failed logons for {}", g.key), g.events.clone()));
+                        }
+                    }
+                    BruteForceTab::Reasons => {
+                        egui::Grid::new("brute_force_reasons")
+                            .striped(true)
+                            .num_columns(3)
+                            .show(ui, |ui| {
+                                for (code, text, count) in &report.reasons {
+                                    ui.label(egui::RichText::new(code).monospace());
+                                    ui.label(text.to_string());
+                                    ui.label(
+                                        egui::RichText::new(count.to_string())
+                                            .color(theme::accent(dark))
+                                            .strong(),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    }
+                });
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_brute_force = false;
+        }
+    }
+}
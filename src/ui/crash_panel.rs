@@ -0,0 +1,420 @@
+//! App crashes window: crash and hang aggregation.
+//!
+//! Parsed crash records grouped by application or by application +
+//! faulting module, each with its kind mix, versions, top exception,
+//! first / last occurrence and a per-day trend over the span of all
+//! records. A third tab lists the individual records. Every row links
+//! back to its events in the main table.
+
+use chrono::NaiveDate;
+use egui_extras::{Column, TableBuilder};
+
+use crate::app::EventSleuthApp;
+use crate::core::crash::{self, CrashGroup, CrashKind, CrashReport};
+use crate::ui::theme;
+use crate::util::time;
+
+/// Which list the window shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CrashTab {
+    #[default]
+    ByApp,
+    ByModule,
+    Records,
+}
+
+/// Cached report and the window's view options.
+#[derive(Debug, Default)]
+pub struct CrashView {
+    pub report: CrashReport,
+    /// `events_generation` and WER setting the report was built for.
+    pub built_for: Option<(u64, bool)>,
+    /// Include Windows Error Reporting 1001 (mostly duplicates of 1000).
+    pub include_reports: bool,
+    pub tab: CrashTab,
+    pub search: String,
+}
+
+/// `0xc0000005 (Access violation)`, or the code alone.
+fn exception_text(code: &str) -> String {
+    match crash::decode_exception(code) {
+        Some(text) => format!("{code} ({text})"),
+        None => code.to_owned(),
+    }
+}
+
+/// Per-day counts of a group as bars over the report's whole day range.
+fn sparkline(
+    ui: &mut egui::Ui,
+    daily: &[(NaiveDate, usize)],
+    days: (NaiveDate, NaiveDate),
+    dark: bool,
+) {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 14.0), egui::Sense::hover());
+    let span = (days.1 - days.0).num_days().max(0) as f32 + 1.0;
+    let max = daily.iter().map(|d| d.1).max().unwrap_or(1).max(1) as f32;
+    let bar_w = (rect.width() / span).max(1.0);
+    let painter = ui.painter_at(rect);
+    for (day, count) in daily {
+        let x = rect.left() + (*day - days.0).num_days() as f32 * rect.width() / span;
+        let h = (rect.height() * *count as f32 / max).max(2.0);
+        painter.rect_filled(
+            egui::Rect::from_x_y_ranges(x..=x + bar_w, rect.bottom() - h..=rect.bottom()),
+            0.0,
+            theme::level_color(2, dark),
+        );
+    }
+    response.on_hover_ui(|ui| {
+        for (day, count) in daily.iter().rev().take(10) {
+            ui.label(format!("{day}: {count}"));
+        }
+        if daily.len() > 10 {
+            ui.label(
+                egui::RichText::new(format!("\u{2026} {} more days", daily.len() - 10))
+                    .color(theme::text_dim(dark))
+                    .small(),
+            );
+        }
+    });
+}
+
+/// A groups table (by application or by module); returns the group whose
+/// events were asked for.
+fn groups_table<'a>(
+    ui: &mut egui::Ui,
+    groups: &[&'a CrashGroup],
+    with_module: bool,
+    days: (NaiveDate, NaiveDate),
+    dark: bool,
+) -> Option<&'a CrashGroup> {
+    let mut picked = None;
+    let mut titles = vec!["Application"];
+    if with_module {
+        titles.push("Module");
+    }
+    titles.extend([
+        "Count",
+        "Kinds",
+        "Versions",
+        "Top exception",
+        "Hosts",
+        "First",
+        "Last",
+        "Trend",
+        "Events",
+    ]);
+    let mut table = TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(150.0).clip(true)); // Application
+    if with_module {
+        table = table.column(Column::auto().at_least(130.0).clip(true)); // Module
+    }
+    table
+        .column(Column::auto().at_least(50.0)) // Count
+        .column(Column::auto().at_least(90.0).clip(true)) // Kinds
+        .column(Column::auto().at_least(80.0).clip(true)) // Versions
+        .column(Column::auto().at_least(150.0).clip(true)) // Top exception
+        .column(Column::auto().at_least(45.0)) // Hosts
+        .column(Column::auto().at_least(135.0)) // First
+        .column(Column::auto().at_least(135.0)) // Last
+        .column(Column::auto().at_least(110.0)) // Trend
+        .column(Column::remainder().at_least(60.0)) // Events
+        .header(20.0, |mut header| {
+            for title in titles {
+                header.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(title)
+                            .color(theme::text_primary(dark))
+                            .strong(),
+                    );
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(theme::TABLE_ROW_HEIGHT, groups.len(), |mut row| {
+                let g = groups[row.index()];
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(&g.app)
+                            .color(theme::text_primary(dark))
+                            .strong(),
+                    );
+                });
+                if with_module {
+                    row.col(|ui| {
+                        let text = if g.module.is_empty() {
+                            "\u{2014}"
+                        } else {
+                            &g.module
+                        };
+                        ui.label(egui::RichText::new(text).color(theme::text_primary(dark)));
+                    });
+                }
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(g.count().to_string())
+                            .color(theme::level_color(2, dark))
+                            .strong(),
+                    );
+                });
+                row.col(|ui| {
+                    let kinds: Vec<String> = CrashKind::ALL
+                        .iter()
+                        .zip(g.kinds)
+                        .filter(|(_, n)| *n > 0)
+                        .map(|(k, n)| format!("{n} {}", k.label()))
+                        .collect();
+                    let text = kinds.join(", ");
+                    ui.label(egui::RichText::new(&text).color(theme::text_secondary(dark)))
+                        .on_hover_text(&text);
+                });
+                row.col(|ui| {
+                    let text = g.versions.join(", ");
+                    ui.label(egui::RichText::new(&text).color(theme::text_secondary(dark)))
+                        .on_hover_text(&text);
+                });
+                row.col(|ui| {
+                    let text = exception_text(&g.top_exception);
+                    ui.label(egui::RichText::new(&text).color(theme::text_secondary(dark)))
+                        .on_hover_text(&text);
+                });
+                row.col(|ui| {
+                    ui.label(g.computers.to_string());
+                });
+                for t in [g.first, g.last] {
+                    row.col(|ui| {
+                        ui.label(
+                            egui::RichText::new(time::format_table_timestamp(&t))
+                                .color(theme::text_secondary(dark))
+                                .small(),
+                        );
+                    });
+                }
+                row.col(|ui| sparkline(ui, &g.daily, days, dark));
+                row.col(|ui| {
+                    if ui
+                        .small_button(format!("{} \u{2192}", g.count()))
+                        .on_hover_text("Show these events in the table")
+                        .clicked()
+                    {
+                        picked = Some(g);
+                    }
+                });
+            });
+        });
+    picked
+}
+
+impl EventSleuthApp {
+    /// Render the floating app crashes window.
+    ///
+    /// Visibility is controlled by `self.show_crashes`.
+    pub fn render_crash_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_crashes {
+            return;
+        }
+        let key = (self.events_generation, self.crash_view.include_reports);
+        if self.crash_view.built_for != Some(key) {
+            self.crash_view.report = crash::analyse(&self.all_events, key.1);
+            self.crash_view.built_for = Some(key);
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.8;
+
+        egui::Window::new("\u{1F4A5} App Crashes")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(960.0)
+            .default_height(max_h.min(520.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.crash_view;
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut view.search)
+                            .hint_text("Filter applications / modules")
+                            .desired_width(220.0),
+                    );
+                    ui.checkbox(&mut view.include_reports, "Include WER reports")
+                        .on_hover_text(
+                            "Windows Error Reporting 1001 usually repeats a crash already \
+                             logged as 1000 or 1026",
+                        );
+                });
+
+                let report = &view.report;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} records from {} applications ({} application / module pairs)",
+                        report.records.len(),
+                        report.by_app.len(),
+                        report.by_module.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                let Some(days) = report.days else {
+                    ui.label(
+                        egui::RichText::new(
+                            "No Application Error 1000, Application Hang 1002 or \
+                             .NET Runtime 1026 events loaded",
+                        )
+                        .color(theme::text_dim(dark))
+                        .italics(),
+                    );
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut view.tab, CrashTab::ByApp, "By application");
+                    ui.selectable_value(&mut view.tab, CrashTab::ByModule, "By module");
+                    ui.selectable_value(&mut view.tab, CrashTab::Records, "Records");
+                });
+                ui.separator();
+
+                let needle = view.search.trim().to_lowercase();
+                let matches = |app: &str, module: &str| {
+                    needle.is_empty()
+                        || app.to_lowercase().contains(&needle)
+                        || module.to_lowercase().contains(&needle)
+                };
+
+                ui.push_id("crash_table", |ui| {
+                    if view.tab != CrashTab::Records {
+                        let with_module = view.tab == CrashTab::ByModule;
+                        let all = if with_module {
+                            &report.by_module
+                        } else {
+                            &report.by_app
+                        };
+                        let groups: Vec<&CrashGroup> =
+                            all.iter().filter(|g| matches(&g.app, &g.module)).collect();
+                        if let Some(g) = groups_table(ui, &groups, with_module, days, dark) {
+                            let label = if with_module && !g.module.is_empty() {
+                                format!("Crashes of {} in {}", g.app, g.module)
+                            } else {
+                                format!("Crashes of {}", g.app)
+                            };
+                            filter_to = Some((label, g.events.clone()));
+                        }
+                        return;
+                    }
+
+                    let records: Vec<usize> = (0..report.records.len())
+                        .rev()
+                        .filter(|&i| {
+                            let r = &report.records[i];
+                            matches(&r.app, &r.module)
+                        })
+                        .collect();
+                    TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                        .column(Column::auto().at_least(135.0)) // Time
+                        .column(Column::auto().at_least(95.0)) // Kind
+                        .column(Column::auto().at_least(150.0).clip(true)) // Application
+                        .column(Column::auto().at_least(70.0).clip(true)) // Version
+                        .column(Column::auto().at_least(130.0).clip(true)) // Module
+                        .column(Column::auto().at_least(170.0).clip(true)) // Exception
+                        .column(Column::auto().at_least(120.0).clip(true)) // Offset
+                        .column(Column::remainder().at_least(80.0).clip(true)) // Computer
+                        .sense(egui::Sense::click())
+                        .header(20.0, |mut header| {
+                            for title in [
+                                "Time",
+                                "Kind",
+                                "Application",
+                                "Version",
+                                "Module",
+                                "Exception",
+                                "Offset",
+                                "Computer",
+                            ] {
+                                header.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(title)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                });
+                            }
+                        })
+                        .body(|body| {
+                            body.rows(theme::TABLE_ROW_HEIGHT, records.len(), |mut row| {
+                                let r = &report.records[records[row.index()]];
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(time::format_table_timestamp(&r.time))
+                                            .color(theme::text_secondary(dark))
+                                            .small(),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    let level = if r.kind == CrashKind::Hang { 3 } else { 2 };
+                                    ui.label(
+                                        egui::RichText::new(r.kind.label())
+                                            .color(theme::level_color(level, dark)),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(&r.app)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                });
+                                for text in [&r.app_version, &r.module] {
+                                    row.col(|ui| {
+                                        ui.label(text.as_str());
+                                    });
+                                }
+                                row.col(|ui| {
+                                    let text = exception_text(&r.exception);
+                                    ui.label(
+                                        egui::RichText::new(&text)
+                                            .color(theme::text_secondary(dark)),
+                                    )
+                                    .on_hover_text(&text);
+                                });
+                                row.col(|ui| {
+                                    ui.label(egui::RichText::new(&r.offset).monospace());
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(&r.computer)
+                                            .color(theme::text_secondary(dark)),
+                                    );
+                                });
+                                if row
+                                    .response()
+                                    .on_hover_text("Click to show this event in the table")
+                                    .clicked()
+                                {
+                                    filter_to = Some((
+                                        format!("{} {}", r.kind.label(), r.app),
+                                        vec![r.event],
+                                    ));
+                                }
+                            });
+                        });
+                });
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_crashes = false;
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Pivot table window: event counts across two chosen dimensions.
+//!
+//! Rows and columns can be any header field, an EventData key, or the
+//! event's day / hour. Cells are heat-coloured by count; clicking a cell,
+//! row label or total narrows the event table to the events behind it,
+//! and the table as shown exports to CSV.
+
+use std::collections::BTreeSet;
+
+use crate::app::EventSleuthApp;
+use crate::core::crosstab::{self, CrossTab, Dimension, Sort};
+use crate::core::pivot::PivotField;
+use crate::ui::theme;
+use egui_extras::{Column, TableBuilder};
+
+/// Pivot table state and the window's view options.
+#[derive(Debug)]
+pub struct CrosstabView {
+    pub row_dim: Dimension,
+    pub col_dim: Dimension,
+    pub row_sort: Sort,
+    pub col_sort: Sort,
+    pub table: CrossTab,
+    /// EventData keys seen in the filtered events, for the axis pickers.
+    keys: Vec<String>,
+    /// Set when `filtered_indices` or a dimension changed; the table
+    /// rebuilds on next render.
+    pub dirty: bool,
+}
+
+impl Default for CrosstabView {
+    /// Default: event ID × computer, busiest rows first.
+    fn default() -> Self {
+        Self {
+            row_dim: Dimension::Field(PivotField::EventId),
+            col_dim: Dimension::Field(PivotField::Computer),
+            row_sort: Sort::Total,
+            col_sort: Sort::Total,
+            table: CrossTab::default(),
+            keys: Vec::new(),
+            dirty: true,
+        }
+    }
+}
+
+/// Axis picker over the built-in dimensions and the seen EventData keys.
+/// Returns `true` when the selection changed.
+fn dimension_combo(ui: &mut egui::Ui, id: &str, dim: &mut Dimension, keys: &[String]) -> bool {
+    let before = dim.clone();
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(dim.label())
+        .width(170.0)
+        .height(400.0)
+        .show_ui(ui, |ui| {
+            for d in Dimension::builtin() {
+                let label = d.label().into_owned();
+                ui.selectable_value(dim, d, label);
+            }
+            if !keys.is_empty() {
+                ui.separator();
+            }
+            for key in keys {
+                let d = Dimension::Field(PivotField::EventData(key.clone()));
+                ui.selectable_value(dim, d, key);
+            }
+        });
+    *dim != before
+}
+
+/// Row / column order picker.
+fn sort_combo(ui: &mut egui::Ui, id: &str, sort: &mut Sort) {
+    let text = match sort {
+        Sort::Label => "A \u{2192} Z".to_owned(),
+        Sort::Total => "By total".to_owned(),
+        Sort::Column(_) => "By column".to_owned(),
+    };
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(sort, Sort::Label, "A \u{2192} Z");
+            ui.selectable_value(sort, Sort::Total, "By total");
+        });
+}
+
+impl EventSleuthApp {
+    /// Rebuild the pivot table from the filtered events if it is stale.
+    fn refresh_crosstab(&mut self) {
+        let view = &mut self.crosstab_view;
+        if !view.dirty {
+            return;
+        }
+        view.table = crosstab::build(
+            &self.all_events,
+            &self.filtered_indices,
+            &view.row_dim,
+            &view.col_dim,
+            &chrono::Local,
+        );
+        let keys: BTreeSet<&str> = self
+            .filtered_indices
+            .iter()
+            .flat_map(|&i| {
+                self.all_events[i]
+                    .event_data
+                    .iter()
+                    .map(|(k, _)| k.as_str())
+            })
+            .collect();
+        view.keys = keys.into_iter().map(str::to_owned).collect();
+        if matches!(view.row_sort, Sort::Column(c) if c >= view.table.cols.len()) {
+            view.row_sort = Sort::Total;
+        }
+        view.dirty = false;
+    }
+
+    /// Export the pivot table, in its displayed order, to CSV via a
+    /// native save dialog on a background thread.
+    fn export_crosstab_csv(&mut self) {
+        if self.export_rx.is_some() {
+            self.export_message = Some((
+                "Export already in progress".into(),
+                std::time::Instant::now(),
+            ));
+            return;
+        }
+        let view = &self.crosstab_view;
+        let corner = format!("{} \\ {}", view.row_dim.label(), view.col_dim.label());
+        let records = view.table.to_records(
+            &corner,
+            &view.table.row_order(view.row_sort),
+            &view.table.col_order(view.col_sort),
+        );
+
+        let (tx, rx) = crossbeam_channel::bounded::<String>(1);
+        self.export_rx = Some(rx);
+
+        std::thread::spawn(move || {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .set_file_name("EventSleuth_pivot.csv")
+                .save_file()
+            {
+                match crate::export::csv_export::export_crosstab(&records, &path) {
+                    Ok(()) => {
+                        let _ = tx.send(format!(
+                            "Exported pivot table ({} rows) to CSV",
+                            records.len().saturating_sub(2)
+                        ));
+                    }
+                    Err(e) => {
+                        tracing::error!("Pivot table export failed: {}", e);
+                        let _ = tx.send(format!("Pivot table export failed: {e}"));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Render the floating pivot table window.
+    ///
+    /// Visibility is controlled by `self.show_crosstab`.
+    pub fn render_crosstab_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_crosstab {
+            return;
+        }
+        self.refresh_crosstab();
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut drill: Option<(Option<usize>, Option<usize>)> = None;
+        let mut export = false;
+        let max_h = ctx.screen_rect().height() * 0.8;
+
+        egui::Window::new("\u{1F9EE} Pivot Table")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(720.0)
+            .default_height(max_h.min(480.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.crosstab_view;
+                ui.horizontal(|ui| {
+                    ui.label("Rows");
+                    let mut changed =
+                        dimension_combo(ui, "crosstab_rows", &mut view.row_dim, &view.keys);
+                    if ui
+                        .small_button("\u{21C4}")
+                        .on_hover_text("Swap rows and columns")
+                        .clicked()
+                    {
+                        std::mem::swap(&mut view.row_dim, &mut view.col_dim);
+                        changed = true;
+                    }
+                    ui.label("Columns");
+                    changed |= dimension_combo(ui, "crosstab_cols", &mut view.col_dim, &view.keys);
+                    if changed {
+                        view.dirty = true;
+                        view.row_sort = Sort::Total;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Row order");
+                    sort_combo(ui, "crosstab_row_sort", &mut view.row_sort);
+                    ui.label("Column order");
+                    sort_combo(ui, "crosstab_col_sort", &mut view.col_sort);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        export = ui
+                            .add_enabled(
+                                view.table.total > 0,
+                                egui::Button::new("\u{1F4C4} Export CSV..."),
+                            )
+                            .clicked();
+                    });
+                });
+                if view.dirty {
+                    // A dimension changed this frame; draw the new table next frame.
+                    ui.ctx().request_repaint();
+                    return;
+                }
+
+                let table = &view.table;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} rows \u{D7} {} columns over {} events \u{2014} click a count to \
+                         filter to its events",
+                        table.rows.len(),
+                        table.cols.len(),
+                        table.total
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+                if table.total == 0 {
+                    ui.label(
+                        egui::RichText::new("No events to pivot")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let rows = table.row_order(view.row_sort);
+                let cols = table.col_order(view.col_sort);
+                let mut row_sort = view.row_sort;
+                let heat = |count: usize| {
+                    theme::accent(dark)
+                        .gamma_multiply(0.1 + 0.6 * count as f32 / table.max_cell.max(1) as f32)
+                };
+                // A clickable count, optionally on a heat-coloured background.
+                let count_cell =
+                    |ui: &mut egui::Ui, count: usize, fill: bool, strong: bool| -> bool {
+                        if count == 0 {
+                            ui.label(egui::RichText::new("\u{B7}").color(theme::text_dim(dark)));
+                            return false;
+                        }
+                        if fill {
+                            ui.painter().rect_filled(ui.max_rect(), 0.0, heat(count));
+                        }
+                        let mut text =
+                            egui::RichText::new(count.to_string()).color(theme::text_primary(dark));
+                        if strong {
+                            text = text.strong();
+                        }
+                        ui.add(egui::Label::new(text).sense(egui::Sense::click()))
+                            .on_hover_cursor(egui::CursorIcon::PointingHand)
+                            .clicked()
+                    };
+
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .vscroll(true)
+                        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                        .column(Column::auto().at_least(160.0).clip(true))
+                        .columns(Column::auto().at_least(48.0).clip(true), cols.len())
+                        .column(Column::auto().at_least(56.0))
+                        .header(22.0, |mut header| {
+                            header.col(|ui| {
+                                let text = egui::RichText::new(format!(
+                                    "{} \\ {}",
+                                    view.row_dim.label(),
+                                    view.col_dim.label()
+                                ))
+                                .color(theme::text_primary(dark))
+                                .strong();
+                                if ui
+                                    .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                    .on_hover_text("Sort rows A \u{2192} Z")
+                                    .clicked()
+                                {
+                                    row_sort = Sort::Label;
+                                }
+                            });
+                            for &c in &cols {
+                                header.col(|ui| {
+                                    let active = row_sort == Sort::Column(c);
+                                    let mut text =
+                                        egui::RichText::new(&table.cols[c]).color(if active {
+                                            theme::accent(dark)
+                                        } else {
+                                            theme::text_primary(dark)
+                                        });
+                                    if active {
+                                        text = text.strong();
+                                    }
+                                    if ui
+                                        .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                        .on_hover_text(format!(
+                                            "{}\nSort rows by this column",
+                                            table.cols[c]
+                                        ))
+                                        .clicked()
+                                    {
+                                        row_sort = Sort::Column(c);
+                                    }
+                                });
+                            }
+                            header.col(|ui| {
+                                let text = egui::RichText::new("Total")
+                                    .color(theme::text_primary(dark))
+                                    .strong();
+                                if ui
+                                    .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                    .on_hover_text("Sort rows by total")
+                                    .clicked()
+                                {
+                                    row_sort = Sort::Total;
+                                }
+                            });
+                        })
+                        .body(|body| {
+                            body.rows(theme::TABLE_ROW_HEIGHT, rows.len() + 1, |mut row| {
+                                let Some(&r) = rows.get(row.index()) else {
+                                    // Totals row.
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new("Total")
+                                                .color(theme::text_primary(dark))
+                                                .strong(),
+                                        );
+                                    });
+                                    for &c in &cols {
+                                        row.col(|ui| {
+                                            if count_cell(ui, table.col_totals[c], false, true) {
+                                                drill = Some((None, Some(c)));
+                                            }
+                                        });
+                                    }
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(table.total.to_string()).strong(),
+                                        );
+                                    });
+                                    return;
+                                };
+                                row.col(|ui| {
+                                    let text = egui::RichText::new(&table.rows[r])
+                                        .color(theme::text_primary(dark));
+                                    if ui
+                                        .add(egui::Label::new(text).sense(egui::Sense::click()))
+                                        .on_hover_text(&table.rows[r])
+                                        .clicked()
+                                    {
+                                        drill = Some((Some(r), None));
+                                    }
+                                });
+                                for &c in &cols {
+                                    row.col(|ui| {
+                                        if count_cell(ui, table.count(r, c), true, false) {
+                                            drill = Some((Some(r), Some(c)));
+                                        }
+                                    });
+                                }
+                                row.col(|ui| {
+                                    if count_cell(ui, table.row_totals[r], false, true) {
+                                        drill = Some((Some(r), None));
+                                    }
+                                });
+                            });
+                        });
+                });
+                view.row_sort = row_sort;
+            });
+
+        if export {
+            self.export_crosstab_csv();
+        }
+        if let Some((r, c)) = drill {
+            let view = &self.crosstab_view;
+            let table = &view.table;
+            let mut parts = Vec::new();
+            if let Some(r) = r {
+                parts.push(format!("{} = {}", view.row_dim.label(), table.rows[r]));
+            }
+            if let Some(c) = c {
+                parts.push(format!("{} = {}", view.col_dim.label(), table.cols[c]));
+            }
+            let indices = table.events(r, c);
+            self.set_event_index_filter(format!("Pivot: {}", parts.join(", ")), &indices);
+        }
+        if !open {
+            self.show_crosstab = false;
+        }
+    }
+}
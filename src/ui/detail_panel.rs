@@ -4,9 +4,12 @@
 //! and **XML** (raw XML string in a monospaced scrollable area).
 //!
 //! When a text search is active, matching substrings are highlighted with
-//! a contrasting background colour via `egui::text::LayoutJob`.
+//! a contrasting background colour via `egui::text::LayoutJob`. Header
+//! fields and EventData values offer a right-click pivot menu.
 
 use crate::app::{DetailTab, EventSleuthApp};
+use crate::core::pivot::{PivotField, PivotRequest};
+use crate::ui::pivot_menu::pivot_label;
 use crate::ui::theme;
 use crate::util::time::format_detail_timestamp;
 
@@ -72,6 +75,15 @@ impl EventSleuthApp {
                 {
                     ui.ctx().copy_text(event.event_id.to_string());
                 }
+                if ui
+                    .small_button("\u{2728} Similar")
+                    .on_hover_text(
+                        "Suggest a filter matching this event and all pinned events\n(fields they share: IDs, provider, levels, EventData values)",
+                    )
+                    .clicked()
+                {
+                    self.open_filter_suggestion();
+                }
                 ui.separator();
                 // Bookmark toggle for the selected event
                 if let Some(vis_idx) = self.selected_event_idx {
@@ -104,20 +116,33 @@ impl EventSleuthApp {
 
         ui.separator();
 
-        egui::ScrollArea::vertical().show(ui, |ui| match self.detail_tab {
-            DetailTab::Details => self.render_detail_formatted(ui, &event),
-            DetailTab::Xml => self.render_detail_xml(ui, &event),
-        });
+        let pivot = egui::ScrollArea::vertical()
+            .show(ui, |ui| match self.detail_tab {
+                DetailTab::Details => self.render_detail_formatted(ui, &event),
+                DetailTab::Xml => {
+                    self.render_detail_xml(ui, &event);
+                    None
+                }
+            })
+            .inner;
+        if let Some(req) = pivot {
+            self.apply_pivot_request(req);
+        }
     }
 
     /// Render the formatted details view: header fields, message, event data.
+    ///
+    /// Returns the pivot chosen from a field's context menu, if any.
     fn render_detail_formatted(
         &self,
         ui: &mut egui::Ui,
         event: &crate::core::event_record::EventRecord,
-    ) {
+    ) -> Option<PivotRequest> {
         let dark = self.dark_mode;
         let level_color = theme::level_color(event.level, dark);
+        let at = event.timestamp;
+        let window = self.pivot_window_minutes;
+        let mut pivot: Option<PivotRequest> = None;
 
         // ── Header grid ─────────────────────────────────────────────
         egui::Grid::new("detail_header_grid")
@@ -126,49 +151,125 @@ impl EventSleuthApp {
             .spacing([20.0, 6.0])
             .show(ui, |ui| {
                 // Row 1
+                let id = event.event_id.to_string();
                 ui.label(egui::RichText::new("Event ID").color(theme::text_dim(dark)));
-                ui.label(event.event_id.to_string());
+                pivot =
+                    pivot
+                        .take()
+                        .or(pivot_label(ui, &id, &PivotField::EventId, &id, at, window));
                 ui.label(egui::RichText::new("Level").color(theme::text_dim(dark)));
-                ui.label(egui::RichText::new(&event.level_name).color(level_color));
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    egui::RichText::new(&event.level_name).color(level_color),
+                    &PivotField::Level,
+                    &event.level.to_string(),
+                    at,
+                    window,
+                ));
                 ui.end_row();
 
                 // Row 2
                 ui.label(egui::RichText::new("Provider").color(theme::text_dim(dark)));
-                ui.label(&event.provider_name);
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    &event.provider_name,
+                    &PivotField::Provider,
+                    &event.provider_name,
+                    at,
+                    window,
+                ));
                 ui.label(egui::RichText::new("Channel").color(theme::text_dim(dark)));
-                ui.label(&event.channel);
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    &event.channel,
+                    &PivotField::Channel,
+                    &event.channel,
+                    at,
+                    window,
+                ));
                 ui.end_row();
 
                 // Row 3
                 ui.label(egui::RichText::new("Timestamp").color(theme::text_dim(dark)));
                 ui.label(format_detail_timestamp(&event.timestamp));
                 ui.label(egui::RichText::new("Computer").color(theme::text_dim(dark)));
-                ui.label(&event.computer);
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    &event.computer,
+                    &PivotField::Computer,
+                    &event.computer,
+                    at,
+                    window,
+                ));
                 ui.end_row();
 
                 // Row 4
+                let pid = event.process_id.to_string();
+                let tid = event.thread_id.to_string();
                 ui.label(egui::RichText::new("Process ID").color(theme::text_dim(dark)));
-                ui.label(event.process_id.to_string());
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    &pid,
+                    &PivotField::ProcessId,
+                    &pid,
+                    at,
+                    window,
+                ));
                 ui.label(egui::RichText::new("Thread ID").color(theme::text_dim(dark)));
-                ui.label(event.thread_id.to_string());
+                pivot = pivot.take().or(pivot_label(
+                    ui,
+                    &tid,
+                    &PivotField::ThreadId,
+                    &tid,
+                    at,
+                    window,
+                ));
                 ui.end_row();
 
                 // Row 5 (optional fields)
                 if let Some(ref sid) = event.user_sid {
                     ui.label(egui::RichText::new("User SID").color(theme::text_dim(dark)));
-                    ui.label(sid);
+                    pivot = pivot.take().or(pivot_label(
+                        ui,
+                        sid,
+                        &PivotField::UserSid,
+                        sid,
+                        at,
+                        window,
+                    ));
                 } else {
                     ui.label("");
                     ui.label("");
                 }
                 if let Some(ref aid) = event.activity_id {
                     ui.label(egui::RichText::new("Activity ID").color(theme::text_dim(dark)));
-                    ui.label(aid);
+                    pivot = pivot.take().or(pivot_label(
+                        ui,
+                        aid,
+                        &PivotField::ActivityId,
+                        aid,
+                        at,
+                        window,
+                    ));
                 } else {
                     ui.label("");
                     ui.label("");
                 }
                 ui.end_row();
+
+                // Row 6 (optional)
+                if let Some(ref rid) = event.related_activity_id {
+                    ui.label(egui::RichText::new("Related Activity").color(theme::text_dim(dark)));
+                    pivot = pivot.take().or(pivot_label(
+                        ui,
+                        rid,
+                        &PivotField::RelatedActivityId,
+                        rid,
+                        at,
+                        window,
+                    ));
+                    ui.end_row();
+                }
             });
 
         ui.add_space(8.0);
@@ -258,9 +359,12 @@ impl EventSleuthApp {
                             value.clone()
                         };
                         // Highlight search matches in event data values
+                        let field = PivotField::EventData(key.clone());
                         let search = &self.filter.text_search;
                         if search.is_empty() {
-                            ui.label(&display);
+                            pivot = pivot
+                                .take()
+                                .or(pivot_label(ui, &display, &field, value, at, window));
                         } else {
                             let regex_ref = if self.filter.use_regex {
                                 self.filter.compiled_regex.as_ref()
@@ -276,12 +380,16 @@ impl EventSleuthApp {
                                 dark,
                                 regex_ref,
                             );
-                            ui.label(job);
+                            pivot = pivot
+                                .take()
+                                .or(pivot_label(ui, job, &field, value, at, window));
                         }
                         ui.end_row();
                     }
                 });
         }
+
+        pivot
     }
 
     /// Render the raw XML view with monospace font in a scrollable area.
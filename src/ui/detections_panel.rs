@@ -0,0 +1,379 @@
+//! Detections window: built-in and user-supplied Sigma rules that matched
+//! loaded events, and completed correlation chains.
+//!
+//! Lists each rule with hits (level, title, ATT&CK tags, hit count) and
+//! each chain (rule, grouping key, time span), and lets the user narrow the
+//! event table to the events behind any of them.
+
+use crate::app::EventSleuthApp;
+use crate::core::sigma::SigmaRule;
+use crate::ui::theme;
+use crate::util::time;
+
+/// Most recent correlated chains listed in the window.
+const MAX_CHAINS_SHOWN: usize = 200;
+
+impl EventSleuthApp {
+    /// Render the floating detections window.
+    ///
+    /// Visibility is controlled by `self.show_detections`.
+    pub fn render_detections_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_detections {
+            return;
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let mut pick_dir = false;
+        let mut reload = false;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F6E1} Detections")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(420.0)
+            .default_height(max_h.min(480.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                // ── Rule source ─────────────────────────────────────
+                ui.horizontal(|ui| {
+                    let loading = self.sigma_rx.is_some();
+                    if ui
+                        .add_enabled(
+                            !loading,
+                            egui::Button::new("\u{1F4C2} Load rules folder..."),
+                        )
+                        .on_hover_text("Load every Sigma .yml/.yaml rule under a folder")
+                        .clicked()
+                    {
+                        pick_dir = true;
+                    }
+                    if self.sigma_rules_dir.is_some()
+                        && ui
+                            .add_enabled(!loading, egui::Button::new("\u{1F504}"))
+                            .on_hover_text("Reload rules from the same folder")
+                            .clicked()
+                    {
+                        reload = true;
+                    }
+                    if loading {
+                        ui.spinner();
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .button("\u{1F5FA} ATT&CK matrix")
+                            .on_hover_text("Tactics and techniques observed in the loaded events")
+                            .clicked()
+                        {
+                            self.show_attack = true;
+                        }
+                    });
+                });
+                if let Some(dir) = &self.sigma_rules_dir {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} rules from {}",
+                            self.sigma_rules.len(),
+                            dir.display()
+                        ))
+                        .color(theme::text_dim(dark))
+                        .small(),
+                    );
+                }
+
+                ui.add_space(theme::ITEM_SPACING);
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        // ── Built-in pack ───────────────────────────
+                        let builtin_total: usize = self.builtin_hits.iter().map(Vec::len).sum();
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(format!(
+                                "\u{1F9F0} Built-in pack ({builtin_total})"
+                            ))
+                            .strong(),
+                        )
+                        .id_salt("detections_builtin")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if let Some(i) = rule_hit_list(
+                                ui,
+                                &self.builtin_rules,
+                                &self.builtin_hits,
+                                "No built-in rule matched the loaded events",
+                                dark,
+                            ) {
+                                filter_to = Some((
+                                    format!("Built-in: {}", self.builtin_rules[i].title),
+                                    self.builtin_hits[i].clone(),
+                                ));
+                            }
+                        });
+
+                        ui.add_space(theme::SECTION_SPACING);
+
+                        // ── User-supplied Sigma rules ───────────────
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(format!(
+                                "\u{1F4DC} Sigma rules ({})",
+                                self.sigma_hit_count()
+                            ))
+                            .strong(),
+                        )
+                        .id_salt("detections_sigma")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            let empty = if self.sigma_rules.is_empty() {
+                                "No rules loaded"
+                            } else {
+                                "No rule matched the loaded events"
+                            };
+                            if let Some(i) =
+                                rule_hit_list(ui, &self.sigma_rules, &self.sigma_hits, empty, dark)
+                            {
+                                filter_to = Some((
+                                    format!("Sigma: {}", self.sigma_rules[i].title),
+                                    self.sigma_hits[i].clone(),
+                                ));
+                            }
+                        });
+
+                        ui.add_space(theme::SECTION_SPACING);
+
+                        // ── Correlated chains, newest first ─────────
+                        let chains = self.correlation_engine.matches();
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(format!(
+                                "\u{1F517} Correlated chains ({})",
+                                chains.len()
+                            ))
+                            .strong(),
+                        )
+                        .id_salt("detections_chains")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if chains.is_empty() {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "No sequence completed ({} correlation rules)",
+                                        self.correlation_rules.len()
+                                    ))
+                                    .color(theme::text_dim(dark))
+                                    .italics(),
+                                );
+                            }
+                            for chain in chains.iter().rev().take(MAX_CHAINS_SHOWN) {
+                                let Some(rule) = self.correlation_rules.get(chain.rule) else {
+                                    continue;
+                                };
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(rule.level.label())
+                                            .color(theme::level_color(
+                                                rule.level.event_level(),
+                                                dark,
+                                            ))
+                                            .strong()
+                                            .small(),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(&rule.title)
+                                            .color(theme::text_primary(dark)),
+                                    )
+                                    .on_hover_text(rule_hover(
+                                        &rule.description,
+                                        &rule.id,
+                                        &rule.path,
+                                    ));
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            if ui
+                                                .small_button("Filter")
+                                                .on_hover_text("Show only the events in this chain")
+                                                .clicked()
+                                            {
+                                                filter_to = Some((
+                                                    format!(
+                                                        "Chain: {} ({})",
+                                                        rule.title, chain.key
+                                                    ),
+                                                    chain.events.clone(),
+                                                ));
+                                            }
+                                        },
+                                    );
+                                });
+                                let key = if chain.key.is_empty() {
+                                    String::new()
+                                } else {
+                                    format!("{} \u{2022} ", chain.key)
+                                };
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{key}{} events \u{2022} {} \u{2013} {}",
+                                        chain.events.len(),
+                                        time::format_table_timestamp(&chain.first),
+                                        time::format_table_timestamp(&chain.last),
+                                    ))
+                                    .color(theme::text_dim(dark))
+                                    .small(),
+                                );
+                                let tags: Vec<&str> = rule.attack_tags().collect();
+                                if !tags.is_empty() {
+                                    ui.label(
+                                        egui::RichText::new(tags.join("  "))
+                                            .color(theme::text_dim(dark))
+                                            .monospace()
+                                            .small(),
+                                    );
+                                }
+                                ui.add_space(2.0);
+                            }
+                            if chains.len() > MAX_CHAINS_SHOWN {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "\u{2026} {} older chains not shown",
+                                        chains.len() - MAX_CHAINS_SHOWN
+                                    ))
+                                    .color(theme::text_dim(dark))
+                                    .italics()
+                                    .small(),
+                                );
+                            }
+                        });
+
+                        // ── Files that could not be loaded ──────────
+                        if !self.sigma_load_errors.is_empty() {
+                            ui.add_space(theme::SECTION_SPACING);
+                            egui::CollapsingHeader::new(
+                                egui::RichText::new(format!(
+                                    "\u{26A0} {} rule file(s) skipped",
+                                    self.sigma_load_errors.len()
+                                ))
+                                .color(theme::level_color(3, dark)),
+                            )
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                for (path, err) in &self.sigma_load_errors {
+                                    let name = path
+                                        .file_name()
+                                        .map(|n| n.to_string_lossy().into_owned())
+                                        .unwrap_or_else(|| path.display().to_string());
+                                    ui.label(
+                                        egui::RichText::new(format!("{name}: {err}"))
+                                            .color(theme::text_secondary(dark))
+                                            .small(),
+                                    )
+                                    .on_hover_text(path.display().to_string());
+                                }
+                            });
+                        }
+                    });
+            });
+
+        if let Some((label, hits)) = filter_to {
+            self.set_event_index_filter(label, &hits);
+        }
+        if pick_dir {
+            self.pick_sigma_rules_dir();
+        }
+        if reload {
+            if let Some(dir) = self.sigma_rules_dir.clone() {
+                self.load_sigma_rules(dir);
+            }
+        }
+        if !open {
+            self.show_detections = false;
+        }
+    }
+}
+
+/// List the rules in `rules` that have hits, most severe first. Returns
+/// the index of the rule whose "Filter" button was clicked.
+fn rule_hit_list(
+    ui: &mut egui::Ui,
+    rules: &[SigmaRule],
+    hits: &[Vec<usize>],
+    empty_text: &str,
+    dark: bool,
+) -> Option<usize> {
+    let mut order: Vec<usize> = (0..rules.len())
+        .filter(|&i| hits.get(i).is_some_and(|h| !h.is_empty()))
+        .collect();
+    order.sort_by(|&a, &b| {
+        rules[b]
+            .level
+            .cmp(&rules[a].level)
+            .then(hits[b].len().cmp(&hits[a].len()))
+    });
+
+    if order.is_empty() {
+        ui.label(
+            egui::RichText::new(empty_text)
+                .color(theme::text_dim(dark))
+                .italics(),
+        );
+        return None;
+    }
+
+    let mut clicked = None;
+    for i in order {
+        let rule = &rules[i];
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(rule.level.label())
+                    .color(theme::level_color(rule.level.event_level(), dark))
+                    .strong()
+                    .small(),
+            );
+            let hover = rule_hover(&rule.description, &rule.id, &rule.path);
+            let title = ui.label(egui::RichText::new(&rule.title).color(theme::text_primary(dark)));
+            if !hover.is_empty() {
+                title.on_hover_text(hover);
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .small_button("Filter")
+                    .on_hover_text("Show only the events this rule matched")
+                    .clicked()
+                {
+                    clicked = Some(i);
+                }
+                ui.label(
+                    egui::RichText::new(hits[i].len().to_string())
+                        .color(theme::text_secondary(dark)),
+                );
+            });
+        });
+        let tags: Vec<&str> = rule.attack_tags().collect();
+        if !tags.is_empty() {
+            ui.label(
+                egui::RichText::new(tags.join("  "))
+                    .color(theme::text_dim(dark))
+                    .monospace()
+                    .small(),
+            );
+        }
+        ui.add_space(2.0);
+    }
+    clicked
+}
+
+/// Tooltip for a rule title: description, id and source file, skipping
+/// whichever are empty.
+fn rule_hover(description: &str, id: &str, path: &std::path::Path) -> String {
+    [
+        description.to_owned(),
+        id.to_owned(),
+        path.display().to_string(),
+    ]
+    .into_iter()
+    .filter(|s| !s.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -5,6 +5,8 @@
 //! visible rows are laid out, keeping performance smooth with 100k+ events.
 
 use crate::app::{EventSleuthApp, SortColumn};
+use crate::core::pivot::PivotField;
+use crate::ui::pivot_menu::pivot_menu_items;
 use crate::ui::theme;
 use crate::util::time::format_table_timestamp;
 use egui_extras::{Column, TableBuilder};
@@ -80,7 +82,7 @@ impl EventSleuthApp {
                         egui::RichText::new(format!(
                             "{} events loaded but hidden by {} active filter(s).",
                             self.all_events.len(),
-                            self.filter.active_count()
+                            self.active_filter_count()
                         ))
                         .color(theme::text_dim(self.dark_mode)),
                     );
@@ -90,16 +92,21 @@ impl EventSleuthApp {
                         .on_hover_text("Remove all active filters (Ctrl+Shift+X)")
                         .clicked()
                     {
-                        self.filter.clear();
-                        self.filter.parse_event_ids();
-                        self.filter.parse_time_range();
-                        self.needs_refilter = true;
+                        self.clear_all_filters();
                     }
                 }
             });
             return;
         }
 
+        if self.template_view.enabled {
+            self.render_template_table(ui);
+            return;
+        }
+        // One row per run of duplicates while collapsing.
+        let row_count = self.table_row_count();
+        let collapsed = self.duplicate_collapse.enabled;
+
         let table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
@@ -148,6 +155,10 @@ impl EventSleuthApp {
         // Collect bookmark toggles to apply after table rendering
         // (the row closure borrows self immutably for `all_events` access).
         let bookmark_toggle = std::cell::Cell::new(None::<usize>);
+        // Pivot chosen from a row's context menu, applied after the table
+        let pivot_request = std::cell::Cell::new(None);
+        let pivot_window = self.pivot_window_minutes;
+        let suggest_from = std::cell::Cell::new(None::<usize>);
 
         table
             .header(22.0, |mut header| {
@@ -213,13 +224,20 @@ impl EventSleuthApp {
             })
             .body(|body| {
                 body.rows(theme::TABLE_ROW_HEIGHT, row_count, |mut row| {
-                    let visible_idx = row.index();
+                    let visible_idx = self.table_row_start(row.index());
                     if visible_idx >= self.filtered_indices.len() {
                         return;
                     }
+                    let run = collapsed
+                        .then(|| self.duplicate_collapse.runs.get(row.index()).copied())
+                        .flatten()
+                        .filter(|r| r.len > 1);
                     let event_idx = self.filtered_indices[visible_idx];
                     let event = &self.all_events[event_idx];
-                    let is_selected = self.selected_event_idx == Some(visible_idx);
+                    let is_selected = match (run, self.selected_event_idx) {
+                        (Some(r), Some(sel)) => r.contains(sel),
+                        (_, sel) => sel == Some(visible_idx),
+                    };
                     let dark = self.dark_mode;
                     let level_color = theme::level_color(event.level, dark);
                     let is_bookmarked = self.bookmarked_indices.contains(&event_idx);
@@ -255,6 +273,34 @@ impl EventSleuthApp {
                         } else {
                             "Bookmark this event"
                         });
+                        // IOC marker
+                        if let Some(iocs) = self.ioc_scanner.event_hits(event_idx) {
+                            let values: Vec<&str> = iocs
+                                .iter()
+                                .map(|&i| self.ioc_scanner.indicators[i].value.as_str())
+                                .collect();
+                            ui.label(
+                                egui::RichText::new("\u{1F3AF}")
+                                    .small()
+                                    .color(theme::level_color(2, dark)),
+                            )
+                            .on_hover_text(format!("IOC match: {}", values.join(", ")));
+                        }
+                        // Collapsed duplicates
+                        if let Some(r) = run {
+                            ui.label(
+                                egui::RichText::new(format!("\u{D7}{}", r.len))
+                                    .small()
+                                    .strong()
+                                    .color(theme::accent(dark)),
+                            )
+                            .on_hover_text(format!(
+                                "{} consecutive duplicates\n{} \u{2192} {}",
+                                r.len,
+                                format_table_timestamp(&r.first),
+                                format_table_timestamp(&r.last)
+                            ));
+                        }
                     });
 
                     let cv = &self.column_visibility;
@@ -319,6 +365,17 @@ impl EventSleuthApp {
                     // messages are not incorrectly truncated short.
                     if cv.message {
                         row.col(|ui| {
+                            if let Some(r) = run {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "[{} \u{2192} {}]",
+                                        format_table_timestamp(&r.first),
+                                        format_table_timestamp(&r.last)
+                                    ))
+                                    .color(theme::text_dim(dark))
+                                    .small(),
+                                );
+                            }
                             let msg = event.display_message();
                             // char_indices().nth(200) is O(200) but the call
                             // occurs only for messages longer than 200 chars.
@@ -333,12 +390,77 @@ impl EventSleuthApp {
                         });
                     }
 
-                    if row.response().clicked() {
+                    let response = row.response();
+                    if response.clicked() {
                         self.selected_event_idx = Some(visible_idx);
                     }
+
+                    // Right-click: pivot on any of the row's fields
+                    response.context_menu(|ui| {
+                        let mut fields = vec![
+                            (
+                                PivotField::EventId,
+                                event.event_id.to_string(),
+                                event.event_id.to_string(),
+                            ),
+                            (
+                                PivotField::Level,
+                                event.level_name.clone(),
+                                event.level.to_string(),
+                            ),
+                            (
+                                PivotField::Provider,
+                                event.provider_name.clone(),
+                                event.provider_name.clone(),
+                            ),
+                            (
+                                PivotField::Channel,
+                                event.channel.clone(),
+                                event.channel.clone(),
+                            ),
+                            (
+                                PivotField::Computer,
+                                event.computer.clone(),
+                                event.computer.clone(),
+                            ),
+                        ];
+                        if let Some(ref sid) = event.user_sid {
+                            fields.push((PivotField::UserSid, sid.clone(), sid.clone()));
+                        }
+                        if ui
+                            .button("\u{2728} Suggest similar filter")
+                            .on_hover_text("Build a filter from this event and all pinned events")
+                            .clicked()
+                        {
+                            suggest_from.set(Some(visible_idx));
+                            ui.close_menu();
+                        }
+                        ui.separator();
+                        for (field, display, value) in fields {
+                            ui.menu_button(format!("{}: {display}", field.label()), |ui| {
+                                if let Some(req) = pivot_menu_items(
+                                    ui,
+                                    &field,
+                                    &value,
+                                    event.timestamp,
+                                    pivot_window,
+                                ) {
+                                    pivot_request.set(Some(req));
+                                }
+                            });
+                        }
+                    });
                 });
             });
 
+        if let Some(req) = pivot_request.take() {
+            self.apply_pivot_request(req);
+        }
+        if let Some(visible_idx) = suggest_from.get() {
+            self.selected_event_idx = Some(visible_idx);
+            self.open_filter_suggestion();
+        }
+
         // Apply deferred bookmark toggle
         if let Some(idx) = bookmark_toggle.get() {
             if self.bookmarked_indices.contains(&idx) {
@@ -0,0 +1,350 @@
+//! Field explorer window: EventData value distributions.
+//!
+//! Lists every EventData key in the filtered events with its coverage and
+//! number of distinct values; selecting a key shows its most and least
+//! frequent values. Profiling runs on a background thread over a shared
+//! snapshot of the event list. Clicking a value filters to it; the
+//! context menu can exclude it instead.
+
+use std::sync::Arc;
+
+use crossbeam_channel::Receiver;
+use egui_extras::{Column, TableBuilder};
+
+use crate::app::EventSleuthApp;
+use crate::core::field_stats::{self, FieldProfile, FieldSummary};
+use crate::core::pivot::{PivotAction, PivotField, PivotRequest};
+use crate::ui::theme;
+
+/// Latest profile, the pending background run and the window's view
+/// options.
+#[derive(Debug)]
+pub struct FieldExplorerView {
+    pub profile: FieldProfile,
+    /// Receives the result of the running profile, if any.
+    rx: Option<Receiver<FieldProfile>>,
+    /// Set when `filtered_indices` changed; a new profile starts on the
+    /// next render once the running one (if any) is done.
+    pub dirty: bool,
+    pub search: String,
+    /// Key whose values are shown.
+    selected: Option<String>,
+}
+
+impl Default for FieldExplorerView {
+    fn default() -> Self {
+        Self {
+            profile: FieldProfile::default(),
+            rx: None,
+            dirty: true,
+            search: String::new(),
+            selected: None,
+        }
+    }
+}
+
+/// One value list (top or bottom) of the selected key; returns the value
+/// clicked and whether it should be excluded rather than filtered to.
+fn value_list(
+    ui: &mut egui::Ui,
+    id: &str,
+    values: &[(String, usize)],
+    present: usize,
+    dark: bool,
+) -> Option<(String, bool)> {
+    let mut picked = None;
+    ui.push_id(id, |ui| {
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto().at_least(56.0)) // Count
+            .column(Column::auto().at_least(48.0)) // Share
+            .column(Column::remainder().clip(true)) // Value
+            .sense(egui::Sense::click())
+            .body(|body| {
+                body.rows(theme::TABLE_ROW_HEIGHT, values.len(), |mut row| {
+                    let (value, count) = &values[row.index()];
+                    row.col(|ui| {
+                        ui.label(
+                            egui::RichText::new(count.to_string())
+                                .color(theme::accent(dark))
+                                .strong(),
+                        );
+                    });
+                    row.col(|ui| {
+                        let pct = *count as f64 * 100.0 / present.max(1) as f64;
+                        ui.label(
+                            egui::RichText::new(format!("{pct:.1}%"))
+                                .color(theme::text_secondary(dark))
+                                .small(),
+                        );
+                    });
+                    row.col(|ui| {
+                        let text = if value.is_empty() { "(empty)" } else { value };
+                        ui.label(egui::RichText::new(text).color(theme::text_primary(dark)))
+                            .on_hover_text(value);
+                    });
+                    let response = row.response();
+                    if response.clicked() {
+                        picked = Some((value.clone(), false));
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button("Filter to this value").clicked() {
+                            picked = Some((value.clone(), false));
+                            ui.close_menu();
+                        }
+                        if ui.button("Exclude this value").clicked() {
+                            picked = Some((value.clone(), true));
+                            ui.close_menu();
+                        }
+                    });
+                });
+            });
+    });
+    picked
+}
+
+impl EventSleuthApp {
+    /// Collect a finished profile and start a new one if the filtered
+    /// events changed.
+    fn refresh_field_profile(&mut self, ctx: &egui::Context) {
+        let view = &mut self.field_view;
+        if let Some(rx) = &view.rx {
+            match rx.try_recv() {
+                Ok(profile) => {
+                    view.profile = profile;
+                    view.rx = None;
+                }
+                Err(crossbeam_channel::TryRecvError::Disconnected) => view.rx = None,
+                Err(crossbeam_channel::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    return;
+                }
+            }
+        }
+        if !view.dirty {
+            return;
+        }
+        view.dirty = false;
+        let events = Arc::clone(&self.all_events);
+        let indices = self.filtered_indices.clone();
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        view.rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(field_stats::profile(&events, &indices));
+        });
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    /// Render the floating field explorer window.
+    ///
+    /// Visibility is controlled by `self.show_field_explorer`.
+    pub fn render_field_explorer(&mut self, ctx: &egui::Context) {
+        if !self.show_field_explorer {
+            return;
+        }
+        self.refresh_field_profile(ctx);
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut pivot: Option<(String, String, bool)> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F9EC} Field Explorer")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(820.0)
+            .default_height(max_h.min(480.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.field_view;
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut view.search)
+                            .hint_text("Filter keys")
+                            .desired_width(200.0),
+                    );
+                    if view.rx.is_some() {
+                        ui.spinner();
+                        ui.label(
+                            egui::RichText::new("Profiling\u{2026}")
+                                .color(theme::text_dim(dark))
+                                .small(),
+                        );
+                    }
+                });
+                let profile = &view.profile;
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} EventData keys across {} filtered events",
+                        profile.fields.len(),
+                        profile.total
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+                if profile.fields.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No EventData in the filtered events")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let needle = view.search.trim().to_lowercase();
+                let keys: Vec<usize> = (0..profile.fields.len())
+                    .filter(|&i| {
+                        needle.is_empty() || profile.fields[i].key.to_lowercase().contains(&needle)
+                    })
+                    .collect();
+                let selected: Option<&FieldSummary> = view
+                    .selected
+                    .as_ref()
+                    .and_then(|k| profile.fields.iter().find(|f| &f.key == k));
+                let mut select: Option<String> = None;
+
+                ui.columns(2, |cols| {
+                    cols[0].push_id("field_keys", |ui| {
+                        TableBuilder::new(ui)
+                            .striped(true)
+                            .resizable(true)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(Column::remainder().at_least(140.0).clip(true)) // Key
+                            .column(Column::auto().at_least(70.0)) // Coverage
+                            .column(Column::auto().at_least(60.0)) // Distinct
+                            .sense(egui::Sense::click())
+                            .header(20.0, |mut header| {
+                                for title in ["Key", "Coverage", "Distinct"] {
+                                    header.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(title)
+                                                .color(theme::text_primary(dark))
+                                                .strong(),
+                                        );
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(theme::TABLE_ROW_HEIGHT, keys.len(), |mut row| {
+                                    let field = &profile.fields[keys[row.index()]];
+                                    row.set_selected(selected.is_some_and(|s| s.key == field.key));
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&field.key)
+                                                .color(theme::text_primary(dark)),
+                                        );
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "{:.1}%",
+                                                field.coverage(profile.total)
+                                            ))
+                                            .color(theme::text_secondary(dark)),
+                                        );
+                                    });
+                                    row.col(|ui| {
+                                        let text = if field.capped {
+                                            format!("{}+", field.distinct)
+                                        } else {
+                                            field.distinct.to_string()
+                                        };
+                                        ui.label(
+                                            egui::RichText::new(text)
+                                                .color(theme::text_secondary(dark)),
+                                        );
+                                    });
+                                    if row.response().clicked() {
+                                        select = Some(field.key.clone());
+                                    }
+                                });
+                            });
+                    });
+
+                    let ui = &mut cols[1];
+                    let Some(field) = selected else {
+                        ui.label(
+                            egui::RichText::new("Select a key to see its values")
+                                .color(theme::text_dim(dark))
+                                .italics(),
+                        );
+                        return;
+                    };
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} \u{2014} {} events, {} distinct values",
+                            field.key, field.present, field.distinct
+                        ))
+                        .color(theme::text_primary(dark))
+                        .strong(),
+                    );
+                    let half = (ui.available_height() / 2.0 - 30.0).max(80.0);
+                    ui.label(
+                        egui::RichText::new("\u{2B06} Most frequent")
+                            .color(theme::text_secondary(dark)),
+                    );
+                    egui::ScrollArea::vertical()
+                        .id_salt("field_top")
+                        .max_height(half)
+                        .show(ui, |ui| {
+                            if let Some((v, exclude)) =
+                                value_list(ui, "field_top_values", &field.top, field.present, dark)
+                            {
+                                pivot = Some((field.key.clone(), v, exclude));
+                            }
+                        });
+                    ui.add_space(theme::ITEM_SPACING);
+                    ui.label(
+                        egui::RichText::new("\u{2B07} Least frequent")
+                            .color(theme::text_secondary(dark)),
+                    );
+                    if field.capped {
+                        ui.label(
+                            egui::RichText::new(
+                                "Too many distinct values; the rare tail is approximate",
+                            )
+                            .color(theme::level_color(3, dark))
+                            .small(),
+                        );
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_salt("field_bottom")
+                        .max_height(half)
+                        .show(ui, |ui| {
+                            if let Some((v, exclude)) = value_list(
+                                ui,
+                                "field_bottom_values",
+                                &field.bottom,
+                                field.present,
+                                dark,
+                            ) {
+                                pivot = Some((field.key.clone(), v, exclude));
+                            }
+                        });
+                });
+                if select.is_some() {
+                    view.selected = select;
+                }
+            });
+
+        if let Some((key, value, exclude)) = pivot {
+            self.apply_pivot_request(PivotRequest {
+                field: PivotField::EventData(key),
+                value,
+                action: if exclude {
+                    PivotAction::Exclude
+                } else {
+                    PivotAction::FilterTo
+                },
+                at: chrono::Utc::now(),
+            });
+        }
+        if !open {
+            self.show_field_explorer = false;
+        }
+    }
+}
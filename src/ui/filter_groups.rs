@@ -0,0 +1,156 @@
+//! Filter-group bar shown near the top of the filter panel.
+//!
+//! Lists the primary filter plus any extra OR / exclude groups (see
+//! [`crate::core::filter_group`]). Selecting a row makes the criteria
+//! sections below edit that group; each extra group can be switched
+//! between include and exclude, disabled, or removed.
+
+use crate::app::EventSleuthApp;
+use crate::core::filter_group::{FilterGroup, GroupMode};
+use crate::ui::theme;
+
+/// Maximum characters of a group summary shown inline before truncating.
+const MAX_SUMMARY_CHARS: usize = 48;
+
+/// Shorten `s` to [`MAX_SUMMARY_CHARS`] characters, appending an ellipsis.
+fn truncate_summary(s: &str) -> String {
+    if s.chars().count() <= MAX_SUMMARY_CHARS {
+        return s.to_owned();
+    }
+    let mut out: String = s.chars().take(MAX_SUMMARY_CHARS - 3).collect();
+    out.push_str("...");
+    out
+}
+
+impl EventSleuthApp {
+    /// Render the filter-group list and the "add group" controls.
+    ///
+    /// Changes that affect matching (mode, enable, delete) set
+    /// `needs_refilter` directly; selecting a row only changes
+    /// `editing_group`.
+    pub fn render_filter_groups(&mut self, ui: &mut egui::Ui) {
+        let dark = self.dark_mode;
+
+        // Guard against a stale index (e.g. after a preset load).
+        if self
+            .editing_group
+            .is_some_and(|i| i >= self.filter_groups.len())
+        {
+            self.editing_group = None;
+        }
+
+        egui::CollapsingHeader::new(egui::RichText::new("\u{1F500} Filter Groups").strong())
+            .default_open(!self.filter_groups.is_empty())
+            .show(ui, |ui| {
+                // Primary filter row
+                let primary_summary = truncate_summary(&self.filter.summary());
+                if ui
+                    .selectable_label(
+                        self.editing_group.is_none(),
+                        egui::RichText::new(format!("Primary: {primary_summary}"))
+                            .color(theme::text_primary(dark)),
+                    )
+                    .on_hover_text(
+                        "The main filter. Click to edit it in the sections below.",
+                    )
+                    .clicked()
+                {
+                    self.editing_group = None;
+                }
+
+                let mut delete_idx: Option<usize> = None;
+                for i in 0..self.filter_groups.len() {
+                    let selected = self.editing_group == Some(i);
+                    ui.horizontal(|ui| {
+                        let group = &mut self.filter_groups[i];
+                        if ui
+                            .checkbox(&mut group.enabled, "")
+                            .on_hover_text("Enable or disable this group")
+                            .changed()
+                        {
+                            self.needs_refilter = true;
+                        }
+                        egui::ComboBox::from_id_salt(("filter_group_mode", i))
+                            .width(90.0)
+                            .selected_text(group.mode.label())
+                            .show_ui(ui, |ui| {
+                                for mode in [GroupMode::Include, GroupMode::Exclude] {
+                                    if ui
+                                        .selectable_value(&mut group.mode, mode, mode.label())
+                                        .changed()
+                                    {
+                                        self.needs_refilter = true;
+                                    }
+                                }
+                            });
+                        if ui
+                            .small_button("\u{1F5D1}")
+                            .on_hover_text("Remove this group")
+                            .clicked()
+                        {
+                            delete_idx = Some(i);
+                        }
+                    });
+                    let group = &self.filter_groups[i];
+                    let color = if !group.enabled {
+                        theme::text_dim(dark)
+                    } else if group.mode == GroupMode::Exclude {
+                        theme::level_color(2, dark)
+                    } else {
+                        theme::text_primary(dark)
+                    };
+                    let summary = truncate_summary(&group.state.summary());
+                    if ui
+                        .selectable_label(
+                            selected,
+                            egui::RichText::new(format!("  #{}: {summary}", i + 1)).color(color),
+                        )
+                        .on_hover_text("Click to edit this group in the sections below")
+                        .clicked()
+                    {
+                        self.editing_group = Some(i);
+                    }
+                }
+
+                if let Some(idx) = delete_idx {
+                    self.filter_groups.remove(idx);
+                    self.editing_group = match self.editing_group {
+                        Some(e) if e == idx => None,
+                        Some(e) if e > idx => Some(e - 1),
+                        other => other,
+                    };
+                    self.needs_refilter = true;
+                }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .small_button("\u{2795} OR group")
+                        .on_hover_text(
+                            "Add a group whose matches are shown in addition\nto the primary filter's matches",
+                        )
+                        .clicked()
+                    {
+                        self.filter_groups.push(FilterGroup::new(GroupMode::Include));
+                        self.editing_group = Some(self.filter_groups.len() - 1);
+                    }
+                    if ui
+                        .small_button("\u{2796} Exclude group")
+                        .on_hover_text("Add a group whose matches are always hidden")
+                        .clicked()
+                    {
+                        self.filter_groups.push(FilterGroup::new(GroupMode::Exclude));
+                        self.editing_group = Some(self.filter_groups.len() - 1);
+                    }
+                });
+
+                if let Some(i) = self.editing_group {
+                    ui.label(
+                        egui::RichText::new(format!("Editing group #{} below", i + 1))
+                            .color(theme::accent(dark))
+                            .small()
+                            .strong(),
+                    );
+                }
+            });
+    }
+}
@@ -1,6 +1,6 @@
 //! Left-side filter panel: Event ID, level, provider, text search,
 //! time range, case sensitivity toggle, apply/clear, time presets,
-//! and saved filter preset management.
+//! filter groups, undo/redo history, and saved filter preset management.
 //!
 //! Sections use `CollapsingHeader` so users can collapse areas they are
 //! not actively using, reducing visual noise.  An active-filter banner
@@ -21,7 +21,7 @@ impl EventSleuthApp {
         // ── Active-filter summary banner ────────────────────────────
         // Shown only when at least one filter is active so users always
         // know the table is narrowed.
-        if !self.filter.is_empty() {
+        if self.has_active_filters() {
             egui::Frame::new()
                 .fill(theme::filter_active_bg(dark))
                 .inner_margin(egui::Margin::same(6))
@@ -30,7 +30,7 @@ impl EventSleuthApp {
                     ui.horizontal(|ui| {
                         theme::badge(
                             ui,
-                            self.filter.active_count(),
+                            self.active_filter_count(),
                             theme::accent(dark),
                             egui::Color32::WHITE,
                         );
@@ -45,20 +45,53 @@ impl EventSleuthApp {
             ui.add_space(theme::SECTION_SPACING);
         }
 
+        // ── Event-index restriction (e.g. a detection's hits) ──────
+        if let Some(f) = &self.event_index_filter {
+            let mut clear = false;
+            egui::Frame::new()
+                .fill(theme::filter_active_bg(dark))
+                .inner_margin(egui::Margin::same(6))
+                .corner_radius(4.0)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "\u{1F3AF} {} ({} events)",
+                                f.label,
+                                f.indices.len()
+                            ))
+                            .color(theme::accent(dark))
+                            .small()
+                            .strong(),
+                        );
+                        if ui
+                            .small_button("\u{2716}")
+                            .on_hover_text("Show all events matching the filter again")
+                            .clicked()
+                        {
+                            clear = true;
+                        }
+                    });
+                });
+            if clear {
+                self.clear_event_index_filter();
+            }
+            ui.add_space(theme::SECTION_SPACING);
+        }
+
         // ── Heading row with Clear All always visible ──────────────
         ui.horizontal(|ui| {
             ui.heading(egui::RichText::new("\u{1F50D} Filters").color(theme::accent(dark)));
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                if !self.filter.is_empty()
+                if (self.has_active_filters() || !self.filter_groups.is_empty())
                     && ui
                         .small_button("\u{2716} Clear all")
-                        .on_hover_text("Reset every filter to its default (Ctrl+Shift+X)")
+                        .on_hover_text(
+                            "Reset every filter to its default and remove all groups (Ctrl+Shift+X)",
+                        )
                         .clicked()
                 {
-                    self.filter.clear();
-                    self.filter.parse_event_ids();
-                    self.filter.parse_time_range();
-                    self.needs_refilter = true;
+                    self.clear_all_filters();
                 }
             });
         });
@@ -67,6 +100,8 @@ impl EventSleuthApp {
 
         // ── Preset controls (inline) ────────────────────────────────
         ui.horizontal(|ui| {
+            self.render_filter_history_controls(ui);
+
             ui.menu_button("\u{1F4C2} Presets", |ui| {
                 if self.filter_presets.is_empty() {
                     ui.label(
@@ -92,8 +127,7 @@ impl EventSleuthApp {
                         });
                     }
                     if let Some(idx) = load_idx {
-                        self.filter = self.filter_presets[idx].to_filter_state();
-                        self.needs_refilter = true;
+                        self.load_preset(idx);
                         ui.close_menu();
                     }
                     if let Some(idx) = delete_idx {
@@ -113,11 +147,24 @@ impl EventSleuthApp {
 
         ui.add_space(theme::ITEM_SPACING);
 
+        // ── Filter groups ───────────────────────────────────────────
+        self.render_filter_groups(ui);
+
+        ui.add_space(theme::ITEM_SPACING);
+
         // Track whether any immediate (non-debounced) change occurs
         let mut changed = false;
         // Track whether any text field was edited (debounced)
         let mut text_changed = false;
 
+        // The criteria sections below always bind to `self.filter`. When an
+        // extra group is selected, swap its state in for the duration of
+        // those sections and swap it back afterwards.
+        let editing = self.editing_group;
+        if let Some(i) = editing {
+            std::mem::swap(&mut self.filter, &mut self.filter_groups[i].state);
+        }
+
         // ── Event ID ────────────────────────────────────────────────
         egui::CollapsingHeader::new(
             egui::RichText::new("\u{1F194} Event ID").strong(),
@@ -225,6 +272,44 @@ impl EventSleuthApp {
 
         ui.add_space(theme::ITEM_SPACING);
 
+        // ── Field pivots ────────────────────────────────────────────
+        // Clauses are only added from the table / detail context menus,
+        // so the section is hidden until there is something to show.
+        if !self.filter.field_clauses.is_empty() {
+            egui::CollapsingHeader::new(egui::RichText::new("\u{1F4CC} Pivots").strong())
+                .default_open(true)
+                .show(ui, |ui| {
+                    let mut remove_idx: Option<usize> = None;
+                    for (i, clause) in self.filter.field_clauses.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .small_button("\u{2716}")
+                                .on_hover_text("Remove this clause")
+                                .clicked()
+                            {
+                                remove_idx = Some(i);
+                            }
+                            let color = if clause.exclude {
+                                theme::level_color(2, dark)
+                            } else {
+                                theme::text_primary(dark)
+                            };
+                            ui.label(egui::RichText::new(clause.describe()).color(color));
+                        });
+                    }
+                    if let Some(idx) = remove_idx {
+                        self.filter.field_clauses.remove(idx);
+                        changed = true;
+                    }
+                    if ui.small_button("Clear pivots").clicked() {
+                        self.filter.field_clauses.clear();
+                        changed = true;
+                    }
+                });
+
+            ui.add_space(theme::ITEM_SPACING);
+        }
+
         // ── Text search ─────────────────────────────────────────────
         egui::CollapsingHeader::new(
             egui::RichText::new("\u{1F50E} Search").strong(),
@@ -240,14 +325,58 @@ impl EventSleuthApp {
                 text_changed = true;
             }
             search_response.on_hover_text(
-                "Full-text search across all event fields:\nMessage, Provider, Event ID, Event Data, etc.",
+                "Full-text search across the fields chosen in \"Scope\".\nDefault: Message, Provider, Channel, Event Data, and raw XML.",
             );
+
+            // Search scope selector
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("Scope").color(theme::text_dim(dark)));
+                egui::ComboBox::from_id_salt("search_scope_combo")
+                    .selected_text(self.filter.search_scope.label())
+                    .show_ui(ui, |ui| {
+                        for scope in crate::core::filter::SearchScope::ALL {
+                            if ui
+                                .selectable_value(&mut self.filter.search_scope, scope, scope.label())
+                                .changed()
+                            {
+                                changed = true;
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "Limit which fields the search looks at.\nUse \"Message only\" or \"EventData values\" to avoid\nmatching XML markup such as \"System\" or \"Data\".",
+                    );
+            });
+            if self.filter.search_scope == crate::core::filter::SearchScope::SpecificKeys {
+                let keys_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.filter.search_keys)
+                        .hint_text("e.g. TargetUserName, IpAddress")
+                        .desired_width(f32::INFINITY),
+                );
+                if keys_response.changed() {
+                    text_changed = true;
+                }
+                keys_response.on_hover_text(
+                    "Comma-separated EventData key names to search.\nKey names are case-insensitive. Leave empty to search every key.",
+                );
+            }
+
             if ui
                 .checkbox(&mut self.filter.case_sensitive, "Case sensitive")
                 .changed()
             {
                 changed = true;
             }
+            if ui
+                .checkbox(&mut self.filter.whole_word, "Whole word")
+                .on_hover_text(
+                    "Only match complete words.\nExample: \"Data\" matches \"Data\" but not \"EventData\".",
+                )
+                .changed()
+            {
+                changed = true;
+            }
             if ui
                 .checkbox(&mut self.filter.use_regex, "Regex")
                 .on_hover_text(
@@ -350,6 +479,14 @@ impl EventSleuthApp {
                 });
         });
 
+        if let Some(i) = editing {
+            if changed {
+                self.filter.parse_event_ids();
+                self.filter.parse_time_range();
+            }
+            std::mem::swap(&mut self.filter, &mut self.filter_groups[i].state);
+        }
+
         ui.add_space(theme::SECTION_SPACING);
 
         // ── Bookmarks toggle ────────────────────────────────────────
@@ -444,6 +581,22 @@ impl EventSleuthApp {
                         .small(),
                 );
             }
+
+            ui.add_space(theme::ITEM_SPACING);
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new("Pivot window \u{00B1}")
+                        .color(theme::text_dim(dark)),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut self.pivot_window_minutes)
+                        .range(1..=24 * 60)
+                        .suffix(" min"),
+                )
+                .on_hover_text(
+                    "Half-width of the \"same value within \u{00B1}N minutes\"\nright-click pivot in the table and detail panel",
+                );
+            });
         });
 
         ui.add_space(theme::SECTION_SPACING);
@@ -484,4 +637,69 @@ impl EventSleuthApp {
             self.debounce_timer = Some(std::time::Instant::now());
         }
     }
+
+    /// Undo / redo buttons and the history dropdown, shown beside the
+    /// Presets menu.
+    fn render_filter_history_controls(&mut self, ui: &mut egui::Ui) {
+        let dark = self.dark_mode;
+        if ui
+            .add_enabled(
+                self.filter_history.can_undo(),
+                egui::Button::new("\u{21B6}").small(),
+            )
+            .on_hover_text("Undo filter change (Ctrl+Z)")
+            .clicked()
+        {
+            self.undo_filter();
+        }
+        if ui
+            .add_enabled(
+                self.filter_history.can_redo(),
+                egui::Button::new("\u{21B7}").small(),
+            )
+            .on_hover_text("Redo filter change (Ctrl+Y)")
+            .clicked()
+        {
+            self.redo_filter();
+        }
+
+        let mut jump: Option<usize> = None;
+        ui.menu_button("\u{1F552} History", |ui| {
+            let entries = self.filter_history.entries();
+            if entries.is_empty() {
+                ui.label(
+                    egui::RichText::new("No filter history yet")
+                        .color(theme::text_dim(dark))
+                        .italics(),
+                );
+                return;
+            }
+            let cursor = self.filter_history.cursor();
+            egui::ScrollArea::vertical()
+                .max_height(320.0)
+                .show(ui, |ui| {
+                    // Newest first
+                    for (i, entry) in entries.iter().enumerate().rev() {
+                        let text = egui::RichText::new(format!(
+                            "{}  ({} results)",
+                            entry.summary, entry.result_count
+                        ))
+                        .color(if i > cursor {
+                            theme::text_dim(dark)
+                        } else {
+                            theme::text_primary(dark)
+                        });
+                        if ui.selectable_label(i == cursor, text).clicked() {
+                            jump = Some(i);
+                            ui.close_menu();
+                        }
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Earlier filters this session, with their result counts");
+        if let Some(idx) = jump {
+            self.jump_to_filter_history(idx);
+        }
+    }
 }
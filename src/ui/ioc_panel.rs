@@ -0,0 +1,191 @@
+//! IOC window: imported indicator lists and the events they matched.
+//!
+//! Lists every indicator with hits (kind, value, source list, event count)
+//! and lets the user narrow the event table to the events behind one
+//! indicator or all of them. Matching rows are also marked in the event
+//! table.
+
+use crate::app::EventSleuthApp;
+use crate::ui::theme;
+
+/// Most indicators with hits listed in the window (by hit count).
+const MAX_INDICATORS_SHOWN: usize = 500;
+
+impl EventSleuthApp {
+    /// Render the floating IOC results window.
+    ///
+    /// Visibility is controlled by `self.show_iocs`.
+    pub fn render_ioc_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_iocs {
+            return;
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let mut pick = false;
+        let mut clear = false;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F3AF} IOC Matches")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(440.0)
+            .default_height(max_h.min(480.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let scanner = &self.ioc_scanner;
+
+                // ── Indicator source ────────────────────────────────
+                ui.horizontal(|ui| {
+                    let loading = self.ioc_rx.is_some();
+                    if ui
+                        .add_enabled(!loading, egui::Button::new("\u{1F4C2} Import lists..."))
+                        .on_hover_text(
+                            "Add indicators from plain text (one per line), CSV or STIX 2.1 JSON",
+                        )
+                        .clicked()
+                    {
+                        pick = true;
+                    }
+                    if !scanner.indicators.is_empty()
+                        && ui
+                            .add_enabled(!loading, egui::Button::new("\u{2716} Clear"))
+                            .on_hover_text("Unload all indicators")
+                            .clicked()
+                    {
+                        clear = true;
+                    }
+                    if loading {
+                        ui.spinner();
+                    }
+                });
+
+                let mut sources: Vec<&str> = scanner
+                    .indicators
+                    .iter()
+                    .map(|i| i.source.as_str())
+                    .collect();
+                sources.dedup();
+                ui.label(
+                    egui::RichText::new(if scanner.indicators.is_empty() {
+                        "No indicators loaded".to_owned()
+                    } else {
+                        format!(
+                            "{} indicators from {}; {} events matched",
+                            scanner.indicators.len(),
+                            sources.join(", "),
+                            scanner.hit_event_count()
+                        )
+                    })
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                for (path, reason) in &self.ioc_load_errors {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "\u{26A0} {}: {reason}",
+                            path.file_name()
+                                .map(|n| n.to_string_lossy())
+                                .unwrap_or_default()
+                        ))
+                        .color(theme::level_color(2, dark))
+                        .small(),
+                    );
+                }
+
+                if scanner.hit_event_count() > 0
+                    && ui
+                        .button("Show all matching events")
+                        .on_hover_text("Filter the table to every event that matched an indicator")
+                        .clicked()
+                {
+                    filter_to = Some(("IOC matches".to_owned(), scanner.hit_events()));
+                }
+
+                ui.add_space(theme::ITEM_SPACING);
+                ui.separator();
+
+                // ── Indicators with hits, most hits first ───────────
+                let mut order: Vec<usize> = (0..scanner.indicators.len())
+                    .filter(|&i| scanner.hits.get(i).is_some_and(|h| !h.is_empty()))
+                    .collect();
+                order.sort_by_key(|&i| std::cmp::Reverse(scanner.hits[i].len()));
+                let hidden = order.len().saturating_sub(MAX_INDICATORS_SHOWN);
+                order.truncate(MAX_INDICATORS_SHOWN);
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        if order.is_empty() && !scanner.indicators.is_empty() {
+                            ui.label(
+                                egui::RichText::new("No indicator matched the loaded events")
+                                    .color(theme::text_dim(dark))
+                                    .italics(),
+                            );
+                        }
+                        for i in order {
+                            let ioc = &scanner.indicators[i];
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(ioc.kind.label())
+                                        .color(theme::accent(dark))
+                                        .strong()
+                                        .small(),
+                                );
+                                ui.label(
+                                    egui::RichText::new(&ioc.value)
+                                        .color(theme::text_primary(dark))
+                                        .monospace(),
+                                )
+                                .on_hover_text(format!("From {}", ioc.source));
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .small_button("Filter")
+                                            .on_hover_text(
+                                                "Show only the events this indicator matched",
+                                            )
+                                            .clicked()
+                                        {
+                                            filter_to = Some((
+                                                format!("IOC: {}", ioc.value),
+                                                scanner.hits[i].clone(),
+                                            ));
+                                        }
+                                        ui.label(
+                                            egui::RichText::new(scanner.hits[i].len().to_string())
+                                                .color(theme::text_secondary(dark)),
+                                        );
+                                    },
+                                );
+                            });
+                        }
+                        if hidden > 0 {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "... and {hidden} more indicators with hits"
+                                ))
+                                .color(theme::text_dim(dark))
+                                .small(),
+                            );
+                        }
+                    });
+            });
+
+        if pick {
+            self.pick_ioc_files();
+        }
+        if clear {
+            self.clear_iocs();
+        }
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_iocs = false;
+        }
+    }
+}
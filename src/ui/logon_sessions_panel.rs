@@ -0,0 +1,287 @@
+//! Logon sessions window: sessions reconstructed from Security events.
+//!
+//! One row per session (start, duration, user, logon type, source,
+//! computer, flags) with search and "open only" / "hide failed" toggles.
+//! Each row links back to its underlying events in the main table.
+//! Sessions are rebuilt on a background thread when the event list
+//! changes.
+
+use std::sync::Arc;
+
+use crossbeam_channel::Receiver;
+
+use crate::app::EventSleuthApp;
+use crate::core::logon_session::{self, LogonSession, SessionStatus};
+use crate::ui::theme;
+use crate::util::time;
+use egui_extras::{Column, TableBuilder};
+
+/// Cached sessions and the window's view options.
+#[derive(Debug, Default)]
+pub struct LogonSessionView {
+    /// Reconstructed sessions, newest first.
+    pub sessions: Vec<LogonSession>,
+    /// `events_generation` of the sessions, or of the running rebuild.
+    pub built_for: Option<u64>,
+    /// Receives the result of the running rebuild, if any.
+    rx: Option<Receiver<Vec<LogonSession>>>,
+    /// Case-insensitive substring over user, source and computer.
+    pub search: String,
+    /// Show only sessions that never closed.
+    pub open_only: bool,
+    /// Hide failed logon attempts.
+    pub hide_failed: bool,
+}
+
+impl LogonSessionView {
+    fn shows(&self, s: &LogonSession, needle: &str) -> bool {
+        if self.open_only && !s.never_closed() {
+            return false;
+        }
+        if self.hide_failed && s.status == SessionStatus::Failed {
+            return false;
+        }
+        needle.is_empty()
+            || [
+                Some(&s.user),
+                Some(&s.computer),
+                s.source_ip.as_ref(),
+                s.workstation.as_ref(),
+                s.logon_id.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|v| v.to_lowercase().contains(needle))
+    }
+}
+
+impl EventSleuthApp {
+    /// Collect finished sessions and start a rebuild if the event list
+    /// changed.
+    fn refresh_logon_sessions(&mut self, ctx: &egui::Context) {
+        let view = &mut self.logon_view;
+        if let Some(rx) = &view.rx {
+            match rx.try_recv() {
+                Ok(sessions) => {
+                    view.sessions = sessions;
+                    view.rx = None;
+                }
+                Err(crossbeam_channel::TryRecvError::Disconnected) => view.rx = None,
+                Err(crossbeam_channel::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    return;
+                }
+            }
+        }
+        if view.built_for == Some(self.events_generation) {
+            return;
+        }
+        view.built_for = Some(self.events_generation);
+        let events = Arc::clone(&self.all_events);
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        view.rx = Some(rx);
+        std::thread::spawn(move || {
+            let _ = tx.send(logon_session::reconstruct(&events));
+        });
+        ctx.request_repaint_after(std::time::Duration::from_millis(100));
+    }
+
+    /// Render the floating logon sessions window.
+    ///
+    /// Visibility is controlled by `self.show_logon_sessions`.
+    pub fn render_logon_sessions_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_logon_sessions {
+            return;
+        }
+        self.refresh_logon_sessions(ctx);
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.75;
+
+        egui::Window::new("\u{1F464} Logon Sessions")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(820.0)
+            .default_height(max_h.min(460.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.logon_view;
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut view.search)
+                            .hint_text("User, IP, workstation, computer or logon ID")
+                            .desired_width(240.0),
+                    );
+                    ui.checkbox(&mut view.open_only, "Never closed only");
+                    ui.checkbox(&mut view.hide_failed, "Hide failed");
+                    if view.rx.is_some() {
+                        ui.spinner();
+                    }
+                });
+
+                let needle = view.search.trim().to_lowercase();
+                let rows: Vec<usize> = (0..view.sessions.len())
+                    .filter(|&i| view.shows(&view.sessions[i], &needle))
+                    .collect();
+                let never_closed = view.sessions.iter().filter(|s| s.never_closed()).count();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} of {} sessions shown, {never_closed} never closed \
+                         (from 4624/4625/4634/4647/4648/4672)",
+                        rows.len(),
+                        view.sessions.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if view.sessions.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No logon events in the loaded Security log")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let sessions = &view.sessions;
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::auto().at_least(135.0)) // Start
+                    .column(Column::auto().at_least(70.0)) // Duration
+                    .column(Column::auto().at_least(150.0).clip(true)) // User
+                    .column(Column::auto().at_least(110.0).clip(true)) // Type
+                    .column(Column::auto().at_least(110.0).clip(true)) // Source
+                    .column(Column::auto().at_least(90.0).clip(true)) // Computer
+                    .column(Column::auto().at_least(60.0)) // Flags
+                    .column(Column::remainder().at_least(60.0)) // Events
+                    .header(20.0, |mut header| {
+                        for title in [
+                            "Start", "Duration", "User", "Type", "Source", "Computer", "Flags",
+                            "Events",
+                        ] {
+                            header.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(title)
+                                        .color(theme::text_primary(dark))
+                                        .strong(),
+                                );
+                            });
+                        }
+                    })
+                    .body(|body| {
+                        body.rows(theme::TABLE_ROW_HEIGHT, rows.len(), |mut row| {
+                            let s = &sessions[rows[row.index()]];
+                            row.col(|ui| {
+                                let start = s
+                                    .start
+                                    .map(|t| time::format_table_timestamp(&t))
+                                    .unwrap_or_else(|| "(before log)".to_owned());
+                                ui.label(
+                                    egui::RichText::new(start)
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                            });
+                            row.col(|ui| {
+                                let text = match (s.status, s.duration()) {
+                                    (SessionStatus::Failed, _) => "failed".to_owned(),
+                                    (_, Some(d)) => time::format_span(d),
+                                    (SessionStatus::Open, None) => "open".to_owned(),
+                                    (_, None) => "?".to_owned(),
+                                };
+                                let color = match s.status {
+                                    SessionStatus::Failed => theme::level_color(2, dark),
+                                    SessionStatus::Open => theme::level_color(3, dark),
+                                    SessionStatus::Closed => theme::text_secondary(dark),
+                                };
+                                ui.label(egui::RichText::new(text).color(color));
+                            });
+                            row.col(|ui| {
+                                let label = ui.label(
+                                    egui::RichText::new(&s.user).color(theme::text_primary(dark)),
+                                );
+                                if let Some(id) = &s.logon_id {
+                                    label.on_hover_text(format!("Logon ID {id}"));
+                                }
+                            });
+                            row.col(|ui| {
+                                if let Some(t) = s.logon_type {
+                                    ui.label(format!("{t} {}", logon_session::logon_type_name(t)));
+                                }
+                            });
+                            row.col(|ui| {
+                                let source = match (&s.source_ip, &s.workstation) {
+                                    (Some(ip), Some(ws)) => format!("{ip} ({ws})"),
+                                    (Some(v), None) | (None, Some(v)) => v.clone(),
+                                    (None, None) => String::new(),
+                                };
+                                ui.label(
+                                    egui::RichText::new(source).color(theme::text_secondary(dark)),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(&s.computer)
+                                        .color(theme::text_secondary(dark)),
+                                );
+                            });
+                            row.col(|ui| {
+                                if s.is_elevated() {
+                                    ui.label(
+                                        egui::RichText::new("\u{1F511}")
+                                            .color(theme::level_color(3, dark)),
+                                    )
+                                    .on_hover_text(format!(
+                                        "Special privileges:\n{}",
+                                        s.privileges.join("\n")
+                                    ));
+                                }
+                                if !s.explicit_credentials.is_empty() {
+                                    ui.label(
+                                        egui::RichText::new("\u{1F4DD}").color(theme::accent(dark)),
+                                    )
+                                    .on_hover_text(format!(
+                                        "Explicit credentials used:\n{}",
+                                        s.explicit_credentials.join("\n")
+                                    ));
+                                }
+                                if s.never_closed() {
+                                    ui.label(
+                                        egui::RichText::new("\u{26A0}")
+                                            .color(theme::level_color(3, dark)),
+                                    )
+                                    .on_hover_text("No logoff in the loaded events");
+                                }
+                            });
+                            row.col(|ui| {
+                                if ui
+                                    .small_button(format!("{} \u{2192}", s.events.len()))
+                                    .on_hover_text("Show this session's events in the table")
+                                    .clicked()
+                                {
+                                    let id = s.logon_id.as_deref().unwrap_or("failed");
+                                    filter_to = Some((
+                                        format!("Session {} ({id})", s.user),
+                                        s.events.clone(),
+                                    ));
+                                }
+                            });
+                        });
+                    });
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_logon_sessions = false;
+        }
+    }
+}
@@ -3,10 +3,28 @@
 //! Each sub-module adds rendering methods to [`crate::app::EventSleuthApp`]
 //! via `impl` blocks, keeping UI code cleanly separated from state management.
 
+pub mod activity_panel;
+pub mod alerts_panel;
+pub mod anomaly_panel;
+pub mod attack_panel;
+pub mod boot_sessions_panel;
+pub mod brute_force_panel;
+pub mod crash_panel;
+pub mod crosstab_panel;
 pub mod detail_panel;
+pub mod detections_panel;
 pub mod event_table;
+pub mod field_explorer;
+pub mod filter_groups;
 pub mod filter_panel;
+pub mod ioc_panel;
+pub mod logon_sessions_panel;
+pub mod pivot_menu;
+pub mod process_tree_panel;
+pub mod services_panel;
 pub mod stats_panel;
 pub mod status_bar;
+pub mod suggest_dialog;
+pub mod template_table;
 pub mod theme;
 pub mod toolbar;
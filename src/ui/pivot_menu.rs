@@ -0,0 +1,75 @@
+//! Right-click pivot menus shared by the event table and detail panel.
+//!
+//! Each menu offers "filter to this value", "exclude this value", and
+//! "same value within ±N minutes" for one field. The chosen action is
+//! returned as a [`PivotRequest`] so callers can apply it after their
+//! borrow of the event list ends (see `EventSleuthApp::apply_pivot_request`).
+
+use crate::core::pivot::{PivotAction, PivotField, PivotRequest};
+use chrono::{DateTime, Utc};
+
+/// Add the pivot actions for `field` = `value` to an open menu.
+///
+/// Returns the chosen request, if any, and closes the menu on selection.
+pub fn pivot_menu_items(
+    ui: &mut egui::Ui,
+    field: &PivotField,
+    value: &str,
+    at: DateTime<Utc>,
+    window_minutes: i64,
+) -> Option<PivotRequest> {
+    let mut chosen = None;
+    let actions = [
+        (
+            PivotAction::FilterTo,
+            "\u{1F50D} Filter to this value".to_owned(),
+        ),
+        (
+            PivotAction::Exclude,
+            "\u{1F6AB} Exclude this value".to_owned(),
+        ),
+        (
+            PivotAction::Nearby,
+            format!("\u{1F552} Same value within \u{00B1}{window_minutes} min"),
+        ),
+    ];
+    for (action, label) in actions {
+        if ui.button(label).clicked() {
+            chosen = Some(PivotRequest {
+                field: field.clone(),
+                value: value.to_owned(),
+                action,
+                at,
+            });
+            ui.close_menu();
+        }
+    }
+    chosen
+}
+
+/// Render `text` as a label with a right-click pivot menu.
+///
+/// The menu also offers "Copy value" since a clickable label cannot be
+/// text-selected.
+pub fn pivot_label(
+    ui: &mut egui::Ui,
+    text: impl Into<egui::WidgetText>,
+    field: &PivotField,
+    value: &str,
+    at: DateTime<Utc>,
+    window_minutes: i64,
+) -> Option<PivotRequest> {
+    let response = ui
+        .add(egui::Label::new(text).sense(egui::Sense::click()))
+        .on_hover_text("Right-click to filter on this value");
+    let mut chosen = None;
+    response.context_menu(|ui| {
+        if ui.button("\u{1F4CB} Copy value").clicked() {
+            ui.ctx().copy_text(value.to_owned());
+            ui.close_menu();
+        }
+        ui.separator();
+        chosen = pivot_menu_items(ui, field, value, at, window_minutes);
+    });
+    chosen
+}
@@ -0,0 +1,334 @@
+//! Process tree side panel: parent/child process trees beside the table.
+//!
+//! Built from Security 4688/4689 and Sysmon 1/5 events, with Sysmon
+//! network, file and registry activity counted on the owning process.
+//! Clicking a process narrows the event table to its events; the subtree
+//! button includes every descendant as well.
+
+use crate::app::EventSleuthApp;
+use crate::core::process_tree::{self, ProcessForest, ProcessNode};
+use crate::ui::theme;
+use crate::util::time;
+
+/// Roots rendered at most; a search narrows the rest down.
+const MAX_ROOTS_SHOWN: usize = 500;
+
+/// Cached process trees and the panel's view options.
+#[derive(Debug, Default)]
+pub struct ProcessTreeView {
+    pub forest: ProcessForest,
+    /// `events_generation` the forest was built from.
+    pub built_for: Option<u64>,
+    /// Case-insensitive substring over image, command line and user.
+    pub search: String,
+    /// Per node: it or a descendant matches `search`. Empty when not
+    /// searching.
+    matching: Vec<bool>,
+    /// Search text `matching` was computed for.
+    matched_for: String,
+}
+
+impl ProcessTreeView {
+    /// Recompute `matching` after the search text or forest changed.
+    fn refresh_matches(&mut self) {
+        let needle = self.search.trim().to_lowercase();
+        if needle == self.matched_for && self.matching.len() == self.forest.nodes.len() {
+            return;
+        }
+        self.matched_for = needle.clone();
+        self.matching.clear();
+        if needle.is_empty() {
+            return;
+        }
+        let nodes = &self.forest.nodes;
+        self.matching = vec![false; nodes.len()];
+        for (i, n) in nodes.iter().enumerate() {
+            let hit = [&n.image, &n.command_line, &n.user]
+                .iter()
+                .any(|v| v.to_lowercase().contains(&needle))
+                || n.pid.to_string() == needle;
+            if !hit {
+                continue;
+            }
+            // Mark the node and its ancestors so the path stays visible.
+            let mut cur = Some(i);
+            while let Some(c) = cur {
+                if self.matching[c] && c != i {
+                    break;
+                }
+                self.matching[c] = true;
+                cur = nodes[c].parent;
+            }
+        }
+    }
+
+    fn shows(&self, node: usize) -> bool {
+        self.matching.is_empty() || self.matching[node]
+    }
+}
+
+/// What a click in the tree asked for.
+enum TreeAction {
+    /// The process's own events.
+    Node(usize),
+    /// The process and all its descendants.
+    Subtree(usize),
+}
+
+impl EventSleuthApp {
+    /// Render the process tree side panel.
+    ///
+    /// Visibility is controlled by `self.show_process_tree`.
+    pub fn render_process_tree_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_process_tree {
+            return;
+        }
+        if self.process_view.built_for != Some(self.events_generation) {
+            self.process_view.forest = process_tree::build(&self.all_events);
+            self.process_view.built_for = Some(self.events_generation);
+            self.process_view.matching.clear();
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut action: Option<TreeAction> = None;
+
+        egui::SidePanel::right("process_tree_panel")
+            .resizable(true)
+            .default_width(340.0)
+            .min_width(220.0)
+            .max_width(720.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("\u{1F333} Process Tree")
+                            .color(theme::text_primary(dark))
+                            .strong(),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("\u{2715}").on_hover_text("Close").clicked() {
+                            open = false;
+                        }
+                    });
+                });
+
+                let view = &mut self.process_view;
+                ui.add(
+                    egui::TextEdit::singleline(&mut view.search)
+                        .hint_text("Image, command line, user or PID")
+                        .desired_width(f32::INFINITY),
+                );
+                view.refresh_matches();
+
+                let forest = &view.forest;
+                let roots: Vec<usize> = forest
+                    .roots
+                    .iter()
+                    .copied()
+                    .filter(|&r| view.shows(r))
+                    .collect();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} processes, {} trees{} (4688/4689, Sysmon 1/3/5/11-14)",
+                        forest.nodes.len(),
+                        roots.len(),
+                        if roots.len() > MAX_ROOTS_SHOWN {
+                            format!(", first {MAX_ROOTS_SHOWN} shown")
+                        } else {
+                            String::new()
+                        }
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if forest.nodes.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            "No process creation events loaded. Enable process creation \
+                             auditing (4688) or Sysmon.",
+                        )
+                        .color(theme::text_dim(dark))
+                        .italics(),
+                    );
+                    return;
+                }
+
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for &root in roots.iter().take(MAX_ROOTS_SHOWN) {
+                            render_node(ui, view, root, dark, &mut action);
+                        }
+                    });
+            });
+
+        if let Some(action) = action {
+            let forest = &self.process_view.forest;
+            let (label, mut indices) = match action {
+                TreeAction::Node(n) => {
+                    let node = &forest.nodes[n];
+                    (
+                        format!("Process {} ({})", node.image_name(), node.pid),
+                        node.events.clone(),
+                    )
+                }
+                TreeAction::Subtree(n) => {
+                    let node = &forest.nodes[n];
+                    let indices = forest
+                        .subtree(n)
+                        .into_iter()
+                        .flat_map(|c| forest.nodes[c].events.iter().copied())
+                        .collect();
+                    (
+                        format!("Process tree {} ({})", node.image_name(), node.pid),
+                        indices,
+                    )
+                }
+            };
+            indices.sort_unstable();
+            indices.dedup();
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_process_tree = false;
+        }
+    }
+}
+
+/// One node row, plus its children when expanded.
+fn render_node(
+    ui: &mut egui::Ui,
+    view: &ProcessTreeView,
+    idx: usize,
+    dark: bool,
+    action: &mut Option<TreeAction>,
+) {
+    let node = &view.forest.nodes[idx];
+    let children: Vec<usize> = node
+        .children
+        .iter()
+        .copied()
+        .filter(|&c| view.shows(c))
+        .collect();
+
+    if children.is_empty() {
+        ui.horizontal(|ui| {
+            // Line up with the labels of expandable siblings.
+            ui.add_space(ui.spacing().indent);
+            node_row(ui, node, idx, false, dark, action);
+        });
+        return;
+    }
+
+    let searching = !view.matching.is_empty();
+    let id = ui.make_persistent_id(("process_node", view.built_for, idx, searching));
+    egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, searching)
+        .show_header(ui, |ui| node_row(ui, node, idx, true, dark, action))
+        .body(|ui| {
+            for c in children {
+                render_node(ui, view, c, dark, action);
+            }
+        });
+}
+
+/// Label, activity counts and the subtree button for one process.
+fn node_row(
+    ui: &mut egui::Ui,
+    node: &ProcessNode,
+    idx: usize,
+    has_children: bool,
+    dark: bool,
+    action: &mut Option<TreeAction>,
+) {
+    let color = if node.is_placeholder() {
+        theme::text_dim(dark)
+    } else if matches!(node.integrity.as_str(), "High" | "System") {
+        theme::level_color(3, dark)
+    } else {
+        theme::text_primary(dark)
+    };
+    let mut text =
+        egui::RichText::new(format!("{} ({})", node.image_name(), node.pid)).color(color);
+    if node.is_placeholder() {
+        text = text.italics();
+    }
+
+    let mut hover = String::new();
+    if !node.image.is_empty() {
+        hover.push_str(&node.image);
+        hover.push('\n');
+    }
+    if !node.command_line.is_empty() {
+        hover.push_str(&format!("{}\n", node.command_line));
+    }
+    if node.is_placeholder() {
+        hover.push_str("Parent inferred from a child; its creation was not captured\n");
+    }
+    for (name, value) in [
+        ("User", &node.user),
+        ("Integrity", &node.integrity),
+        ("Computer", &node.computer),
+    ] {
+        if !value.is_empty() {
+            hover.push_str(&format!("\n{name}: {value}"));
+        }
+    }
+    if let Some(start) = node.start {
+        hover.push_str(&format!(
+            "\nStarted: {}",
+            time::format_table_timestamp(&start)
+        ));
+    }
+    match (node.end, node.lifetime()) {
+        (Some(_), Some(d)) => hover.push_str(&format!("\nExited after {}", time::format_span(d))),
+        (Some(end), None) => {
+            hover.push_str(&format!("\nExited: {}", time::format_table_timestamp(&end)))
+        }
+        (None, _) if !node.is_placeholder() => hover.push_str("\nNo exit event seen"),
+        _ => {}
+    }
+    hover.push_str(&format!(
+        "\n\nClick to show this process's {} events",
+        node.events.len()
+    ));
+
+    if ui
+        .add(egui::Label::new(text).sense(egui::Sense::click()))
+        .on_hover_text(hover)
+        .clicked()
+    {
+        *action = Some(TreeAction::Node(idx));
+    }
+
+    for (count, icon, what) in [
+        (node.network, "\u{1F310}", "network connections"),
+        (node.files, "\u{1F4C4}", "files created"),
+        (node.registry, "\u{1F527}", "registry changes"),
+    ] {
+        if count > 0 {
+            ui.label(
+                egui::RichText::new(format!("{icon}{count}"))
+                    .color(theme::text_secondary(dark))
+                    .small(),
+            )
+            .on_hover_text(format!("{count} {what}"));
+        }
+    }
+    if let Some(d) = node.lifetime() {
+        ui.label(
+            egui::RichText::new(time::format_span(d))
+                .color(theme::text_dim(dark))
+                .small(),
+        );
+    }
+    if has_children
+        && ui
+            .small_button("\u{2935}")
+            .on_hover_text("Show events of this process and all its descendants")
+            .clicked()
+    {
+        *action = Some(TreeAction::Subtree(idx));
+    }
+}
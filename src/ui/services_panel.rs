@@ -0,0 +1,473 @@
+//! Services window: service lifecycle timeline and installations.
+//!
+//! The Services tab lists every service seen in SCM events, those with a
+//! flagged installation or a crash / restart loop first; selecting one
+//! shows its timeline of installations, state transitions, controls,
+//! start-type changes, failures and crashes. The Installations tab lists
+//! 7045 / 4697 events with the reasons an image path was flagged. Every
+//! row links back to its source event.
+
+use egui_extras::{Column, TableBuilder};
+
+use crate::app::EventSleuthApp;
+use crate::core::service::{self, EntryKind, Service, ServiceTimeline};
+use crate::ui::theme;
+use crate::util::time;
+
+/// Which list the window shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ServicesTab {
+    #[default]
+    Services,
+    Installations,
+}
+
+/// Cached timeline and the window's view options.
+#[derive(Debug, Default)]
+pub struct ServicesView {
+    pub timeline: ServiceTimeline,
+    /// `events_generation` the timeline was built for.
+    pub built_for: Option<u64>,
+    pub tab: ServicesTab,
+    pub search: String,
+    /// Only services needing attention / flagged installations.
+    pub attention_only: bool,
+    /// Computer and name of the service whose timeline is shown.
+    selected: Option<(String, String)>,
+}
+
+fn kind_color(kind: EntryKind, dark: bool) -> egui::Color32 {
+    match kind {
+        EntryKind::Installed => theme::accent(dark),
+        EntryKind::Crash => theme::level_color(2, dark),
+        EntryKind::Failure => theme::level_color(3, dark),
+        EntryKind::State | EntryKind::Control | EntryKind::StartType => theme::text_secondary(dark),
+    }
+}
+
+/// Timeline of the selected service; returns the label and event indices
+/// to filter to, if asked.
+fn service_timeline(
+    ui: &mut egui::Ui,
+    service: &Service,
+    dark: bool,
+) -> Option<(String, Vec<usize>)> {
+    let mut picked = None;
+    ui.horizontal(|ui| {
+        ui.label(
+            egui::RichText::new(match &service.display_name {
+                Some(display) => {
+                    format!("{} ({display}) \u{2014} {}", service.name, service.computer)
+                }
+                None => format!("{} \u{2014} {}", service.name, service.computer),
+            })
+            .color(theme::text_primary(dark))
+            .strong(),
+        );
+        if ui
+            .small_button(format!("{} \u{2192}", service.entries.len()))
+            .on_hover_text("Show all of this service's events in the table")
+            .clicked()
+        {
+            picked = Some((
+                format!("Service {}", service.name),
+                service.entries.iter().map(|e| e.event).collect(),
+            ));
+        }
+    });
+    for l in &service.loops {
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!(
+                    "\u{1F501} Crash loop: {} terminations in {} from {}",
+                    l.events.len(),
+                    time::format_span(l.end - l.start),
+                    time::format_table_timestamp(&l.start)
+                ))
+                .color(theme::level_color(2, dark)),
+            );
+            if ui
+                .small_button("\u{2192}")
+                .on_hover_text("Show these terminations in the table")
+                .clicked()
+            {
+                picked = Some((format!("Crash loop of {}", service.name), l.events.clone()));
+            }
+        });
+    }
+    ui.add_space(theme::ITEM_SPACING);
+
+    TableBuilder::new(ui)
+        .striped(true)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(135.0)) // Time
+        .column(Column::auto().at_least(45.0)) // ID
+        .column(Column::auto().at_least(75.0)) // Kind
+        .column(Column::remainder().at_least(200.0).clip(true)) // Detail
+        .sense(egui::Sense::click())
+        .header(20.0, |mut header| {
+            for title in ["Time", "ID", "Kind", "Detail"] {
+                header.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(title)
+                            .color(theme::text_primary(dark))
+                            .strong(),
+                    );
+                });
+            }
+        })
+        .body(|body| {
+            body.rows(theme::TABLE_ROW_HEIGHT, service.entries.len(), |mut row| {
+                let entry = &service.entries[row.index()];
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(time::format_table_timestamp(&entry.time))
+                            .color(theme::text_secondary(dark))
+                            .small(),
+                    );
+                });
+                row.col(|ui| {
+                    ui.label(entry.event_id.to_string());
+                });
+                row.col(|ui| {
+                    ui.label(
+                        egui::RichText::new(entry.kind.label()).color(kind_color(entry.kind, dark)),
+                    );
+                });
+                row.col(|ui| {
+                    ui.label(egui::RichText::new(&entry.detail).color(theme::text_primary(dark)))
+                        .on_hover_text(&entry.detail);
+                });
+                if row
+                    .response()
+                    .on_hover_text("Click to show this event in the table")
+                    .clicked()
+                {
+                    picked = Some((
+                        format!("{} {}", service.name, entry.kind.label()),
+                        vec![entry.event],
+                    ));
+                }
+            });
+        });
+    picked
+}
+
+impl EventSleuthApp {
+    /// Render the floating services window.
+    ///
+    /// Visibility is controlled by `self.show_services`.
+    pub fn render_services_panel(&mut self, ctx: &egui::Context) {
+        if !self.show_services {
+            return;
+        }
+        if self.services_view.built_for != Some(self.events_generation) {
+            self.services_view.timeline = service::build(&self.all_events);
+            self.services_view.built_for = Some(self.events_generation);
+        }
+
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut filter_to: Option<(String, Vec<usize>)> = None;
+        let max_h = ctx.screen_rect().height() * 0.8;
+
+        egui::Window::new("\u{2699} Services")
+            .open(&mut open)
+            .collapsible(true)
+            .resizable(true)
+            .default_width(980.0)
+            .default_height(max_h.min(540.0))
+            .max_height(max_h)
+            .show(ctx, |ui| {
+                let view = &mut self.services_view;
+                let timeline = &view.timeline;
+                let flagged = timeline
+                    .installs
+                    .iter()
+                    .filter(|i| !i.flags.is_empty())
+                    .count();
+                let looping = timeline
+                    .services
+                    .iter()
+                    .filter(|s| !s.loops.is_empty())
+                    .count();
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut view.tab, ServicesTab::Services, "Services");
+                    ui.selectable_value(
+                        &mut view.tab,
+                        ServicesTab::Installations,
+                        format!("Installations ({})", timeline.installs.len()),
+                    );
+                    ui.separator();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut view.search)
+                            .hint_text("Filter services")
+                            .desired_width(180.0),
+                    );
+                    ui.checkbox(&mut view.attention_only, "Needs attention only")
+                        .on_hover_text("Flagged installations and crash / restart loops");
+                });
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} services, {} installations ({flagged} flagged), \
+                         {looping} services in a crash / restart loop",
+                        timeline.services.len(),
+                        timeline.installs.len()
+                    ))
+                    .color(theme::text_dim(dark))
+                    .small(),
+                );
+                ui.separator();
+
+                if timeline.services.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No Service Control Manager or 4697 events loaded")
+                            .color(theme::text_dim(dark))
+                            .italics(),
+                    );
+                    return;
+                }
+
+                let needle = view.search.trim().to_lowercase();
+                let matches =
+                    |name: &str| needle.is_empty() || name.to_lowercase().contains(&needle);
+
+                if view.tab == ServicesTab::Installations {
+                    let installs: Vec<usize> = (0..timeline.installs.len())
+                        .rev()
+                        .filter(|&i| {
+                            let install = &timeline.installs[i];
+                            (!view.attention_only || !install.flags.is_empty())
+                                && (matches(&install.service) || matches(&install.image_path))
+                        })
+                        .collect();
+                    TableBuilder::new(ui)
+                        .striped(true)
+                        .resizable(true)
+                        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                        .column(Column::auto().at_least(135.0)) // Time
+                        .column(Column::auto().at_least(45.0)) // ID
+                        .column(Column::auto().at_least(90.0).clip(true)) // Computer
+                        .column(Column::auto().at_least(140.0).clip(true)) // Service
+                        .column(Column::auto().at_least(260.0).clip(true)) // Image path
+                        .column(Column::auto().at_least(100.0).clip(true)) // Account
+                        .column(Column::auto().at_least(85.0).clip(true)) // Start type
+                        .column(Column::auto().at_least(90.0).clip(true)) // Installed by
+                        .column(Column::remainder().at_least(150.0).clip(true)) // Flags
+                        .sense(egui::Sense::click())
+                        .header(20.0, |mut header| {
+                            for title in [
+                                "Time",
+                                "ID",
+                                "Computer",
+                                "Service",
+                                "Image path",
+                                "Account",
+                                "Start type",
+                                "Installed by",
+                                "Flags",
+                            ] {
+                                header.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(title)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                });
+                            }
+                        })
+                        .body(|body| {
+                            body.rows(theme::TABLE_ROW_HEIGHT, installs.len(), |mut row| {
+                                let install = &timeline.installs[installs[row.index()]];
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(time::format_table_timestamp(
+                                            &install.time,
+                                        ))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    ui.label(install.event_id.to_string());
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(&install.computer)
+                                            .color(theme::text_secondary(dark)),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        egui::RichText::new(&install.service)
+                                            .color(theme::text_primary(dark))
+                                            .strong(),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    ui.label(egui::RichText::new(&install.image_path).monospace())
+                                        .on_hover_text(&install.image_path);
+                                });
+                                for text in [&install.account, &install.start_type] {
+                                    row.col(|ui| {
+                                        ui.label(text.as_str());
+                                    });
+                                }
+                                row.col(|ui| {
+                                    ui.label(install.installed_by.as_deref().unwrap_or("\u{2014}"));
+                                });
+                                row.col(|ui| {
+                                    if !install.flags.is_empty() {
+                                        let text = install.flags.join(", ");
+                                        ui.label(
+                                            egui::RichText::new(format!("\u{26A0} {text}"))
+                                                .color(theme::level_color(2, dark)),
+                                        )
+                                        .on_hover_text(text);
+                                    }
+                                });
+                                if row
+                                    .response()
+                                    .on_hover_text("Click to show this event in the table")
+                                    .clicked()
+                                {
+                                    filter_to = Some((
+                                        format!("Service installed: {}", install.service),
+                                        vec![install.event],
+                                    ));
+                                }
+                            });
+                        });
+                    return;
+                }
+
+                let services: Vec<usize> = (0..timeline.services.len())
+                    .filter(|&i| {
+                        let s = &timeline.services[i];
+                        (!view.attention_only || s.needs_attention())
+                            && (matches(&s.name) || s.display_name.as_deref().is_some_and(matches))
+                    })
+                    .collect();
+                let selected = view.selected.as_ref().and_then(|(computer, name)| {
+                    timeline
+                        .services
+                        .iter()
+                        .find(|s| &s.computer == computer && &s.name == name)
+                });
+                let mut select: Option<(String, String)> = None;
+
+                ui.columns(2, |cols| {
+                    cols[0].push_id("services_list", |ui| {
+                        TableBuilder::new(ui)
+                            .striped(true)
+                            .resizable(true)
+                            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                            .column(Column::auto().at_least(20.0)) // Attention
+                            .column(Column::auto().at_least(150.0).clip(true)) // Service
+                            .column(Column::auto().at_least(80.0).clip(true)) // Computer
+                            .column(Column::auto().at_least(65.0).clip(true)) // State
+                            .column(Column::auto().at_least(80.0).clip(true)) // Start type
+                            .column(Column::remainder().at_least(50.0)) // Crashes
+                            .sense(egui::Sense::click())
+                            .header(20.0, |mut header| {
+                                for title in
+                                    ["", "Service", "Computer", "State", "Start type", "Crashes"]
+                                {
+                                    header.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(title)
+                                                .color(theme::text_primary(dark))
+                                                .strong(),
+                                        );
+                                    });
+                                }
+                            })
+                            .body(|body| {
+                                body.rows(theme::TABLE_ROW_HEIGHT, services.len(), |mut row| {
+                                    let s = &timeline.services[services[row.index()]];
+                                    row.set_selected(selected.is_some_and(|sel| {
+                                        sel.computer == s.computer && sel.name == s.name
+                                    }));
+                                    row.col(|ui| {
+                                        if s.needs_attention() {
+                                            let why = match (s.flagged, s.loops.is_empty()) {
+                                                (true, true) => "Flagged installation",
+                                                (false, _) => "Crash / restart loop",
+                                                (true, false) => {
+                                                    "Flagged installation and crash / restart loop"
+                                                }
+                                            };
+                                            ui.label(
+                                                egui::RichText::new("\u{26A0}")
+                                                    .color(theme::level_color(2, dark)),
+                                            )
+                                            .on_hover_text(why);
+                                        }
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&s.name)
+                                                .color(theme::text_primary(dark)),
+                                        );
+                                    });
+                                    row.col(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(&s.computer)
+                                                .color(theme::text_secondary(dark)),
+                                        );
+                                    });
+                                    for text in [&s.state, &s.start_type] {
+                                        row.col(|ui| {
+                                            ui.label(
+                                                egui::RichText::new(
+                                                    text.as_deref().unwrap_or("\u{2014}"),
+                                                )
+                                                .color(theme::text_secondary(dark)),
+                                            );
+                                        });
+                                    }
+                                    row.col(|ui| {
+                                        if s.crashes > 0 {
+                                            ui.label(
+                                                egui::RichText::new(s.crashes.to_string())
+                                                    .color(theme::level_color(2, dark))
+                                                    .strong(),
+                                            );
+                                        }
+                                    });
+                                    if row.response().clicked() {
+                                        select = Some((s.computer.clone(), s.name.clone()));
+                                    }
+                                });
+                            });
+                    });
+
+                    let ui = &mut cols[1];
+                    let Some(service) = selected else {
+                        ui.label(
+                            egui::RichText::new("Select a service to see its timeline")
+                                .color(theme::text_dim(dark))
+                                .italics(),
+                        );
+                        return;
+                    };
+                    ui.push_id("service_timeline", |ui| {
+                        if let Some(picked) = service_timeline(ui, service, dark) {
+                            filter_to = Some(picked);
+                        }
+                    });
+                });
+                if select.is_some() {
+                    view.selected = select;
+                }
+            });
+
+        if let Some((label, indices)) = filter_to {
+            self.set_event_index_filter(label, &indices);
+        }
+        if !open {
+            self.show_services = false;
+        }
+    }
+}
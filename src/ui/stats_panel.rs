@@ -1,35 +1,138 @@
 //! Event statistics summary panel.
 //!
-//! Displays a collapsible overview of the currently loaded events:
-//! counts by severity level, top providers, and an events-per-hour
-//! histogram. Provides immediate situational awareness for incident
-//! response and triage workflows.
+//! Displays a collapsible overview of the currently filtered events:
+//! counts by severity level, top providers, channels, computers, event IDs
+//! and users, a per-minute / hour / day histogram with levels stacked in
+//! each bar, and a weekday × hour heatmap, in local time or UTC. Clicking
+//! a row, bar or cell narrows the filter to it, so the panel doubles as a
+//! drill-down for incident response and triage.
 
 use crate::app::EventSleuthApp;
+use crate::core::pivot::{PivotAction, PivotField, PivotRequest};
+use crate::core::stats::{self, Granularity};
 use crate::ui::theme;
-use std::collections::HashMap;
-
-/// Maximum number of top providers to display in the summary.
-const MAX_TOP_PROVIDERS: usize = 10;
-
-/// Maximum number of hourly histogram buckets to display.
-const MAX_HISTOGRAM_BUCKETS: usize = 24;
-
-/// Pre-computed statistics snapshot for the current event set.
-///
-/// Computed lazily and cached in app state; invalidated whenever
-/// `needs_refilter` triggers or the event list changes.
-#[derive(Debug, Clone, Default)]
-pub struct EventStats {
-    /// Total events in the filtered set.
-    pub total: usize,
-    /// Counts per severity level (index 0..=5).
-    pub level_counts: [usize; 6],
-    /// Top N providers by frequency: `(provider_name, count)`.
-    pub top_providers: Vec<(String, usize)>,
-    /// Hourly event counts for the histogram, ordered oldest-first.
-    /// Each entry is `(hour_label, count)`.
-    pub hourly_histogram: Vec<(String, usize)>,
+
+/// Severity level names, indexed by level.
+const LEVEL_NAMES: [&str; 6] = [
+    "LogAlways",
+    "Critical",
+    "Error",
+    "Warning",
+    "Information",
+    "Verbose",
+];
+
+/// Stacking order of levels in histogram bars, bottom first.
+const STACK_ORDER: [usize; 6] = [1, 2, 3, 4, 0, 5];
+
+/// Height of the histogram chart.
+const HISTOGRAM_HEIGHT: f32 = 120.0;
+
+/// Height of one weekday row in the heatmap.
+const HEATMAP_ROW_HEIGHT: f32 = 16.0;
+
+/// Width of the heatmap's weekday label column.
+const HEATMAP_LABEL_WIDTH: f32 = 34.0;
+
+/// Filter change chosen in the panel, applied after the window closes its
+/// borrow of the stats cache.
+enum StatsAction {
+    /// Pivots to apply together (e.g. event ID and its provider).
+    Pivot(Vec<(PivotField, String)>),
+    /// Restrict the time range to one histogram bucket.
+    TimeWindow(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>),
+    /// Show only events in one `(weekday, hour)` heatmap slot.
+    WeekSlot(usize, usize),
+}
+
+/// Shorten `name` to about `max` characters with a trailing ellipsis.
+fn truncate(name: &str, max: usize) -> String {
+    match name.char_indices().nth(max.saturating_sub(3)) {
+        Some((end, _)) if name.chars().count() > max => format!("{}...", &name[..end]),
+        _ => name.to_owned(),
+    }
+}
+
+/// A clickable breakdown row: label and count on one line, a proportional
+/// bar underneath. Highlights on hover.
+fn bar_row(
+    ui: &mut egui::Ui,
+    label: egui::RichText,
+    count: usize,
+    frac: f32,
+    color: egui::Color32,
+    dark: bool,
+) -> egui::Response {
+    let response = ui
+        .horizontal(|ui| {
+            ui.label(label);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(egui::RichText::new(count.to_string()).color(theme::text_secondary(dark)));
+            });
+        })
+        .response;
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 3.0), egui::Sense::hover());
+    ui.painter().rect_filled(
+        egui::Rect::from_min_size(rect.min, egui::vec2(rect.width() * frac, rect.height())),
+        1.5,
+        color,
+    );
+    ui.add_space(1.0);
+    let response = ui
+        .interact(
+            response.rect.union(rect),
+            response.id.with("row"),
+            egui::Sense::click(),
+        )
+        .on_hover_cursor(egui::CursorIcon::PointingHand);
+    if response.hovered() {
+        ui.painter().rect_filled(
+            response.rect.expand(1.0),
+            2.0,
+            theme::accent(dark).gamma_multiply(0.12),
+        );
+    }
+    response
+}
+
+/// A collapsible top-N section over `(label, count)` rows; returns the
+/// index of the clicked row.
+fn breakdown_section(
+    ui: &mut egui::Ui,
+    title: &str,
+    rows: &[(String, usize)],
+    empty: &str,
+    dark: bool,
+) -> Option<usize> {
+    let mut clicked = None;
+    egui::CollapsingHeader::new(egui::RichText::new(title).strong())
+        .default_open(false)
+        .show(ui, |ui| {
+            if rows.is_empty() {
+                ui.label(
+                    egui::RichText::new(empty)
+                        .color(theme::text_dim(dark))
+                        .italics(),
+                );
+                return;
+            }
+            let max_count = rows.first().map(|(_, c)| *c).unwrap_or(1).max(1);
+            for (i, (name, count)) in rows.iter().enumerate() {
+                let label =
+                    egui::RichText::new(truncate(name, 35)).color(theme::text_primary(dark));
+                let frac = *count as f32 / max_count as f32;
+                let response = bar_row(ui, label, *count, frac, theme::accent(dark), dark);
+                if response
+                    .on_hover_text(format!("Filter to {name}"))
+                    .clicked()
+                {
+                    clicked = Some(i);
+                }
+            }
+        });
+    ui.add_space(theme::SECTION_SPACING);
+    clicked
 }
 
 impl EventSleuthApp {
@@ -37,119 +140,29 @@ impl EventSleuthApp {
     ///
     /// Called when the stats panel is visible and the event data has changed.
     /// Results are cached in `self.stats_cache` until the next refilter.
-    pub fn compute_stats(&self) -> EventStats {
-        let events = &self.all_events;
-        let indices = &self.filtered_indices;
-
-        if indices.is_empty() {
-            return EventStats::default();
-        }
-
-        // Level counts
-        let mut level_counts = [0usize; 6];
-        let mut provider_counts: HashMap<&str, usize> = HashMap::new();
-
-        // Collect timestamps for histogram
-        let mut timestamps: Vec<chrono::DateTime<chrono::Utc>> = Vec::with_capacity(indices.len());
-
-        for &idx in indices {
-            let event = &events[idx];
-            let level_idx = (event.level as usize).min(5);
-            level_counts[level_idx] += 1;
-            *provider_counts
-                .entry(event.provider_name.as_str())
-                .or_insert(0) += 1;
-            timestamps.push(event.timestamp);
-        }
-
-        // Top providers
-        let mut provider_vec: Vec<(String, usize)> = provider_counts
-            .into_iter()
-            .map(|(k, v)| (k.to_owned(), v))
-            .collect();
-        provider_vec.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
-        provider_vec.truncate(MAX_TOP_PROVIDERS);
-
-        // Hourly histogram
-        let hourly_histogram = if timestamps.is_empty() {
-            Vec::new()
+    pub fn compute_stats(&self) -> stats::EventStats {
+        let (events, indices) = (&self.all_events, &self.filtered_indices);
+        if self.stats_utc {
+            stats::compute(events, indices, self.stats_granularity, &chrono::Utc)
         } else {
-            Self::build_hourly_histogram(&timestamps)
-        };
-
-        EventStats {
-            total: indices.len(),
-            level_counts,
-            top_providers: provider_vec,
-            hourly_histogram,
+            stats::compute(events, indices, self.stats_granularity, &chrono::Local)
         }
     }
 
-    /// Build an hourly histogram from a set of timestamps.
-    ///
-    /// Divides the time span into hour-aligned buckets and counts events
-    /// in each. Limits to the most recent [`MAX_HISTOGRAM_BUCKETS`] hours.
-    fn build_hourly_histogram(
-        timestamps: &[chrono::DateTime<chrono::Utc>],
-    ) -> Vec<(String, usize)> {
-        use chrono::{Duration, Local, Timelike};
-
-        if timestamps.is_empty() {
-            return Vec::new();
-        }
-
-        let min_ts = timestamps.iter().copied().min().unwrap();
-        let max_ts = timestamps.iter().copied().max().unwrap();
-
-        // Round down to the nearest hour
-        let start_hour = min_ts
-            .with_minute(0)
-            .and_then(|t| t.with_second(0))
-            .and_then(|t| t.with_nanosecond(0))
-            .unwrap_or(min_ts);
-        // Use checked arithmetic when advancing the end bucket by one hour to
-        // guard against a panic if max_ts is near DateTime<Utc>::MAX_UTC.
-        // In practice event timestamps are never near the maximum, but defensive
-        // code here avoids a panic on malformed or synthetic evtx files.
-        let max_ts_rounded = max_ts
-            .with_minute(0)
-            .and_then(|t| t.with_second(0))
-            .and_then(|t| t.with_nanosecond(0))
-            .unwrap_or(max_ts);
-        let end_hour = max_ts_rounded
-            .checked_add_signed(Duration::hours(1))
-            .unwrap_or(max_ts_rounded);
-
-        let total_hours = ((end_hour - start_hour).num_hours()).max(1) as usize;
-
-        // If span is too wide, take only the most recent hours
-        let display_hours = total_hours.min(MAX_HISTOGRAM_BUCKETS);
-        let bucket_start = if total_hours > MAX_HISTOGRAM_BUCKETS {
-            end_hour - Duration::hours(MAX_HISTOGRAM_BUCKETS as i64)
-        } else {
-            start_hour
-        };
-
-        let mut buckets = vec![0usize; display_hours];
-
-        for &ts in timestamps {
-            if ts < bucket_start {
-                continue;
-            }
-            let idx = ((ts - bucket_start).num_hours()).max(0) as usize;
-            if idx < buckets.len() {
-                buckets[idx] += 1;
-            }
-        }
-
-        buckets
-            .into_iter()
-            .enumerate()
-            .map(|(i, count)| {
-                let hour_ts = bucket_start + Duration::hours(i as i64);
-                let local_hour = hour_ts.with_timezone(&Local);
-                let label = local_hour.format("%H:%M").to_string();
-                (label, count)
+    /// Filtered events in heatmap slot `(day, hour)`.
+    fn week_slot_events(&self, day: usize, hour: usize) -> Vec<usize> {
+        let utc = self.stats_utc;
+        self.filtered_indices
+            .iter()
+            .copied()
+            .filter(|&i| {
+                let ts = self.all_events[i].timestamp;
+                let slot = if utc {
+                    stats::week_slot(ts, &chrono::Utc)
+                } else {
+                    stats::week_slot(ts, &chrono::Local)
+                };
+                slot == (day, hour)
             })
             .collect()
     }
@@ -164,12 +177,16 @@ impl EventSleuthApp {
         }
 
         let mut open = true;
+        let mut open_anomalies = false;
+        let mut action: Option<StatsAction> = None;
+        let mut granularity = self.stats_granularity;
+        let mut utc = self.stats_utc;
         let max_h = ctx.screen_rect().height() * 0.75;
         egui::Window::new("\u{1F4CA} Event Statistics")
             .open(&mut open)
             .collapsible(true)
             .resizable(true)
-            .default_width(340.0)
+            .default_width(360.0)
             .default_height(max_h.min(500.0))
             .max_height(max_h)
             .show(ctx, |ui| {
@@ -192,11 +209,29 @@ impl EventSleuthApp {
                         return;
                     }
 
-                    ui.label(
-                        egui::RichText::new(format!("{} filtered events", stats.total))
-                            .color(theme::accent(dark))
-                            .strong(),
-                    );
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("{} filtered events", stats.total))
+                                .color(theme::accent(dark))
+                                .strong(),
+                        );
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            open_anomalies = ui
+                                .button("\u{26A1} Anomalies")
+                                .on_hover_text("Volume spikes and rare events in the loaded window")
+                                .clicked();
+                        });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Times").color(theme::text_secondary(dark)));
+                        ui.selectable_value(&mut utc, false, "Local");
+                        ui.selectable_value(&mut utc, true, "UTC");
+                        ui.label(
+                            egui::RichText::new("\u{2014} click a row, bar or cell to filter")
+                                .color(theme::text_dim(dark))
+                                .small(),
+                        );
+                    });
 
                     ui.add_space(theme::SECTION_SPACING);
 
@@ -206,187 +241,339 @@ impl EventSleuthApp {
                     )
                     .default_open(true)
                     .show(ui, |ui| {
-                        let level_names = [
-                            "LogAlways",
-                            "Critical",
-                            "Error",
-                            "Warning",
-                            "Information",
-                            "Verbose",
-                        ];
-                        for (i, name) in level_names.iter().enumerate() {
+                        for (i, name) in LEVEL_NAMES.iter().enumerate() {
                             let count = stats.level_counts[i];
                             if count == 0 {
                                 continue;
                             }
                             let color = theme::level_color(i as u8, dark);
                             let pct = (count as f64 / stats.total as f64 * 100.0).round() as u32;
-                            ui.horizontal(|ui| {
-                                ui.label(egui::RichText::new(*name).color(color));
-                                ui.with_layout(
-                                    egui::Layout::right_to_left(egui::Align::Center),
-                                    |ui| {
-                                        ui.label(
-                                            egui::RichText::new(format!("{count} ({pct}%)"))
-                                                .color(theme::text_secondary(dark)),
-                                        );
-                                    },
-                                );
-                            });
-                            // Mini bar
-                            let bar_frac = count as f32 / stats.total as f32;
-                            let (rect, _) = ui.allocate_exact_size(
-                                egui::vec2(ui.available_width(), 4.0),
-                                egui::Sense::hover(),
-                            );
-                            ui.painter().rect_filled(
-                                egui::Rect::from_min_size(
-                                    rect.min,
-                                    egui::vec2(rect.width() * bar_frac, rect.height()),
-                                ),
-                                2.0,
-                                color,
-                            );
-                            ui.painter().rect_filled(
-                                egui::Rect::from_min_size(
-                                    rect.min + egui::vec2(rect.width() * bar_frac, 0.0),
-                                    egui::vec2(rect.width() * (1.0 - bar_frac), rect.height()),
-                                ),
-                                2.0,
-                                if dark {
-                                    egui::Color32::from_rgb(50, 50, 65)
-                                } else {
-                                    egui::Color32::from_rgb(220, 220, 228)
-                                },
-                            );
-                            ui.add_space(2.0);
+                            let label =
+                                egui::RichText::new(format!("{name} ({pct}%)")).color(color);
+                            let frac = count as f32 / stats.total as f32;
+                            if bar_row(ui, label, count, frac, color, dark)
+                                .on_hover_text(format!("Show only {name}"))
+                                .clicked()
+                            {
+                                action = Some(StatsAction::Pivot(vec![(
+                                    PivotField::Level,
+                                    i.to_string(),
+                                )]));
+                            }
                         }
                     });
 
                     ui.add_space(theme::SECTION_SPACING);
 
-                    // ── Top providers ───────────────────────────────────
+                    // ── Histogram ───────────────────────────────────────
                     egui::CollapsingHeader::new(
-                        egui::RichText::new("\u{1F3F7}\u{FE0F} Top Providers").strong(),
+                        egui::RichText::new("\u{1F552} Events over Time").strong(),
                     )
                     .default_open(true)
                     .show(ui, |ui| {
-                        if stats.top_providers.is_empty() {
-                            ui.label(
-                                egui::RichText::new("No providers")
-                                    .color(theme::text_dim(dark))
-                                    .italics(),
-                            );
-                            return;
-                        }
-                        let max_count = stats
-                            .top_providers
-                            .first()
-                            .map(|(_, c)| *c)
-                            .unwrap_or(1)
-                            .max(1);
-
-                        for (name, count) in &stats.top_providers {
-                            ui.horizontal(|ui| {
-                                // Truncate long provider names
-                                let display_name = if name.len() > 35 {
-                                    let end = name
-                                        .char_indices()
-                                        .nth(32)
-                                        .map(|(i, _)| i)
-                                        .unwrap_or(name.len());
-                                    format!("{}...", &name[..end])
-                                } else {
-                                    name.clone()
-                                };
-                                ui.label(
-                                    egui::RichText::new(display_name)
-                                        .color(theme::text_primary(dark)),
-                                );
-                                ui.with_layout(
-                                    egui::Layout::right_to_left(egui::Align::Center),
-                                    |ui| {
-                                        ui.label(
-                                            egui::RichText::new(count.to_string())
-                                                .color(theme::text_secondary(dark)),
-                                        );
-                                    },
-                                );
-                            });
-                            // Mini bar
-                            let bar_frac = *count as f32 / max_count as f32;
-                            let (rect, _) = ui.allocate_exact_size(
-                                egui::vec2(ui.available_width(), 3.0),
-                                egui::Sense::hover(),
-                            );
-                            ui.painter().rect_filled(
-                                egui::Rect::from_min_size(
-                                    rect.min,
-                                    egui::vec2(rect.width() * bar_frac, rect.height()),
-                                ),
-                                1.5,
-                                theme::accent(dark),
-                            );
-                            ui.add_space(1.0);
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Per").color(theme::text_secondary(dark)));
+                            for g in Granularity::ALL {
+                                ui.selectable_value(&mut granularity, g, g.label());
+                            }
+                        });
+                        if let Some(start) = render_histogram(ui, stats, dark) {
+                            let range = if self.stats_utc {
+                                stats::bucket_range(start, stats.granularity, &chrono::Utc)
+                            } else {
+                                stats::bucket_range(start, stats.granularity, &chrono::Local)
+                            };
+                            if let Some((from, to)) = range {
+                                action = Some(StatsAction::TimeWindow(from, to));
+                            }
                         }
                     });
 
                     ui.add_space(theme::SECTION_SPACING);
 
-                    // ── Hourly histogram ────────────────────────────────
+                    // ── Weekday × hour heatmap ──────────────────────────
                     egui::CollapsingHeader::new(
-                        egui::RichText::new("\u{1F552} Events per Hour").strong(),
+                        egui::RichText::new("\u{1F5D3} Weekday \u{D7} Hour").strong(),
                     )
-                    .default_open(false)
+                    .default_open(true)
                     .show(ui, |ui| {
-                        if stats.hourly_histogram.is_empty() {
-                            ui.label(
-                                egui::RichText::new("Insufficient data")
-                                    .color(theme::text_dim(dark))
-                                    .italics(),
-                            );
-                            return;
-                        }
-
-                        let max_count = stats
-                            .hourly_histogram
-                            .iter()
-                            .map(|(_, c)| *c)
-                            .max()
-                            .unwrap_or(1)
-                            .max(1);
-
-                        let bar_height = 14.0;
-                        let total_width = ui.available_width();
-
-                        for (label, count) in &stats.hourly_histogram {
-                            ui.horizontal(|ui| {
-                                ui.label(
-                                    egui::RichText::new(label)
-                                        .color(theme::text_dim(dark))
-                                        .monospace()
-                                        .small(),
-                                );
-                                let bar_frac = *count as f32 / max_count as f32;
-                                let bar_width = (total_width - 80.0) * bar_frac;
-                                let (rect, _) = ui.allocate_exact_size(
-                                    egui::vec2(bar_width.max(2.0), bar_height),
-                                    egui::Sense::hover(),
-                                );
-                                ui.painter().rect_filled(rect, 2.0, theme::accent_dim(dark));
-                                ui.label(
-                                    egui::RichText::new(count.to_string())
-                                        .color(theme::text_secondary(dark))
-                                        .small(),
-                                );
-                            });
+                        if let Some((day, hour)) = render_heatmap(ui, &stats.week_hours, dark) {
+                            action = Some(StatsAction::WeekSlot(day, hour));
                         }
                     });
+
+                    ui.add_space(theme::SECTION_SPACING);
+
+                    // ── Breakdowns ──────────────────────────────────────
+                    if let Some(i) = breakdown_section(
+                        ui,
+                        "\u{1F3F7}\u{FE0F} Top Providers",
+                        &stats.top_providers,
+                        "No providers",
+                        dark,
+                    ) {
+                        let value = stats.top_providers[i].0.clone();
+                        action = Some(StatsAction::Pivot(vec![(PivotField::Provider, value)]));
+                    }
+                    if let Some(i) = breakdown_section(
+                        ui,
+                        "\u{1F4C2} Top Channels",
+                        &stats.top_channels,
+                        "No channels",
+                        dark,
+                    ) {
+                        let value = stats.top_channels[i].0.clone();
+                        action = Some(StatsAction::Pivot(vec![(PivotField::Channel, value)]));
+                    }
+                    if let Some(i) = breakdown_section(
+                        ui,
+                        "\u{1F5A5} Top Computers",
+                        &stats.top_computers,
+                        "No computers",
+                        dark,
+                    ) {
+                        let value = stats.top_computers[i].0.clone();
+                        action = Some(StatsAction::Pivot(vec![(PivotField::Computer, value)]));
+                    }
+                    let id_rows: Vec<(String, usize)> = stats
+                        .top_event_ids
+                        .iter()
+                        .map(|e| (format!("{} \u{2014} {}", e.event_id, e.provider), e.count))
+                        .collect();
+                    if let Some(i) = breakdown_section(
+                        ui,
+                        "\u{1F522} Top Event IDs",
+                        &id_rows,
+                        "No events",
+                        dark,
+                    ) {
+                        let e = &stats.top_event_ids[i];
+                        action = Some(StatsAction::Pivot(vec![
+                            (PivotField::EventId, e.event_id.to_string()),
+                            (PivotField::Provider, e.provider.clone()),
+                        ]));
+                    }
+                    if let Some(i) = breakdown_section(
+                        ui,
+                        "\u{1F464} Top Users",
+                        &stats.top_users,
+                        "No events carry a user SID",
+                        dark,
+                    ) {
+                        let value = stats.top_users[i].0.clone();
+                        action = Some(StatsAction::Pivot(vec![(PivotField::UserSid, value)]));
+                    }
                 }); // ScrollArea
             });
 
+        if granularity != self.stats_granularity || utc != self.stats_utc {
+            self.stats_granularity = granularity;
+            self.stats_utc = utc;
+            self.stats_dirty = true;
+        }
+        match action {
+            Some(StatsAction::Pivot(pivots)) => {
+                for (field, value) in pivots {
+                    self.apply_pivot_request(PivotRequest {
+                        field,
+                        value,
+                        action: PivotAction::FilterTo,
+                        at: chrono::Utc::now(),
+                    });
+                }
+            }
+            Some(StatsAction::TimeWindow(from, to)) => self.apply_time_window(from, to),
+            Some(StatsAction::WeekSlot(day, hour)) => {
+                let indices = self.week_slot_events(day, hour);
+                let zone = if self.stats_utc { "UTC" } else { "local" };
+                let label = format!(
+                    "{} {hour:02}:00\u{2013}{hour:02}:59 ({zone})",
+                    stats::WEEKDAYS[day]
+                );
+                self.set_event_index_filter(label, &indices);
+            }
+            None => {}
+        }
+        if open_anomalies {
+            self.show_anomalies = true;
+        }
         if !open {
             self.show_stats = false;
         }
     }
 }
+
+/// Draw the stacked-level histogram; returns the start of the clicked
+/// bucket.
+fn render_histogram(
+    ui: &mut egui::Ui,
+    stats: &stats::EventStats,
+    dark: bool,
+) -> Option<chrono::NaiveDateTime> {
+    let buckets = &stats.histogram;
+    if buckets.is_empty() {
+        ui.label(
+            egui::RichText::new("Insufficient data")
+                .color(theme::text_dim(dark))
+                .italics(),
+        );
+        return None;
+    }
+    let max_count = buckets.iter().map(|b| b.total()).max().unwrap_or(1).max(1);
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), HISTOGRAM_HEIGHT),
+        egui::Sense::click(),
+    );
+    let painter = ui.painter_at(rect);
+    let bar_w = rect.width() / buckets.len() as f32;
+    let gap = if bar_w > 4.0 { 1.0 } else { 0.0 };
+    let hovered = response
+        .hover_pos()
+        .map(|p| (((p.x - rect.left()) / bar_w) as usize).min(buckets.len() - 1));
+
+    for (i, bucket) in buckets.iter().enumerate() {
+        let x = rect.left() + i as f32 * bar_w;
+        if hovered == Some(i) {
+            painter.rect_filled(
+                egui::Rect::from_x_y_ranges(x..=x + bar_w, rect.y_range()),
+                0.0,
+                theme::accent(dark).gamma_multiply(0.12),
+            );
+        }
+        let mut bottom = rect.bottom();
+        for level in STACK_ORDER {
+            let count = bucket.levels[level];
+            if count == 0 {
+                continue;
+            }
+            let h = rect.height() * count as f32 / max_count as f32;
+            painter.rect_filled(
+                egui::Rect::from_x_y_ranges(x + gap..=x + bar_w - gap, bottom - h..=bottom),
+                0.0,
+                theme::level_color(level as u8, dark),
+            );
+            bottom -= h;
+        }
+    }
+
+    let fmt = stats.granularity.label_format();
+    ui.horizontal(|ui| {
+        let small = |t: String| {
+            egui::RichText::new(t)
+                .color(theme::text_dim(dark))
+                .monospace()
+                .small()
+        };
+        ui.label(small(buckets[0].start.format(fmt).to_string()));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(small(
+                buckets[buckets.len() - 1].start.format(fmt).to_string(),
+            ));
+        });
+    });
+
+    let i = hovered?;
+    let bucket = &buckets[i];
+    let response = response
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+        .on_hover_ui_at_pointer(|ui| {
+            ui.label(egui::RichText::new(bucket.start.format(fmt).to_string()).strong());
+            ui.label(format!("{} events", bucket.total()));
+            for level in STACK_ORDER {
+                let count = bucket.levels[level];
+                if count > 0 {
+                    ui.label(
+                        egui::RichText::new(format!("{}: {count}", LEVEL_NAMES[level]))
+                            .color(theme::level_color(level as u8, dark)),
+                    );
+                }
+            }
+            ui.label(
+                egui::RichText::new("Click to filter to this interval")
+                    .color(theme::text_dim(dark))
+                    .small(),
+            );
+        });
+    response.clicked().then_some(bucket.start)
+}
+
+/// Draw the 7 × 24 weekday / hour heatmap; returns the clicked
+/// `(weekday, hour)` slot.
+fn render_heatmap(
+    ui: &mut egui::Ui,
+    counts: &[[usize; 24]; 7],
+    dark: bool,
+) -> Option<(usize, usize)> {
+    let max_count = counts.iter().flatten().copied().max().unwrap_or(0).max(1);
+    let label_h = 12.0;
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), label_h + 7.0 * HEATMAP_ROW_HEIGHT),
+        egui::Sense::click(),
+    );
+    let painter = ui.painter_at(rect);
+    let grid = egui::Rect::from_min_max(
+        rect.min + egui::vec2(HEATMAP_LABEL_WIDTH, label_h),
+        rect.max,
+    );
+    let cell_w = grid.width() / 24.0;
+    let cell_rect = |day: usize, hour: usize| {
+        egui::Rect::from_min_size(
+            egui::pos2(
+                grid.left() + hour as f32 * cell_w,
+                grid.top() + day as f32 * HEATMAP_ROW_HEIGHT,
+            ),
+            egui::vec2(cell_w, HEATMAP_ROW_HEIGHT),
+        )
+    };
+    let small = egui::FontId::monospace(9.0);
+
+    for hour in (0..24).step_by(3) {
+        painter.text(
+            egui::pos2(grid.left() + hour as f32 * cell_w, rect.top()),
+            egui::Align2::LEFT_TOP,
+            format!("{hour:02}"),
+            small.clone(),
+            theme::text_dim(dark),
+        );
+    }
+    let empty = if dark {
+        egui::Color32::from_rgb(40, 40, 52)
+    } else {
+        egui::Color32::from_rgb(232, 232, 238)
+    };
+    for (day, row) in counts.iter().enumerate() {
+        painter.text(
+            egui::pos2(rect.left(), cell_rect(day, 0).center().y),
+            egui::Align2::LEFT_CENTER,
+            stats::WEEKDAYS[day],
+            small.clone(),
+            theme::text_secondary(dark),
+        );
+        for (hour, &count) in row.iter().enumerate() {
+            let color = if count == 0 {
+                empty
+            } else {
+                theme::accent(dark).gamma_multiply(0.15 + 0.85 * count as f32 / max_count as f32)
+            };
+            painter.rect_filled(cell_rect(day, hour).shrink(0.5), 1.0, color);
+        }
+    }
+
+    let pos = response.hover_pos().filter(|p| grid.contains(*p))?;
+    let day = (((pos.y - grid.top()) / HEATMAP_ROW_HEIGHT) as usize).min(6);
+    let hour = (((pos.x - grid.left()) / cell_w) as usize).min(23);
+    let count = counts[day][hour];
+    painter.rect_stroke(
+        cell_rect(day, hour),
+        1.0,
+        egui::Stroke::new(1.0, theme::text_primary(dark)),
+        egui::StrokeKind::Inside,
+    );
+    let response = response
+        .on_hover_cursor(egui::CursorIcon::PointingHand)
+        .on_hover_text_at_pointer(format!(
+            "{} {hour:02}:00\u{2013}{hour:02}:59: {count} events\nClick to filter to this slot",
+            stats::WEEKDAYS[day]
+        ));
+    (response.clicked() && count > 0).then_some((day, hour))
+}
@@ -0,0 +1,174 @@
+//! "Suggest filter" dialog for query-by-example.
+//!
+//! Builds a [`FilterSuggestion`] from the pinned events (plus the selected
+//! event) and lets the user toggle and edit each part before applying it
+//! to the active filter or saving it as a preset.
+
+use crate::app::EventSleuthApp;
+use crate::core::filter_preset::{CompoundPreset, FilterPreset};
+use crate::core::query_by_example::{suggest_filter, FilterSuggestion};
+use crate::ui::theme;
+
+impl EventSleuthApp {
+    /// Open the suggestion dialog using every pinned event plus the
+    /// selected event as examples. Does nothing when there are none.
+    pub fn open_filter_suggestion(&mut self) {
+        let mut indices: Vec<usize> = self.bookmarked_indices.iter().copied().collect();
+        if let Some(&sel) = self
+            .selected_event_idx
+            .and_then(|v| self.filtered_indices.get(v))
+        {
+            if !indices.contains(&sel) {
+                indices.push(sel);
+            }
+        }
+        let examples: Vec<_> = indices
+            .iter()
+            .filter_map(|&i| self.all_events.get(i))
+            .collect();
+        self.filter_suggestion = suggest_filter(&examples);
+        self.suggestion_preset_name.clear();
+    }
+
+    /// Render the suggestion dialog while `filter_suggestion` is set.
+    pub fn render_filter_suggestion_dialog(&mut self, ctx: &egui::Context) {
+        let Some(mut suggestion) = self.filter_suggestion.take() else {
+            return;
+        };
+        let dark = self.dark_mode;
+        let mut open = true;
+        let mut apply = false;
+        let mut save = false;
+
+        egui::Window::new("\u{2728} Suggested Filter")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(380.0)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Built from {} example event(s). Untick anything too specific.",
+                        suggestion.example_count
+                    ))
+                    .color(theme::text_secondary(dark))
+                    .small(),
+                );
+                ui.add_space(theme::ITEM_SPACING);
+                Self::render_suggestion_fields(ui, &mut suggestion, dark);
+                ui.add_space(theme::ITEM_SPACING);
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("\u{2714} Apply")
+                        .on_hover_text("Replace the filter being edited with this suggestion")
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    ui.separator();
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.suggestion_preset_name)
+                            .hint_text("Preset name")
+                            .desired_width(120.0),
+                    );
+                    let name_valid = !self.suggestion_preset_name.trim().is_empty();
+                    if ui
+                        .add_enabled(name_valid, egui::Button::new("\u{1F4BE} Save preset"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                });
+            });
+
+        if save {
+            let name = self.suggestion_preset_name.trim().to_owned();
+            let preset: CompoundPreset =
+                FilterPreset::from_state(&name, &suggestion.to_filter_state()).into();
+            if let Some(existing) = self.filter_presets.iter_mut().find(|p| p.name == name) {
+                *existing = preset;
+                // Alert rules on this preset now match something else.
+                self.alert_monitor.reset();
+            } else {
+                self.filter_presets.push(preset);
+            }
+            self.suggestion_preset_name.clear();
+            tracing::info!("Saved suggested filter as preset: {}", name);
+        }
+        if apply {
+            let state = suggestion.to_filter_state();
+            match self.editing_group {
+                Some(i) if i < self.filter_groups.len() => self.filter_groups[i].state = state,
+                _ => self.filter = state,
+            }
+            self.needs_refilter = true;
+            return;
+        }
+        if open {
+            self.filter_suggestion = Some(suggestion);
+        }
+    }
+
+    /// Editable criteria rows of the suggestion dialog.
+    fn render_suggestion_fields(ui: &mut egui::Ui, s: &mut FilterSuggestion, dark: bool) {
+        egui::Grid::new("suggestion_grid")
+            .num_columns(2)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                ui.checkbox(&mut s.use_event_ids, "Event IDs");
+                ui.add(egui::TextEdit::singleline(&mut s.event_id_input).desired_width(220.0));
+                ui.end_row();
+
+                ui.checkbox(&mut s.use_provider, "Provider");
+                ui.add(egui::TextEdit::singleline(&mut s.provider).desired_width(220.0));
+                ui.end_row();
+
+                ui.checkbox(&mut s.use_levels, "Levels");
+                ui.horizontal(|ui| {
+                    for (i, name) in ["Always", "Crit", "Error", "Warn", "Info", "Verbose"]
+                        .iter()
+                        .enumerate()
+                    {
+                        ui.checkbox(
+                            &mut s.levels[i],
+                            egui::RichText::new(*name).color(theme::level_color(i as u8, dark)),
+                        );
+                    }
+                });
+                ui.end_row();
+
+                ui.checkbox(&mut s.use_time_span, "Time span");
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut s.time_from_input).desired_width(130.0));
+                    ui.label("\u{2013}");
+                    ui.add(egui::TextEdit::singleline(&mut s.time_to_input).desired_width(130.0));
+                });
+                ui.end_row();
+            });
+
+        ui.add_space(theme::ITEM_SPACING);
+        if s.common_data.is_empty() {
+            ui.label(
+                egui::RichText::new("No EventData values shared by all examples")
+                    .color(theme::text_dim(dark))
+                    .italics(),
+            );
+        } else {
+            ui.label(
+                egui::RichText::new("Shared EventData values")
+                    .color(theme::text_secondary(dark))
+                    .strong(),
+            );
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (key, value, on) in &mut s.common_data {
+                        ui.checkbox(on, format!("{key} = {value}"));
+                    }
+                });
+        }
+    }
+}
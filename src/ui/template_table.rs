@@ -0,0 +1,268 @@
+//! Grouped table mode: one row per message template.
+//!
+//! Replaces the event table while enabled. Each template row shows the
+//! number of filtered events it covers, first / last seen and the
+//! template text with its variable slots; expanding a row lists its
+//! events, which select into the detail panel like normal rows.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+
+use crate::app::EventSleuthApp;
+use crate::core::template::{TemplateMiner, WILDCARD};
+use crate::ui::theme;
+use crate::util::time::format_table_timestamp;
+use egui_extras::{Column, TableBuilder};
+
+/// Template mining state and the grouped mode's view options.
+#[derive(Debug, Default)]
+pub struct TemplateView {
+    /// Show one row per template instead of one per event.
+    pub enabled: bool,
+    pub miner: TemplateMiner,
+    /// `events_epoch` the miner was fed from; appends within an epoch are
+    /// mined incrementally.
+    pub built_for: Option<u64>,
+    /// Groups over `filtered_indices`, largest first.
+    groups: Vec<TemplateGroup>,
+    /// Set when `filtered_indices` changed; groups rebuild on next render.
+    pub groups_dirty: bool,
+    /// Expanded template IDs.
+    expanded: HashSet<usize>,
+}
+
+/// The filtered events sharing one template.
+#[derive(Debug)]
+struct TemplateGroup {
+    template: usize,
+    /// Positions in `filtered_indices`, in table order.
+    rows: Vec<usize>,
+    first: DateTime<Utc>,
+    last: DateTime<Utc>,
+}
+
+/// One line of the grouped table.
+#[derive(Clone, Copy)]
+enum GroupRow {
+    /// Index into `groups`.
+    Template(usize),
+    /// Position in `filtered_indices`.
+    Event(usize),
+}
+
+impl EventSleuthApp {
+    /// Feed new events to the template miner, starting over when the
+    /// event list was replaced or its oldest events evicted.
+    pub fn refresh_templates(&mut self) {
+        let view = &mut self.template_view;
+        if view.built_for != Some(self.events_epoch) {
+            view.miner = TemplateMiner::default();
+            view.built_for = Some(self.events_epoch);
+            view.expanded.clear();
+            view.groups_dirty = true;
+        }
+        if view.miner.assignments.len() < self.all_events.len() {
+            view.miner.extend(&self.all_events);
+            view.groups_dirty = true;
+        }
+        if !view.groups_dirty {
+            return;
+        }
+        let mut by_template: HashMap<usize, TemplateGroup> = HashMap::new();
+        for (row, &idx) in self.filtered_indices.iter().enumerate() {
+            let Some(template) = view.miner.template_of(idx) else {
+                continue;
+            };
+            let ts = self.all_events[idx].timestamp;
+            let group = by_template.entry(template).or_insert(TemplateGroup {
+                template,
+                rows: Vec::new(),
+                first: ts,
+                last: ts,
+            });
+            group.rows.push(row);
+            group.first = group.first.min(ts);
+            group.last = group.last.max(ts);
+        }
+        view.groups = by_template.into_values().collect();
+        view.groups.sort_by(|a, b| {
+            b.rows
+                .len()
+                .cmp(&a.rows.len())
+                .then(a.template.cmp(&b.template))
+        });
+        view.groups_dirty = false;
+    }
+
+    /// Render the grouped-by-template table in place of the event table.
+    pub fn render_template_table(&mut self, ui: &mut egui::Ui) {
+        self.refresh_templates();
+
+        let dark = self.dark_mode;
+        let view = &self.template_view;
+        let mut rows: Vec<GroupRow> = Vec::with_capacity(view.groups.len());
+        for (g, group) in view.groups.iter().enumerate() {
+            rows.push(GroupRow::Template(g));
+            if view.expanded.contains(&group.template) {
+                rows.extend(group.rows.iter().map(|&r| GroupRow::Event(r)));
+            }
+        }
+
+        let mut toggle: Option<usize> = None;
+        let mut select: Option<usize> = None;
+        let mut only_template: Option<usize> = None;
+
+        ui.label(
+            egui::RichText::new(format!(
+                "{} templates across {} events",
+                view.groups.len(),
+                self.filtered_indices.len()
+            ))
+            .color(theme::text_dim(dark))
+            .small(),
+        );
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::auto().at_least(24.0)) // Expander
+            .column(Column::auto().at_least(70.0)) // Count / level
+            .column(Column::auto().at_least(145.0).clip(true)) // First seen / time
+            .column(Column::auto().at_least(145.0).clip(true)) // Last seen / provider
+            .column(Column::remainder().clip(true)) // Template / message
+            .sense(egui::Sense::click())
+            .header(22.0, |mut header| {
+                for title in ["", "Count", "First seen", "Last seen", "Template"] {
+                    header.col(|ui| {
+                        ui.label(egui::RichText::new(title).color(theme::text_primary(dark)));
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(theme::TABLE_ROW_HEIGHT, rows.len(), |mut row| {
+                    match rows[row.index()] {
+                        GroupRow::Template(g) => {
+                            let group = &view.groups[g];
+                            let template = &view.miner.templates[group.template];
+                            let open = view.expanded.contains(&group.template);
+                            row.col(|ui| {
+                                ui.label(if open { "\u{25BC}" } else { "\u{25B6}" });
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(group.rows.len().to_string())
+                                        .color(theme::accent(dark))
+                                        .strong(),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format_table_timestamp(&group.first))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format_table_timestamp(&group.last))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                            });
+                            row.col(|ui| {
+                                let mut job = egui::text::LayoutJob::default();
+                                let font = egui::TextStyle::Body.resolve(ui.style());
+                                for (i, token) in template.tokens.iter().enumerate() {
+                                    if i > 0 {
+                                        job.append(" ", 0.0, egui::TextFormat::default());
+                                    }
+                                    let color = if token == WILDCARD {
+                                        theme::level_color(3, dark)
+                                    } else {
+                                        theme::text_primary(dark)
+                                    };
+                                    job.append(
+                                        token,
+                                        0.0,
+                                        egui::TextFormat::simple(font.clone(), color),
+                                    );
+                                }
+                                ui.label(job);
+                            });
+                            let response = row.response();
+                            if response.clicked() {
+                                toggle = Some(group.template);
+                            }
+                            response.context_menu(|ui| {
+                                if ui.button("Show only this template").clicked() {
+                                    only_template = Some(group.template);
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                        GroupRow::Event(visible) => {
+                            let event = &self.all_events[self.filtered_indices[visible]];
+                            row.set_selected(self.selected_event_idx == Some(visible));
+                            row.col(|_| {});
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(&event.level_name)
+                                        .color(theme::level_color(event.level, dark)),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format_table_timestamp(&event.timestamp))
+                                        .color(theme::text_secondary(dark))
+                                        .small(),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "{} / {}",
+                                        event.provider_name, event.event_id
+                                    ))
+                                    .color(theme::text_secondary(dark)),
+                                );
+                            });
+                            row.col(|ui| {
+                                let msg = event.display_message();
+                                let line = msg.lines().next().unwrap_or_default();
+                                ui.label(egui::RichText::new(line).color(theme::text_dim(dark)));
+                            });
+                            if row.response().clicked() {
+                                select = Some(visible);
+                            }
+                        }
+                    }
+                });
+            });
+
+        if let Some(t) = toggle {
+            let expanded = &mut self.template_view.expanded;
+            if !expanded.remove(&t) {
+                expanded.insert(t);
+            }
+        }
+        if select.is_some() {
+            self.selected_event_idx = select;
+        }
+        if let Some(t) = only_template {
+            let miner = &self.template_view.miner;
+            let indices: Vec<usize> = (0..miner.assignments.len())
+                .filter(|&i| miner.assignments[i] == t)
+                .collect();
+            let text = miner.templates[t].text();
+            let short: String = text.chars().take(60).collect();
+            let label = if short.len() < text.len() {
+                format!("Template T{t}: {short}\u{2026}")
+            } else {
+                format!("Template T{t}: {text}")
+            };
+            self.set_event_index_filter(label, &indices);
+        }
+    }
+}
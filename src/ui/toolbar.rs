@@ -1,6 +1,7 @@
 //! Top toolbar: channel selector, refresh, and export buttons.
 
 use crate::app::EventSleuthApp;
+use crate::core::sigma::SigmaLevel;
 use crate::ui::theme;
 
 impl EventSleuthApp {
@@ -117,7 +118,7 @@ impl EventSleuthApp {
             }
 
             // ── Active-filter count badge ───────────────────────────
-            let active_count = self.filter.active_count();
+            let active_count = self.active_filter_count();
             if active_count > 0 {
                 ui.add_space(2.0);
                 theme::badge(
@@ -156,6 +157,182 @@ impl EventSleuthApp {
                 }
             }
 
+            // ── Detections button ───────────────────────────────────
+            let det_btn = egui::Button::new(egui::RichText::new("\u{1F6E1} Detections").color(
+                if self.show_detections {
+                    theme::accent(self.dark_mode)
+                } else {
+                    theme::text_primary(self.dark_mode)
+                },
+            ));
+            if ui
+                .add(det_btn)
+                .on_hover_text("Built-in and Sigma rule findings")
+                .clicked()
+            {
+                self.show_detections = !self.show_detections;
+            }
+            let hit_count = self.sigma_hit_count();
+            if hit_count > 0 {
+                theme::badge(
+                    ui,
+                    hit_count,
+                    theme::level_color(2, self.dark_mode),
+                    egui::Color32::WHITE,
+                );
+            }
+            // Built-in pack findings, most severe first.
+            let counts = self.builtin_level_counts();
+            for level in [
+                SigmaLevel::Critical,
+                SigmaLevel::High,
+                SigmaLevel::Medium,
+                SigmaLevel::Low,
+            ] {
+                let n = counts[level as usize];
+                if n > 0 {
+                    let label = ui.label(
+                        egui::RichText::new(format!("{n} {}", level.label().to_lowercase()))
+                            .color(theme::level_color(level.event_level(), self.dark_mode))
+                            .small()
+                            .strong(),
+                    );
+                    if label
+                        .on_hover_text("Built-in detection findings (click to open Detections)")
+                        .interact(egui::Sense::click())
+                        .clicked()
+                    {
+                        self.show_detections = true;
+                    }
+                }
+            }
+
+            // ── Investigation views ─────────────────────────────────
+            ui.menu_button("\u{1F52C} Investigate", |ui| {
+                if ui
+                    .checkbox(&mut self.show_logon_sessions, "\u{1F464} Logon sessions")
+                    .on_hover_text("Sessions reconstructed from Security logon/logoff events")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_brute_force, "\u{1F528} Failed logons")
+                    .on_hover_text("Brute-force bursts and password sprays from 4625 / 4771 / 4776")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_process_tree, "\u{1F333} Process tree")
+                    .on_hover_text("Parent/child processes from 4688 and Sysmon events")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_activity, "\u{1F517} Activity chains")
+                    .on_hover_text("Events grouped by ActivityID / RelatedActivityID")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_boot_sessions, "\u{1F50C} Boot sessions")
+                    .on_hover_text("Boot-to-shutdown spans and unexpected shutdowns")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_crashes, "\u{1F4A5} App crashes")
+                    .on_hover_text("Application crashes and hangs by app and faulting module")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_services, "\u{2699} Services")
+                    .on_hover_text("Service lifecycle timeline and new service installations")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_anomalies, "\u{26A1} Anomalies")
+                    .on_hover_text("Volume spikes / drops and rare events")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_crosstab, "\u{1F9EE} Pivot table")
+                    .on_hover_text("Event counts across two fields, exportable to CSV")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.show_field_explorer, "\u{1F9EC} Field explorer")
+                    .on_hover_text("EventData keys with coverage and most / least frequent values")
+                    .clicked()
+                {
+                    ui.close_menu();
+                }
+            });
+
+            // ── IOC button ──────────────────────────────────────────
+            let ioc_btn = egui::Button::new(egui::RichText::new("\u{1F3AF} IOCs").color(
+                if self.show_iocs {
+                    theme::accent(self.dark_mode)
+                } else {
+                    theme::text_primary(self.dark_mode)
+                },
+            ));
+            if ui
+                .add(ioc_btn)
+                .on_hover_text("Import indicator lists and review matching events")
+                .clicked()
+            {
+                self.show_iocs = !self.show_iocs;
+            }
+            let ioc_events = self.ioc_scanner.hit_event_count();
+            if ioc_events > 0 {
+                theme::badge(
+                    ui,
+                    ioc_events,
+                    theme::level_color(2, self.dark_mode),
+                    egui::Color32::WHITE,
+                );
+            }
+
+            // ── Alerts button ───────────────────────────────────────
+            let alert_btn = egui::Button::new(egui::RichText::new("\u{1F514} Alerts").color(
+                if self.show_alerts {
+                    theme::accent(self.dark_mode)
+                } else {
+                    theme::text_primary(self.dark_mode)
+                },
+            ));
+            if ui
+                .add(alert_btn)
+                .on_hover_text("Live-tail alert rules and recent alerts")
+                .clicked()
+            {
+                self.show_alerts = !self.show_alerts;
+                if self.show_alerts {
+                    self.unseen_alerts = 0;
+                }
+            }
+            if self.unseen_alerts > 0 {
+                theme::badge(
+                    ui,
+                    self.unseen_alerts,
+                    theme::level_color(2, self.dark_mode),
+                    egui::Color32::WHITE,
+                );
+            }
+
             // ── Column visibility dropdown ──────────────────────────
             ui.menu_button("\u{1F4CB} Columns", |ui| {
                 ui.label(
@@ -172,6 +349,40 @@ impl EventSleuthApp {
                 ui.checkbox(&mut self.column_visibility.computer, "Computer");
                 ui.checkbox(&mut self.column_visibility.message, "Message");
                 ui.separator();
+                ui.checkbox(&mut self.template_view.enabled, "Group by message template")
+                    .on_hover_text("One row per message template, expandable to its events");
+                let dc = &mut self.duplicate_collapse;
+                let mut changed = ui
+                    .checkbox(&mut dc.enabled, "Collapse consecutive duplicates")
+                    .on_hover_text("Fold runs of identical neighbouring events into one row")
+                    .changed();
+                ui.add_enabled_ui(dc.enabled, |ui| {
+                    ui.indent("duplicate_key", |ui| {
+                        ui.label(
+                            egui::RichText::new("Compare")
+                                .color(theme::text_dim(self.dark_mode))
+                                .small(),
+                        );
+                        // The last compared field can't be unchecked.
+                        let last = dc.key.compared_fields() == 1;
+                        let key = &mut dc.key;
+                        for (field, label) in [
+                            (&mut key.provider, "Provider"),
+                            (&mut key.event_id, "Event ID"),
+                            (&mut key.message, "Message"),
+                            (&mut key.event_data, "EventData"),
+                        ] {
+                            let enabled = !(last && *field);
+                            changed |= ui
+                                .add_enabled(enabled, egui::Checkbox::new(field, label))
+                                .changed();
+                        }
+                    });
+                });
+                if changed {
+                    dc.dirty = true;
+                }
+                ui.separator();
                 if ui.small_button("Reset to defaults").clicked() {
                     self.column_visibility = crate::app::ColumnVisibility::default();
                     ui.close_menu();
@@ -233,6 +444,8 @@ impl EventSleuthApp {
                         ("Page Up / Down", "Jump 20 events"),
                         ("Home / End", "First / last event"),
                         ("Ctrl+Shift+X", "Clear all filters"),
+                        ("Ctrl+Z", "Undo filter change"),
+                        ("Ctrl+Y / Ctrl+Shift+Z", "Redo filter change"),
                     ];
                     egui::Grid::new("shortcuts_grid")
                         .num_columns(2)
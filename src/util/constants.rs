@@ -61,6 +61,10 @@ pub const EVT_FORMAT_BUFFER_SIZE: usize = 2_048;
 /// Prevents excessive re-filtering while the user is still typing.
 pub const FILTER_DEBOUNCE_MS: u64 = 150;
 
+/// Default half-width, in minutes, of the "same value within ±N minutes"
+/// pivot window.
+pub const DEFAULT_PIVOT_WINDOW_MINUTES: i64 = 5;
+
 /// Interval between live-tail refresh queries (seconds).
 pub const LIVE_TAIL_INTERVAL_SECS: u64 = 5;
 
@@ -88,6 +92,79 @@ pub const LOG_DIR: &str = "logs";
 /// Log file name for persistent error/debug logging.
 pub const LOG_FILE_NAME: &str = "eventsleuth.log";
 
+/// Alert log file name (in the log directory), appended to by live-tail
+/// alert rules.
+pub const ALERT_LOG_FILE_NAME: &str = "alerts.log";
+
+/// Maximum number of fired alerts kept for the Alerts window.
+pub const MAX_FIRED_ALERTS: usize = 200;
+
+/// Fired alerts that may wait for the dispatch worker (log line and
+/// command) before further ones are dropped.
+pub const MAX_PENDING_ALERT_DISPATCHES: usize = 64;
+
+/// Maximum number of indicators loaded for IOC scanning.
+pub const MAX_IOC_INDICATORS: usize = 500_000;
+
+/// Maximum number of logon sessions kept by session reconstruction
+/// (newest first).
+pub const MAX_LOGON_SESSIONS: usize = 50_000;
+
+/// Maximum number of process nodes kept by process tree reconstruction.
+pub const MAX_PROCESS_NODES: usize = 200_000;
+
+/// Maximum number of ActivityID chains kept by correlation grouping
+/// (newest first).
+pub const MAX_ACTIVITY_CHAINS: usize = 50_000;
+
+/// Maximum number of boot sessions kept by boot segmentation (newest
+/// first).
+pub const MAX_BOOT_SESSIONS: usize = 10_000;
+
+/// Boot markers (Kernel-General 12, EventLog 6005 / 6009) within this many
+/// seconds of a session's first one belong to the same boot.
+pub const BOOT_MARKER_WINDOW_SECS: i64 = 600;
+
+/// Maximum number of time buckets anomaly detection splits the loaded
+/// window into; the bucket width grows to stay under it.
+pub const MAX_ANOMALY_BUCKETS: usize = 200;
+
+/// Maximum number of volume anomalies and rare events reported each.
+pub const MAX_ANOMALIES: usize = 1_000;
+
+/// Maximum number of message templates mined; later messages that fit no
+/// template share a catch-all one.
+pub const MAX_TEMPLATES: usize = 10_000;
+
+/// Message tokens considered by template mining; the rest of a longer
+/// message collapses into one trailing slot.
+pub const MAX_TEMPLATE_TOKENS: usize = 64;
+
+/// Entries shown in each statistics breakdown (providers, channels, ...).
+pub const MAX_STATS_TOP_N: usize = 10;
+
+/// Maximum rows and columns of a pivot table; rarer values share an
+/// "(other)" row / column.
+pub const MAX_CROSSTAB_ROWS: usize = 1_000;
+pub const MAX_CROSSTAB_COLUMNS: usize = 50;
+
+/// Distinct values tracked per EventData key by the field explorer.
+pub const MAX_FIELD_DISTINCT_VALUES: usize = 100_000;
+
+/// Most / least frequent values listed per key in the field explorer.
+pub const MAX_FIELD_VALUES_SHOWN: usize = 15;
+
+/// Maximum number of brute-force / password-spray findings reported
+/// (newest first).
+pub const MAX_BRUTE_FORCE_FINDINGS: usize = 5_000;
+
+/// Unexpected terminations (7031 / 7034) of one service within
+/// [`SERVICE_CRASH_LOOP_WINDOW_SECS`] that make a crash / restart loop.
+pub const SERVICE_CRASH_LOOP_COUNT: usize = 3;
+
+/// Window in seconds for [`SERVICE_CRASH_LOOP_COUNT`].
+pub const SERVICE_CRASH_LOOP_WINDOW_SECS: i64 = 3600;
+
 /// Maximum log file size in bytes before rotation (5 MB).
 pub const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
 
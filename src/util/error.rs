@@ -36,6 +36,15 @@ pub enum EventSleuthError {
     #[allow(dead_code)]
     FilterParse(String),
 
+    /// A Sigma detection rule could not be parsed or uses an unsupported
+    /// feature.
+    #[error("Sigma rule error: {0}")]
+    SigmaRule(String),
+
+    /// An indicator (IOC) list could not be parsed.
+    #[error("IOC import error: {0}")]
+    IocImport(String),
+
     /// Catch-all for I/O errors (file writes, etc.).
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
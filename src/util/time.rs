@@ -38,6 +38,23 @@ pub fn format_duration(d: std::time::Duration) -> String {
     }
 }
 
+/// Format the span between two events compactly, to the two largest
+/// units: `45s`, `12m 05s`, `3h 20m`, `2d 04h`. Negative spans show as
+/// `0s`.
+pub fn format_span(d: chrono::Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    let (days, hours, mins) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours:02}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins:02}m")
+    } else if mins > 0 {
+        format!("{mins}m {:02}s", secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
 /// Parse a date-time string from user input into a UTC `DateTime`.
 ///
 /// Accepts several common formats:
@@ -51,6 +51,13 @@ fn channel_enum_error_displays() {
     );
 }
 
+#[test]
+fn sigma_rule_error_preserves_message() {
+    let err = EventSleuthError::SigmaRule("unsupported modifier 'cidr'".into());
+    let msg = err.to_string();
+    assert!(msg.contains("cidr"), "Should contain detail: {msg}");
+}
+
 #[test]
 fn error_is_send_and_sync() {
     fn assert_send_sync<T: Send + Sync>() {}
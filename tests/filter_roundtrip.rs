@@ -1,7 +1,8 @@
 //! Integration tests for filter logic roundtrip.
 
 use eventsleuth::core::filter::FilterState;
-use eventsleuth::core::filter_preset::FilterPreset;
+use eventsleuth::core::filter_group::{FilterGroup, GroupMode};
+use eventsleuth::core::filter_preset::{CompoundPreset, FilterPreset};
 
 #[test]
 fn default_filter_matches_everything() {
@@ -56,3 +57,63 @@ fn preset_serialization_roundtrip() {
     assert_eq!(state.text_search, "serde test");
     assert!(state.levels[1]); // Critical
 }
+
+#[test]
+fn legacy_preset_json_without_scope_fields_loads() {
+    use eventsleuth::core::filter::SearchScope;
+
+    // Preset JSON as written by versions before search scope existed.
+    let json = r#"{
+        "name": "old",
+        "event_id_input": "4625",
+        "levels": [true, true, true, true, true, true],
+        "provider_filter": "",
+        "text_search": "admin",
+        "time_from_input": "",
+        "time_to_input": "",
+        "case_sensitive": false,
+        "use_regex": false
+    }"#;
+    let preset: FilterPreset = serde_json::from_str(json).expect("legacy preset");
+    let state = preset.to_filter_state();
+    assert_eq!(state.search_scope, SearchScope::AllFields);
+    assert!(!state.whole_word);
+    assert!(state.include_ids.contains(&4625));
+}
+
+#[test]
+fn compound_preset_roundtrip_preserves_groups() {
+    let primary = FilterState {
+        event_id_input: "4625".to_string(),
+        ..Default::default()
+    };
+    let mut exclude = FilterGroup::new(GroupMode::Exclude);
+    exclude.state.provider_filter = "Noise".to_string();
+    exclude.enabled = false;
+
+    let preset = CompoundPreset::from_filters("compound", &primary, &[exclude]);
+    let json = serde_json::to_string(&preset).expect("serialize");
+    let restored: CompoundPreset = serde_json::from_str(&json).expect("deserialize");
+
+    let (p, groups) = restored.to_filters();
+    assert_eq!(p.event_id_input, "4625");
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].mode, GroupMode::Exclude);
+    assert!(!groups[0].enabled);
+    assert_eq!(groups[0].state.provider_filter, "Noise");
+}
+
+#[test]
+fn legacy_preset_converts_to_compound() {
+    let filter = FilterState {
+        text_search: "failed".to_string(),
+        ..Default::default()
+    };
+    let legacy = FilterPreset::from_state("old", &filter);
+    let compound = CompoundPreset::from(legacy);
+    assert_eq!(compound.name, "old");
+
+    let (p, groups) = compound.to_filters();
+    assert_eq!(p.text_search, "failed");
+    assert!(groups.is_empty());
+}
@@ -1,6 +1,8 @@
 //! Integration tests for time utilities.
 
-use eventsleuth::util::time::{format_duration, format_table_timestamp, parse_datetime_input};
+use eventsleuth::util::time::{
+    format_duration, format_span, format_table_timestamp, parse_datetime_input,
+};
 
 #[test]
 fn format_duration_sub_second() {
@@ -26,6 +28,16 @@ fn format_duration_minutes() {
     assert!(s.contains("1.5m"), "Expected 1.5m, got: {s}");
 }
 
+#[test]
+fn format_span_uses_two_largest_units() {
+    let span = chrono::Duration::seconds;
+    assert_eq!(format_span(span(45)), "45s");
+    assert_eq!(format_span(span(12 * 60 + 5)), "12m 05s");
+    assert_eq!(format_span(span(3 * 3600 + 20 * 60 + 7)), "3h 20m");
+    assert_eq!(format_span(span(2 * 86_400 + 4 * 3600)), "2d 04h");
+    assert_eq!(format_span(span(-5)), "0s");
+}
+
 #[test]
 fn format_table_timestamp_has_expected_format() {
     use chrono::TimeZone;